@@ -14,9 +14,9 @@ else
   MAX_CHUNK_INDEX=$((2-1)) # we have 2 chunks, since we have a total of 2^11-1 powers
 fi
 CURVE="bw6"
-SEED1=$(tr -dc 'A-F0-9' < /dev/urandom | head -c32)
+SEED1=$(tr -dc 'A-F0-9' < /dev/urandom | head -c64)
 echo $SEED1 > seed1
-SEED2=$(tr -dc 'A-F0-9' < /dev/urandom | head -c32)
+SEED2=$(tr -dc 'A-F0-9' < /dev/urandom | head -c64)
 echo $SEED2 > seed2
 
 function check_hash() {
@@ -25,8 +25,8 @@ function check_hash() {
 
 cargo $CARGO_VER build --release --bin phase1
 
-phase1_1="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode chunked --chunk-size $CHUNK_SIZE --power $POWER --seed seed1 --proving-system $PROVING_SYSTEM"
-phase1_2="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode chunked --chunk-size $CHUNK_SIZE --power $POWER --seed seed2 --proving-system $PROVING_SYSTEM"
+phase1_1="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode chunked --chunk-size $CHUNK_SIZE --power $POWER --seed-file seed1 --proving-system $PROVING_SYSTEM"
+phase1_2="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode chunked --chunk-size $CHUNK_SIZE --power $POWER --seed-file seed2 --proving-system $PROVING_SYSTEM"
 phase1_combine="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode chunked --chunk-size $CHUNK_SIZE --power $POWER --proving-system $PROVING_SYSTEM"
 phase1_full="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode full --power $POWER --proving-system $PROVING_SYSTEM"
 ####### Phase 1
@@ -62,7 +62,8 @@ for i in $(seq $(($MAX_CHUNK_INDEX/2 + 1)) $MAX_CHUNK_INDEX); do
 done
 
 echo "Aggregating..."
-$phase1_combine combine --response-list-fname response_list --combined-fname combined
+$phase1_combine combine --response-list-fname response_list --combined-fname combined --combined-hash-fname combined.hash
+$phase1_combine verify-final --combined-fname combined --final-hash $(xxd -p -c 64 combined.hash)
 echo "Apply beacon..."
 $phase1_full beacon --challenge-fname combined --response-fname response_beacon --beacon-hash 0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620
 echo "Verifying..."
@@ -98,7 +99,7 @@ for i in $(seq $(($MAX_CHUNK_INDEX/2 + 1)) $MAX_CHUNK_INDEX); do
   echo new_response_split_$i >> response_list_split
 done
 
-$phase1_combine combine --response-list-fname response_list_split --combined-fname combined_split
+$phase1_combine combine --response-list-fname response_list_split --combined-fname combined_split --combined-hash-fname combined_split.hash
 $phase1_full beacon --challenge-fname combined_split --challenge-hash-fname challenge_$i.hash --response-fname response_beacon_split --response-hash-fname response_$i.hash --beacon-hash 0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620
 $phase1_full verify-and-transform-pok-and-correctness --challenge-fname combined_split --challenge-hash-fname combined_split.verified.hash --response-fname response_beacon_split --response-hash-fname response_beacon_split.verified.hash --new-challenge-fname response_beacon_new_challenge_split --new-challenge-hash-fname response_beacon_new_challenge_split.verified.hash
 $phase1_full verify-and-transform-ratios --response-fname response_beacon_new_challenge_split
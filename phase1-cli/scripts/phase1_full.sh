@@ -6,7 +6,7 @@ PROVING_SYSTEM=$1
 POWER=10
 BATCH=64
 CURVE="bw6"
-SEED=`tr -dc 'A-F0-9' < /dev/urandom | head -c32`
+SEED=`tr -dc 'A-F0-9' < /dev/urandom | head -c64`
 echo $SEED > seed1
 
 function check_hash() {
@@ -15,7 +15,7 @@ function check_hash() {
 
 cargo build --release --bin phase1
 
-phase1="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode full --power $POWER --seed seed1 --proving-system $PROVING_SYSTEM"
+phase1="../../target/release/phase1 --curve-kind $CURVE --batch-size $BATCH --contribution-mode full --power $POWER --seed-file seed1 --proving-system $PROVING_SYSTEM"
 
 ####### Phase 1
 
@@ -0,0 +1,91 @@
+use phase1::Phase1Parameters;
+use setup_utils::{buffer_size, BatchDeserializer, BatchSerializer, CheckForCorrectness, UseCompression};
+
+use zexe_algebra::{AffineCurve, PairingEngine as Engine, Zero};
+
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Times `read_batch` against `read_batch_preallocated` over a buffer of `batch_size` random
+/// G1 and G2 points of the selected curve, with and without compression, and prints the
+/// throughput of each. This tells whether the paths which allocate a vector per batch, like
+/// combine, would gain from reading into preallocated vectors like verification does.
+pub fn bench_read<T: Engine>(
+    iterations: usize,
+    check_for_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!(
+        "Will read batches of {} elements {} times with each method",
+        parameters.batch_size, iterations
+    );
+    for compression in &[UseCompression::Yes, UseCompression::No] {
+        bench_read_curve::<T::G1Affine>("G1", iterations, *compression, check_for_correctness, parameters);
+        bench_read_curve::<T::G2Affine>("G2", iterations, *compression, check_for_correctness, parameters);
+    }
+}
+
+fn bench_read_curve<C: AffineCurve>(
+    group: &str,
+    iterations: usize,
+    compression: UseCompression,
+    check_for_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<impl Engine>,
+) {
+    let elements = random_points::<C>(parameters.batch_size);
+    let mut buffer = vec![0; elements.len() * buffer_size::<C>(compression)];
+    buffer
+        .write_batch(&elements, compression)
+        .expect("unable to write the random points");
+
+    let allocating = time(iterations, || {
+        let read = buffer
+            .read_batch::<C>(compression, check_for_correctness)
+            .expect("unable to read the batch");
+        assert_eq!(read.len(), elements.len());
+    });
+    let mut read = vec![C::zero(); elements.len()];
+    let preallocated = time(iterations, || {
+        buffer
+            .read_batch_preallocated(&mut read, compression, check_for_correctness)
+            .expect("unable to read the batch");
+    });
+
+    let throughput = |elapsed: Duration| {
+        let seconds = elapsed.as_secs_f64();
+        (
+            (iterations * elements.len()) as f64 / seconds,
+            (iterations * buffer.len()) as f64 / seconds / (1 << 20) as f64,
+        )
+    };
+    for (method, elapsed) in &[("read_batch", allocating), ("read_batch_preallocated", preallocated)] {
+        let (elements_per_second, megabytes_per_second) = throughput(*elapsed);
+        info!(
+            "{} {} ({}compressed): {:?} ({:.1} elements/s, {:.1} MiB/s)",
+            group,
+            method,
+            if compression == UseCompression::Yes { "" } else { "un" },
+            elapsed,
+            elements_per_second,
+            megabytes_per_second
+        );
+    }
+}
+
+fn time(iterations: usize, mut action: impl FnMut()) -> Duration {
+    let now = Instant::now();
+    for _ in 0..iterations {
+        action();
+    }
+    now.elapsed()
+}
+
+#[cfg(feature = "bench")]
+fn random_points<C: AffineCurve>(size: usize) -> Vec<C> {
+    phase1::helpers::testing::random_point_vec(size, &mut rand::thread_rng())
+}
+
+#[cfg(not(feature = "bench"))]
+fn random_points<C: AffineCurve>(_size: usize) -> Vec<C> {
+    panic!("phase1 was built without the bench feature, rebuild it with `--features bench`");
+}
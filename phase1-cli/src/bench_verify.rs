@@ -0,0 +1,129 @@
+use phase1::{Phase1, Phase1Parameters, ProvingSystem};
+use setup_utils::{
+    blank_hash, derive_rng_from_seed, BatchExpMode, CheckForCorrectness, SubgroupCheckMode, UseCompression,
+};
+
+use zexe_algebra::{Bls12_377, Bls12_381, PairingEngine as Engine, BW6_761};
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    time::Duration,
+};
+use tracing::info;
+
+const COMPRESSED_INPUT: UseCompression = UseCompression::No;
+const COMPRESSED_OUTPUT: UseCompression = UseCompression::Yes;
+const COMPRESSED_NEW_CHALLENGE: UseCompression = UseCompression::No;
+
+const BENCH_VERIFY_CSV_HEADER: &str = "curve,power,elements,verify_ms";
+
+/// Generates a fresh accumulator of `2^power` powers for every supported curve, contributes
+/// to it the way `estimate` does, and times the full verification of the contribution, so
+/// that a release can be checked for performance regressions on every curve at once. The
+/// times are printed as a table, and written to `csv_filename` if provided. The curves are
+/// those the workspace enables in zexe_algebra, which does not include Bn254, so that curve
+/// can't be benchmarked until it is supported.
+pub fn bench_verify(
+    power: usize,
+    batch_size: usize,
+    proving_system: ProvingSystem,
+    subgroup_check_mode: SubgroupCheckMode,
+    csv_filename: Option<&str>,
+) {
+    info!(
+        "Will time the verification of a contribution to 2^{} powers on every curve",
+        power
+    );
+
+    let results = vec![
+        (
+            "bls12_377",
+            bench_verify_curve::<Bls12_377>(power, batch_size, proving_system, subgroup_check_mode),
+        ),
+        (
+            "bls12_381",
+            bench_verify_curve::<Bls12_381>(power, batch_size, proving_system, subgroup_check_mode),
+        ),
+        (
+            "bw6_761",
+            bench_verify_curve::<BW6_761>(power, batch_size, proving_system, subgroup_check_mode),
+        ),
+    ];
+
+    info!(
+        "{:<12} {:>10} {:>14} {:>14}",
+        "curve", "elements", "verify", "elements/s"
+    );
+    for (curve, (elements, elapsed)) in &results {
+        info!(
+            "{:<12} {:>10} {:>14} {:>14.1}",
+            curve,
+            elements,
+            format!("{:?}", elapsed),
+            *elements as f64 / elapsed.as_secs_f64()
+        );
+    }
+
+    if let Some(csv_filename) = csv_filename {
+        let mut writer = BufWriter::new(File::create(csv_filename).expect("unable to create the CSV file"));
+        writeln!(writer, "{}", BENCH_VERIFY_CSV_HEADER).expect("unable to write the CSV file");
+        for (curve, (elements, elapsed)) in &results {
+            writeln!(writer, "{},{},{},{}", curve, power, elements, elapsed.as_millis())
+                .expect("unable to write the CSV file");
+        }
+        writer.flush().expect("unable to write the CSV file");
+        info!("Wrote the verification times to {}", csv_filename);
+    }
+}
+
+/// Returns the number of elements of the accumulator and how long verifying it took
+fn bench_verify_curve<E: Engine + Sync>(
+    power: usize,
+    batch_size: usize,
+    proving_system: ProvingSystem,
+    subgroup_check_mode: SubgroupCheckMode,
+) -> (usize, Duration) {
+    let parameters = Phase1Parameters::<E>::new_full(proving_system, power, batch_size);
+
+    let mut input = vec![0; parameters.get_length(COMPRESSED_INPUT)];
+    Phase1::initialization(&mut input, COMPRESSED_INPUT, &parameters)
+        .expect("generation of initial accumulator is successful");
+    let mut output = vec![0; parameters.get_length(COMPRESSED_OUTPUT)];
+    let mut new_challenge = vec![0; parameters.get_length(COMPRESSED_NEW_CHALLENGE)];
+
+    // The contribution is deterministic, so that every run verifies the same accumulator
+    let digest = blank_hash();
+    let mut rng = derive_rng_from_seed(b"bench-verify");
+    let (public_key, private_key) =
+        Phase1::key_generation(&mut rng, digest.as_ref()).expect("could not generate keypair");
+    Phase1::computation(
+        &input,
+        &mut output,
+        COMPRESSED_INPUT,
+        COMPRESSED_OUTPUT,
+        CheckForCorrectness::No,
+        BatchExpMode::Auto,
+        &private_key,
+        &parameters,
+    )
+    .expect("must contribute to the accumulator with the key");
+
+    let report = Phase1::verification(
+        &input,
+        &output,
+        &mut new_challenge,
+        &public_key,
+        &digest,
+        COMPRESSED_INPUT,
+        COMPRESSED_OUTPUT,
+        COMPRESSED_NEW_CHALLENGE,
+        CheckForCorrectness::No,
+        CheckForCorrectness::Full,
+        subgroup_check_mode,
+        &parameters,
+    )
+    .expect("the contribution must verify");
+
+    (report.elements_checked, report.duration)
+}
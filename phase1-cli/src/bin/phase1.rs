@@ -1,33 +1,75 @@
-use phase1::{helpers::CurveKind, CurveParameters, Phase1Parameters};
+use phase1::{helpers::CurveKind, CurveParameters, Phase1Parameters, ProvingSystem};
 use phase1_cli::{
-    combine, contribute, new_challenge, split, transform_pok_and_correctness, transform_ratios, Command, Phase1Opts,
+    append_to_transcript, bench_read, bench_verify, check_seed, chunk_map, combine, combine_files, compare_combined,
+    contribute_per_chunk, contribute_range, contribute_with_entropy, convert_endianness_file, discover_chunk_files,
+    encrypt_seed_file, estimate, export_fingerprints, extend, extract_public_key, init, interop_check, load_seed,
+    manifest_chunk_files, merge_ranges, mix_token_entropy, new_and_beacon, new_challenge, peek, prepare_phase1,
+    rechunk, resign_transcript, split, test_subgroup, transform_pok_and_correctness, transform_ratios,
+    validate_generators, validate_lengths, verify_chain, verify_compression, verify_final, verify_manifest,
+    verify_my_contribution, verify_pok_only, verify_receipt, verify_transcript_chain, write_receipt, write_sha256,
+    Command, InterruptCleanup, Phase1Opts, ReferenceFormat, DETERMINISTIC_VERIFICATION_SEED,
 };
 use setup_utils::{
-    derive_rng_from_seed, from_slice, upgrade_correctness_check_config, DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS,
+    derive_entropy_from_seed, derive_rng_from_seed, from_slice, rng_from_entropy, strict_correctness_check_config,
+    upgrade_correctness_check_config, CheckForCorrectness, UseCompression, DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS,
     DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, DEFAULT_VERIFY_CHECK_OUTPUT_CORRECTNESS,
 };
 
 use zexe_algebra::{Bls12_377, PairingEngine as Engine, BW6_761};
 
 use gumdrop::Options;
-use std::{fs::read_to_string, process, time::Instant};
+use std::{process, time::Instant};
 use tracing::{error, info};
 use tracing_subscriber::{
     filter::EnvFilter,
     fmt::{time::ChronoUtc, Subscriber},
 };
+use zeroize::Zeroize;
+
+/// Upgrades the correctness check as requested by --force-correctness-checks, or to a full
+/// check in --strict mode, in which no element read skips validation
+fn correctness_check(check_correctness: CheckForCorrectness, opts: &Phase1Opts) -> CheckForCorrectness {
+    strict_correctness_check_config(
+        upgrade_correctness_check_config(check_correctness, opts.force_correctness_checks),
+        opts.strict,
+    )
+}
 
 fn execute_cmd<E: Engine>(opts: Phase1Opts) {
     let curve = CurveParameters::<E>::new();
-    let parameters = Phase1Parameters::<E>::new(
+    // A single chunk holds all the 2^{power+1} - 1 tau powers in G1
+    let chunk_size = opts.chunk_size.unwrap_or(1 << (opts.power + 1));
+    let mut parameters = Phase1Parameters::<E>::try_new(
         opts.contribution_mode,
         opts.chunk_index,
-        opts.chunk_size,
+        chunk_size,
         curve,
         opts.proving_system,
         opts.power,
         opts.batch_size,
-    );
+    )
+    .unwrap_or_else(|e| {
+        error!("{}", e);
+        process::exit(2)
+    });
+    parameters.throttle_ms = opts.throttle_ms;
+    parameters.buffer_pool_size = opts.buffer_pool_size;
+    parameters.strict_correctness_checks = opts.strict;
+    parameters.bind_chunk_index = opts.bind_chunk_index;
+    if let Some(parallel_grain) = opts.parallel_grain {
+        if parallel_grain == 0 || parallel_grain >= opts.batch_size {
+            error!("The parallel grain must be between 1 and the batch size - 1");
+            process::exit(2)
+        }
+        parameters.parallel_grain = parallel_grain;
+    }
+    if let Some(max_inflight) = opts.max_inflight {
+        if max_inflight == 0 {
+            error!("The maximum number of sections verified at once must be at least 1");
+            process::exit(2)
+        }
+        parameters.max_inflight = max_inflight;
+    }
 
     let command = opts.clone().command.unwrap_or_else(|| {
         error!("No command was provided.");
@@ -35,81 +77,499 @@ fn execute_cmd<E: Engine>(opts: Phase1Opts) {
         process::exit(2)
     });
 
+    // Responses are only framed with a header if requested, but framed responses are always accepted
+    let framed_for_curve = if opts.framed { Some(&opts.curve_kind) } else { None };
+
     let now = Instant::now();
 
     match command {
         Command::New(opt) => {
-            new_challenge(&opt.challenge_fname, &opt.challenge_hash_fname, &parameters);
+            let cleanup = if opts.no_cleanup {
+                None
+            } else {
+                Some(InterruptCleanup::install(&opt.challenge_fname))
+            };
+            new_challenge(
+                &opt.challenge_fname,
+                &opt.challenge_hash_fname,
+                opt.output_codec,
+                &parameters,
+            );
+            if let Some(cleanup) = cleanup {
+                cleanup.finish();
+            }
+        }
+        Command::Init(opt) => {
+            init(&opt.output_fname, &parameters);
         }
         Command::Contribute(opt) => {
-            // contribute to the randomness
-            let seed = hex::decode(&read_to_string(&opts.seed).expect("should have read seed").trim())
-                .expect("seed should be a hex string");
+            // contribute to the randomness, with the entropy of the seed
+            let seed_entropy = || {
+                let mut seed = load_seed(&opts.encrypted_seed_file, &opts.seed_file, &opts.seed);
+                check_seed(&seed, opts.min_seed_length, opts.allow_weak_seed);
+                if !opts.pkcs11.is_empty() {
+                    mix_token_entropy(&mut seed, &opts.pkcs11);
+                }
+                let entropy = derive_entropy_from_seed(&seed);
+                seed.zeroize();
+                entropy
+            };
+            let check_input_correctness = correctness_check(DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS, &opts);
+            if opt.per_chunk_output {
+                if opts.framed {
+                    error!("The chunk responses of --per-chunk-output can't be framed");
+                    process::exit(2)
+                }
+                contribute_per_chunk(
+                    &opt.challenge_fname,
+                    &opt.response_fname,
+                    check_input_correctness,
+                    opts.batch_exp_mode,
+                    &opts.curve_kind,
+                    &parameters,
+                    rng_from_entropy(seed_entropy()),
+                );
+            } else {
+                contribute_with_entropy(
+                    &opt.challenge_fname,
+                    &opt.challenge_hash_fname,
+                    &opt.response_fname,
+                    &opt.response_hash_fname,
+                    check_input_correctness,
+                    opts.batch_exp_mode,
+                    framed_for_curve,
+                    &parameters,
+                    seed_entropy,
+                );
+            }
+        }
+        Command::ContributeRange(opt) => {
+            let chunk_range = opt.chunk_range.unwrap_or_else(|| {
+                error!("The range of chunks to contribute to must be provided with --chunk-range");
+                process::exit(2)
+            });
+            // every machine must derive the same key, so the seed is the only randomness used
+            if !opts.pkcs11.is_empty() {
+                error!("The entropy of a PKCS#11 token differs on every machine, so it can't be used here");
+                process::exit(2)
+            }
+            let mut seed = load_seed(&opts.encrypted_seed_file, &opts.seed_file, &opts.seed);
+            check_seed(&seed, opts.min_seed_length, opts.allow_weak_seed);
             let rng = derive_rng_from_seed(&seed);
-            contribute(
+            seed.zeroize();
+            contribute_range(
                 &opt.challenge_fname,
-                &opt.challenge_hash_fname,
-                &opt.response_fname,
-                &opt.response_hash_fname,
-                upgrade_correctness_check_config(
-                    DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS,
-                    opts.force_correctness_checks,
-                ),
+                &opt.response_prefix,
+                chunk_range,
+                correctness_check(DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS, &opts),
                 opts.batch_exp_mode,
                 &parameters,
                 rng,
             );
         }
+        Command::MergeRanges(opt) => {
+            merge_ranges(
+                &opt.challenge_fname,
+                &opt.response_prefix,
+                &opt.response_fname,
+                &opt.response_hash_fname,
+                &parameters,
+            );
+        }
         Command::Beacon(opt) => {
             // use the beacon's randomness
             // Place block hash here (block number #564321)
             let beacon_hash = hex::decode(&opt.beacon_hash).expect("could not hex decode beacon hash");
-            let rng = derive_rng_from_seed(&from_slice(&beacon_hash));
-            contribute(
+            contribute_with_entropy(
                 &opt.challenge_fname,
                 &opt.challenge_hash_fname,
                 &opt.response_fname,
                 &opt.response_hash_fname,
-                upgrade_correctness_check_config(
-                    DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS,
-                    opts.force_correctness_checks,
-                ),
+                correctness_check(DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS, &opts),
                 opts.batch_exp_mode,
+                framed_for_curve,
                 &parameters,
-                rng,
+                || derive_entropy_from_seed(&from_slice(&beacon_hash)),
             );
         }
         Command::VerifyAndTransformPokAndCorrectness(opt) => {
+            // a legacy audit tolerates subgroup failures, which must never be cached as validated
+            if opt.continue_on_subgroup_warning && opt.validation_cache.is_some() {
+                error!("A validation cache can't be used when continuing on subgroup warnings");
+                process::exit(2)
+            }
+            if opt.low_memory
+                && (opt.continue_on_subgroup_warning
+                    || opt.validation_cache.is_some()
+                    || opt.timings_csv.is_some()
+                    || opt.parallel_hash)
+            {
+                error!(
+                    "A low memory verification can't audit legacy files, use a cache, time batches or hash in parallel"
+                );
+                process::exit(2)
+            }
+            if opt.low_memory && parameters.proving_system != ProvingSystem::Groth16 {
+                error!("A low memory verification is only available for Groth16");
+                process::exit(2)
+            }
             // we receive a previous participation, verify it, and generate a new challenge from it
             transform_pok_and_correctness(
                 &opt.challenge_fname,
                 &opt.challenge_hash_fname,
-                upgrade_correctness_check_config(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, opts.force_correctness_checks),
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
                 &opt.response_fname,
                 &opt.response_hash_fname,
-                upgrade_correctness_check_config(
-                    DEFAULT_VERIFY_CHECK_OUTPUT_CORRECTNESS,
-                    opts.force_correctness_checks,
-                ),
+                correctness_check(DEFAULT_VERIFY_CHECK_OUTPUT_CORRECTNESS, &opts),
                 &opt.new_challenge_fname,
                 &opt.new_challenge_hash_fname,
                 opts.subgroup_check_mode,
+                opt.continue_on_subgroup_warning,
+                opt.timings_csv.as_deref(),
+                opt.validation_cache.as_deref(),
+                opt.challenge_compressed,
+                opt.response_compressed,
+                opt.expected_response_hash.as_deref(),
+                opt.parallel_hash,
+                opt.reject_identity,
+                opt.low_memory,
+                &opts.curve_kind,
                 &parameters,
             );
+            if opt.sha256 {
+                write_sha256(&opt.challenge_fname);
+                write_sha256(&opt.response_fname);
+                write_sha256(&opt.new_challenge_fname);
+            }
         }
         Command::VerifyAndTransformRatios(opt) => {
             // we receive a previous participation, verify it, and generate a new challenge from it
             transform_ratios(
                 &opt.response_fname,
-                upgrade_correctness_check_config(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, opts.force_correctness_checks),
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                opt.mmap_window,
+                opt.sections,
+                opt.truncate_extra,
+                opt.decompress_verify,
                 &parameters,
             );
+            if let Some(reference_fname) = &opt.interop_check {
+                interop_check(
+                    &opt.response_fname,
+                    reference_fname,
+                    ReferenceFormat {
+                        endianness: opt.interop_endianness,
+                        with_hash: !opt.interop_without_hash,
+                    },
+                    if opt.decompress_verify {
+                        UseCompression::Yes
+                    } else {
+                        UseCompression::No
+                    },
+                    correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                    &parameters,
+                );
+            }
         }
         Command::Combine(opt) => {
-            combine(&opt.response_list_fname, &opt.combined_fname, &parameters);
+            // A resumable combination keeps its partial output on purpose
+            if opt.verify_only && (opt.resume || opt.rechunk_size.is_some()) {
+                error!("Nothing is written with --verify-only, so there is nothing to resume or re-chunk");
+                process::exit(2)
+            }
+            if opt.hash_only && (opt.resume || opt.rechunk_size.is_some()) {
+                error!("Nothing is written with --hash-only, so there is nothing to resume or re-chunk");
+                process::exit(2)
+            }
+            let cleanup = if opts.no_cleanup || opt.resume || opt.verify_only || opt.hash_only {
+                None
+            } else {
+                Some(InterruptCleanup::install(&opt.combined_fname))
+            };
+            if opt.chunk_glob.is_some() && opt.manifest.is_some() {
+                error!("The response files can either be discovered by a pattern or read from a manifest, not both");
+                process::exit(2)
+            }
+            if opt.deterministic {
+                parameters.verification_seed = Some(DETERMINISTIC_VERIFICATION_SEED);
+            }
+            let chunk_files = match (&opt.chunk_glob, &opt.manifest) {
+                (Some(chunk_glob), _) => Some(discover_chunk_files(chunk_glob)),
+                (None, Some(manifest)) => Some(manifest_chunk_files(manifest, &opts.curve_kind, &parameters)),
+                (None, None) => None,
+            };
+            let res = match chunk_files {
+                Some(chunk_files) => combine_files(
+                    chunk_files,
+                    &opt.combined_fname,
+                    &opt.combined_hash_fname,
+                    &opts.curve_kind,
+                    opt.resume,
+                    opt.output_codec,
+                    opt.sort_by_hash,
+                    opt.verify_only,
+                    opt.hash_only,
+                    &parameters,
+                ),
+                None => combine(
+                    &opt.response_list_fname,
+                    &opt.combined_fname,
+                    &opt.combined_hash_fname,
+                    &opts.curve_kind,
+                    opt.resume,
+                    opt.output_codec,
+                    opt.sort_by_hash,
+                    opt.verify_only,
+                    opt.hash_only,
+                    &parameters,
+                ),
+            };
+            if let Err(e) = res {
+                error!("Combining failed: {}", e);
+                process::exit(1)
+            }
+            if let Some(cleanup) = cleanup {
+                cleanup.finish();
+            }
+            if let Some(rechunk_size) = opt.rechunk_size {
+                if rechunk_size == 0 {
+                    error!("The chunk size to split the combined file into must be at least 1");
+                    process::exit(2)
+                }
+                rechunk(
+                    &opt.combined_fname,
+                    &opt.rechunk_prefix,
+                    &opt.rechunk_name_template,
+                    rechunk_size,
+                    &opts.curve_kind,
+                    &parameters,
+                );
+            }
         }
         Command::Split(opt) => {
-            split(&opt.chunk_fname_prefix, &opt.full_fname, &parameters);
+            split(
+                &opt.chunk_fname_prefix,
+                &opt.name_template,
+                &opt.full_fname,
+                opt.only_chunk,
+                &parameters,
+            );
+        }
+        Command::NewAndBeacon(opt) => {
+            let beacon_hash = hex::decode(&opt.beacon_hash).expect("could not hex decode beacon hash");
+            new_and_beacon(
+                &opt.challenge_fname,
+                &opt.challenge_hash_fname,
+                &opt.response_fname,
+                &opt.response_hash_fname,
+                &beacon_hash,
+                correctness_check(DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS, &opts),
+                opts.batch_exp_mode,
+                &parameters,
+            );
+        }
+        Command::VerifyCompression(opt) => {
+            verify_compression(
+                &opt.compressed_fname,
+                &opt.uncompressed_fname,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                &parameters,
+            );
+        }
+        Command::VerifyFinal(opt) => {
+            verify_final(&opt.combined_fname, &opt.final_hash);
+            if opt.sha256 {
+                write_sha256(&opt.combined_fname);
+            }
+        }
+        Command::ChunkMap(opt) => {
+            if parameters.chunk_size == 0 {
+                error!("The chunk size must be greater than 0 to map the chunks");
+                process::exit(2)
+            }
+            chunk_map(&parameters, opt.json);
+        }
+        Command::VerifyChain(opt) => {
+            verify_chain(
+                &opt.challenge_fname,
+                &opt.response_fname,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                correctness_check(DEFAULT_VERIFY_CHECK_OUTPUT_CORRECTNESS, &opts),
+                opts.subgroup_check_mode,
+                &opts.curve_kind,
+                &parameters,
+            );
+        }
+        Command::Estimate(opt) => {
+            estimate(
+                opt.sample_batches,
+                correctness_check(DEFAULT_CONTRIBUTE_CHECK_INPUT_CORRECTNESS, &opts),
+                opts.batch_exp_mode,
+                &parameters,
+                rand::thread_rng(),
+            );
+        }
+        Command::ConvertEndianness(opt) => {
+            convert_endianness_file(&opt.input_fname, &opt.output_fname, opt.endianness, &parameters);
+        }
+        Command::Extend(opt) => {
+            extend(
+                &opt.input_fname,
+                &opt.output_fname,
+                &opt.output_hash_fname,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                &parameters,
+            );
+        }
+        Command::VerifyPok(opt) => {
+            verify_pok_only(
+                &opt.response_fname,
+                &opt.challenge_hash_fname,
+                correctness_check(DEFAULT_VERIFY_CHECK_OUTPUT_CORRECTNESS, &opts),
+                &opts.curve_kind,
+                &parameters,
+            );
+        }
+        Command::VerifyMyContribution(opt) => {
+            verify_my_contribution(
+                &opt.response_fname,
+                &opt.published_hash,
+                &opt.published_public_key,
+                &opts.curve_kind,
+                &parameters,
+            );
+        }
+        Command::EncryptSeed(opt) => {
+            encrypt_seed_file(&opt.seed_fname, &opt.encrypted_seed_fname);
+        }
+        Command::BenchRead(opt) => {
+            bench_read(
+                opt.iterations,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                &parameters,
+            );
+        }
+        Command::PreparePhase1(opt) => {
+            prepare_phase1(
+                &opt.combined_fname,
+                &opt.phase2_fname,
+                opt.compressed,
+                opt.phase2_compressed,
+                opt.phase2_size,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                &parameters,
+            );
+        }
+        Command::ValidateGenerators(opt) => {
+            validate_generators(
+                &opt.input_fname,
+                opt.compressed,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                &parameters,
+            );
+        }
+        Command::ValidateLengths(opt) => {
+            validate_lengths(&opt.directory, opt.json_fname.as_deref(), &parameters);
+        }
+        Command::VerifyManifest(opt) => {
+            verify_manifest(&opt.manifest_fname);
+        }
+        Command::ExportFingerprints(opt) => {
+            export_fingerprints(&opt.input_fname, &opt.output_fname, opt.compressed, &parameters);
+        }
+        Command::CompareCombined(opt) => {
+            compare_combined(
+                &opt.first_fname,
+                &opt.second_fname,
+                correctness_check(DEFAULT_VERIFY_CHECK_INPUT_CORRECTNESS, &opts),
+                &parameters,
+            );
+        }
+        Command::CheckSeed(_) => {
+            let mut seed = load_seed(&opts.encrypted_seed_file, &opts.seed_file, &opts.seed);
+            check_seed(&seed, opts.min_seed_length, opts.allow_weak_seed);
+            seed.zeroize();
+            info!("The seed is fit to contribute with");
+        }
+        Command::ExtractPubkey(opt) => {
+            extract_public_key(
+                &opt.response_fname,
+                &opt.public_key_fname,
+                opt.compressed,
+                &opts.curve_kind,
+                &parameters,
+            );
+        }
+        Command::Receipt(opt) => {
+            if opt.verify_receipt {
+                let mut seed = load_seed(&opts.encrypted_seed_file, &opts.seed_file, &opts.seed);
+                verify_receipt(
+                    &opt.receipt_fname,
+                    &opt.challenge_fname,
+                    &seed,
+                    &opts.curve_kind,
+                    &parameters,
+                );
+                seed.zeroize();
+            } else {
+                let mut seed = if opt.commit_seed {
+                    Some(load_seed(&opts.encrypted_seed_file, &opts.seed_file, &opts.seed))
+                } else {
+                    None
+                };
+                write_receipt(
+                    &opt.challenge_fname,
+                    &opt.response_fname,
+                    &opt.receipt_fname,
+                    seed.as_deref(),
+                    &opts.curve_kind,
+                    opts.framed,
+                    &parameters,
+                );
+                if let Some(seed) = seed.as_mut() {
+                    seed.zeroize();
+                }
+            }
+        }
+        Command::AppendTranscript(opt) => {
+            append_to_transcript(
+                &opt.transcript_fname,
+                &opt.response_fname,
+                opt.compressed,
+                &opts.curve_kind,
+                &parameters,
+            );
+        }
+        Command::VerifyTranscriptChain(opt) => {
+            verify_transcript_chain(&opt.transcript_fname, &opt.chain_hash);
+        }
+        Command::ResignTranscript(opt) => {
+            resign_transcript(
+                &opt.transcript_fname,
+                &opt.response_list_fname,
+                &opt.new_transcript_fname,
+                opt.hash_algorithm,
+                opt.signing_key_fname.as_deref(),
+            );
+        }
+        Command::TestSubgroup(opt) => {
+            if opt.samples == 0 {
+                error!("At least one point must be checked");
+                process::exit(2)
+            }
+            test_subgroup::<E>(opt.samples);
+        }
+        Command::BenchVerify(opt) => {
+            bench_verify(
+                opt.power,
+                opts.batch_size,
+                opts.proving_system,
+                opts.subgroup_check_mode,
+                opt.csv.as_deref(),
+            );
+        }
+        Command::Peek(opt) => {
+            peek(&opt.input_fname, opt.section, opt.index, opt.compressed, &parameters);
         }
     };
 
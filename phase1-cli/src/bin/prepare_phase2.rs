@@ -1,9 +1,9 @@
 use phase1::{
-    helpers::{curve_from_str, proving_system_from_str, CurveKind},
+    helpers::{curve_from_str, proving_system_from_str, use_compression_from_str, CurveKind},
     parameters::*,
     Phase1,
 };
-use setup_utils::{CheckForCorrectness, Groth16Params, Result, UseCompression};
+use setup_utils::{buffer_size, CheckForCorrectness, Groth16Params, Result, UseCompression};
 
 use zexe_algebra::{Bls12_377, PairingEngine, BW6_761};
 
@@ -44,6 +44,50 @@ struct PreparePhase2Opts {
     pub power: usize,
     #[options(help = "the size (in powers) of the phase 2 circuit", default = "21")]
     pub phase2_size: u32,
+    #[options(
+        help = "only check that an existing phase 2 file has the length implied by the phase 2 size",
+        default = "false"
+    )]
+    pub check_only: bool,
+    #[options(
+        help = "whether the phase 2 file is compressed (yes or no), which phase 2 must be told",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
+}
+
+/// Checks that the prepared file contains exactly the coefficients of a phase 2 of the
+/// requested size, so that a truncated file or a file prepared for another circuit size
+/// is caught before the phase 2 ceremony starts. The prepared file doesn't depend on the
+/// circuit, the queries of the phase 2 parameters built from it are checked against the
+/// circuit's variables by the `check-query` command of the phase 2 binary instead.
+fn check_phase2<E: PairingEngine>(opts: &PreparePhase2Opts) -> Result<()> {
+    let phase2_size = 2usize.pow(opts.phase2_size);
+    let expected = Groth16Params::<E>::serialized_size(phase2_size, opts.compressed);
+    let actual = std::fs::metadata(&opts.phase2_fname)?.len() as usize;
+
+    if actual != expected {
+        // The file holds alpha, beta in G1, beta in G2, 3 G1 and 1 G2 vectors of coefficients
+        // and one less G1 element for the H query, so its length is linear in the size
+        let g1_size = buffer_size::<E::G1Affine>(opts.compressed);
+        let g2_size = buffer_size::<E::G2Affine>(opts.compressed);
+        let per_power = 4 * g1_size + g2_size;
+        let found = match actual.checked_sub(g1_size + g2_size) {
+            Some(len) if len % per_power == 0 => format!("the coefficients of a phase 2 of size {}", len / per_power),
+            _ => "a truncated or corrupted file".to_string(),
+        };
+        panic!(
+            "The phase 2 file should be {} bytes long for a phase 2 of size {}, but it's {} bytes long, which is {}",
+            expected, phase2_size, actual, found
+        );
+    }
+    info!(
+        "The phase 2 file has the expected length of {} bytes for a phase 2 of size {}",
+        expected, phase2_size
+    );
+
+    Ok(())
 }
 
 fn prepare_phase2<E: PairingEngine + Sync>(opts: &PreparePhase2Opts) -> Result<()> {
@@ -88,7 +132,7 @@ fn prepare_phase2<E: PairingEngine + Sync>(opts: &PreparePhase2Opts) -> Result<(
     .expect("could not create Groth16 Lagrange coefficients");
 
     // Write the parameters
-    groth16_params.write(&mut writer, UseCompression::No)?;
+    groth16_params.write(&mut writer, opts.compressed)?;
 
     Ok(())
 }
@@ -102,9 +146,11 @@ fn main() -> Result<()> {
     let opts = PreparePhase2Opts::parse_args_default_or_exit();
 
     let now = Instant::now();
-    match opts.curve_kind {
-        CurveKind::Bls12_377 => prepare_phase2::<Bls12_377>(&opts)?,
-        CurveKind::BW6 => prepare_phase2::<BW6_761>(&opts)?,
+    match (&opts.curve_kind, opts.check_only) {
+        (CurveKind::Bls12_377, false) => prepare_phase2::<Bls12_377>(&opts)?,
+        (CurveKind::BW6, false) => prepare_phase2::<BW6_761>(&opts)?,
+        (CurveKind::Bls12_377, true) => check_phase2::<Bls12_377>(&opts)?,
+        (CurveKind::BW6, true) => check_phase2::<BW6_761>(&opts)?,
     }
 
     let new_now = Instant::now();
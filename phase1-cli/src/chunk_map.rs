@@ -0,0 +1,61 @@
+use phase1::{ContributionMode, Phase1Parameters, ProvingSystem};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use serde::Serialize;
+
+/// The element ranges covered by a single chunk
+#[derive(Serialize)]
+struct ChunkRange {
+    chunk_index: usize,
+    /// The range of tau_g1 elements
+    g1: (usize, usize),
+    /// The range of the other sections' elements
+    other: (usize, usize),
+}
+
+fn chunk_ranges<T: Engine>(parameters: &Phase1Parameters<T>) -> Vec<ChunkRange> {
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+    let num_chunks = (upper_bound + parameters.chunk_size - 1) / parameters.chunk_size;
+
+    (0..num_chunks)
+        .map(|chunk_index| {
+            let chunk = parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, parameters.chunk_size);
+            let start = chunk_index * parameters.chunk_size;
+            let other = match parameters.proving_system {
+                ProvingSystem::Groth16 => (start, start + chunk.other_chunk_size),
+                // Marlin keeps all of the G2 powers in the first chunk
+                ProvingSystem::Marlin if chunk_index == 0 => (0, parameters.total_size_in_log2 + 2),
+                ProvingSystem::Marlin => (0, 0),
+            };
+            ChunkRange {
+                chunk_index,
+                g1: (start, start + chunk.g1_chunk_size),
+                other,
+            }
+        })
+        .collect()
+}
+
+/// Prints the `[start, end)` element ranges covered by each chunk for the current
+/// power and chunk size, in plain text or as JSON.
+pub fn chunk_map<T: Engine>(parameters: &Phase1Parameters<T>, json: bool) {
+    let ranges = chunk_ranges(parameters);
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&ranges).expect("unable to serialize the chunk map")
+        );
+    } else {
+        for r in ranges.iter() {
+            println!(
+                "chunk {}: g1 [{}, {}), other [{}, {})",
+                r.chunk_index, r.g1.0, r.g1.1, r.other.0, r.other.1
+            );
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::collections::HashSet;
+
+/// Returns the filenames of `num_chunks` chunks, generated from `template` by replacing
+/// `{base}` with `base` and `{index}` with the chunk index, so that the chunk files can
+/// follow the naming convention of an existing distribution system. The commands default
+/// to `{base}_{index}`. Panics if the template has other placeholders, or does not yield
+/// a different filename for every chunk.
+pub fn chunk_filenames(template: &str, base: &str, num_chunks: usize) -> Vec<String> {
+    let filenames = (0..num_chunks)
+        .map(|chunk_index| {
+            template
+                .replace("{base}", base)
+                .replace("{index}", &chunk_index.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    // Only the template is checked, so that the base may contain braces
+    let unknown = template.replace("{base}", "").replace("{index}", "");
+    if unknown.contains('{') || unknown.contains('}') {
+        panic!(
+            "The name template {} has placeholders other than {{base}} and {{index}}",
+            template
+        );
+    }
+    if filenames.iter().collect::<HashSet<_>>().len() != num_chunks {
+        panic!(
+            "The name template {} does not yield a different filename for each of the {} chunks",
+            template, num_chunks
+        );
+    }
+
+    filenames
+}
@@ -0,0 +1,181 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use memmap::*;
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    ops::Deref,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The gzip magic bytes, followed by the deflate compression method
+const GZIP_MAGIC: [u8; 3] = [0x1f, 0x8b, 0x08];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The codec which wraps the byte stream of the files we write, on top of the point
+/// compression of the elements. Encoded files are transparently decoded when read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputCodec {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Default for OutputCodec {
+    fn default() -> Self {
+        OutputCodec::None
+    }
+}
+
+pub fn output_codec_from_str(src: &str) -> Result<OutputCodec, String> {
+    let codec = match src.to_lowercase().as_str() {
+        "none" => OutputCodec::None,
+        "gzip" => OutputCodec::Gzip,
+        "zstd" => OutputCodec::Zstd,
+        _ => return Err("unsupported output codec".to_string()),
+    };
+    Ok(codec)
+}
+
+/// Encodes the file in place with the provided codec. The file is first written
+/// in full, since the accumulators are produced through a memory map.
+pub fn encode_file(filename: &str, codec: OutputCodec) -> io::Result<()> {
+    if codec == OutputCodec::None {
+        return Ok(());
+    }
+
+    let encoded_filename = format!("{}.encoding", filename);
+    {
+        let mut reader = BufReader::new(File::open(filename)?);
+        let writer = BufWriter::new(File::create(&encoded_filename)?);
+        match codec {
+            OutputCodec::Gzip => {
+                let mut encoder = GzEncoder::new(writer, Compression::default());
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            OutputCodec::Zstd => {
+                let mut encoder = zstd::Encoder::new(writer, 0)?;
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            OutputCodec::None => unreachable!("unencoded files are left as they are"),
+        }
+    }
+    fs::rename(&encoded_filename, filename)
+}
+
+/// The contents of an input file, either mapped as-is, decoded to a temporary file
+/// which is mapped, or read in memory
+pub enum InputMap {
+    Mapped(Mmap),
+    Decoded(Mmap, DecodedFile),
+    Buffered(Vec<u8>),
+}
+
+impl Deref for InputMap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputMap::Mapped(map) | InputMap::Decoded(map, _) => map,
+            InputMap::Buffered(buffer) => buffer,
+        }
+    }
+}
+
+/// Counts the temporary files decoded by `map_input`, to give each a unique name
+static DECODED_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Maps the file in memory. A file written with an output codec is first stream decoded
+/// to a temporary file in the directory of `std::env::temp_dir`, which is mapped instead
+/// and removed once the returned map is dropped. A file which starts with the magic number
+/// of a codec but fails to decode is an error.
+pub fn map_input(file: &File) -> io::Result<InputMap> {
+    let map = unsafe { MmapOptions::new().map(file)? };
+    if !is_encoded(&map) {
+        return Ok(InputMap::Mapped(map));
+    }
+
+    let decoded_filename = env::temp_dir().join(format!(
+        "phase1-{}-{}.decoded",
+        process::id(),
+        DECODED_FILES.fetch_add(1, Ordering::SeqCst)
+    ));
+    let decoded = decode_to_file(&map[..], &map, decoded_filename)?;
+    let decoded_map = unsafe { MmapOptions::new().map(&decoded.file)? };
+    Ok(InputMap::Decoded(decoded_map, decoded))
+}
+
+/// An input file, either opened as-is or decoded to a temporary file
+pub enum InputFile {
+    Raw(File),
+    Decoded(DecodedFile),
+}
+
+/// A decoded temporary file, which is removed when dropped
+pub struct DecodedFile {
+    filename: PathBuf,
+    file: File,
+}
+
+impl Deref for InputFile {
+    type Target = File;
+
+    fn deref(&self) -> &File {
+        match self {
+            InputFile::Raw(file) => file,
+            InputFile::Decoded(decoded) => &decoded.file,
+        }
+    }
+}
+
+impl Drop for DecodedFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.filename);
+    }
+}
+
+/// Opens the file, stream decoding it first to `{filename}.decoded` if it was written with
+/// an output codec, as `map_input` does. The decoded file is removed once the returned file
+/// is dropped. A file which starts with the magic number of a codec but fails to decode is
+/// an error.
+pub fn open_input(filename: &str) -> io::Result<InputFile> {
+    let mut file = File::open(filename)?;
+    let mut magic = [0; 4];
+    let magic_length = file.read(&mut magic)?;
+    if !is_encoded(&magic[..magic_length]) {
+        return File::open(filename).map(InputFile::Raw);
+    }
+
+    let reader = BufReader::new(File::open(filename)?);
+    let decoded = decode_to_file(reader, &magic[..magic_length], format!("{}.decoded", filename).into())?;
+    Ok(InputFile::Decoded(decoded))
+}
+
+/// Whether the contents, or their first bytes, start with the magic number of a codec
+pub fn is_encoded(magic: &[u8]) -> bool {
+    magic.starts_with(&GZIP_MAGIC) || magic.starts_with(&ZSTD_MAGIC)
+}
+
+/// Stream decodes the reader to `decoded_filename` with the codec of `magic`, and reopens
+/// the decoded file for reading from its start
+fn decode_to_file<R: Read>(reader: R, magic: &[u8], decoded_filename: PathBuf) -> io::Result<DecodedFile> {
+    let mut decoded = DecodedFile {
+        file: File::create(&decoded_filename)?,
+        filename: decoded_filename,
+    };
+    {
+        let mut writer = BufWriter::new(&decoded.file);
+        if magic.starts_with(&GZIP_MAGIC) {
+            io::copy(&mut GzDecoder::new(reader), &mut writer)?;
+        } else {
+            io::copy(&mut zstd::Decoder::new(reader)?, &mut writer)?;
+        }
+        writer.flush()?;
+    }
+    decoded.file = File::open(&decoded.filename)?;
+    Ok(decoded)
+}
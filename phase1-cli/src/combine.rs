@@ -1,114 +1,648 @@
-use phase1::{Phase1, Phase1Parameters};
-use setup_utils::UseCompression;
+use crate::{
+    encode_file, verify_manifest::curve_name, InputMap, LocalFile, Manifest, ManifestEntry, OutputCodec, Storage,
+};
+use phase1::{
+    helpers::{use_compression_from_str, CurveKind},
+    ContributionMode, CurveParameters, Phase1, Phase1Parameters, ProvingSystem, ResponseHeader,
+};
+use setup_utils::{
+    calculate_hash, print_hash, CheckForCorrectness, Error, GenericArray, HashWriter, UseCompression, U64,
+};
 
-use zexe_algebra::PairingEngine as Engine;
+use zexe_algebra::{Bls12_377, PairingEngine as Engine, BW6_761};
 
-use memmap::*;
 use std::{
-    fs::{File, OpenOptions},
-    io::{BufRead, BufReader},
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
 };
 use tracing::info;
 
 const CONTRIBUTION_IS_COMPRESSED: UseCompression = UseCompression::Yes;
 const COMPRESS_NEW_COMBINED: UseCompression = UseCompression::No;
+/// The number of chunks combined between two progress checkpoints when resuming is enabled
+const RESUME_CHECKPOINT_CHUNKS: usize = 16;
+/// The verification seed of a deterministic combination
+pub const DETERMINISTIC_VERIFICATION_SEED: [u8; 32] = *b"phase1-deterministic-combination";
 
+/// Combines the chunked responses listed in the response list into a single accumulator.
+/// Each line of the list is the file name of a response, optionally followed by whether
+/// that response is compressed (`yes` or `no`), so that responses of contributors who chose
+/// different compressions can be combined. Unannotated responses are assumed compressed.
+/// Combining only decompresses and copies the elements of each chunk, and resuming compares
+/// the partial output byte for byte, so two runs over the same responses produce identical
+/// files. Only the verification of `verify_only` draws randomness, for the random linear
+/// combinations of its ratio checks, which `Phase1Parameters::verification_seed` fixes.
 pub fn combine<T: Engine + Sync>(
     response_list_filename: &str,
     combined_filename: &str,
+    combined_hash_filename: &str,
+    curve_kind: &CurveKind,
+    resume: bool,
+    output_codec: OutputCodec,
+    sort_by_hash: bool,
+    verify_only: bool,
+    hash_only: bool,
+    parameters: &Phase1Parameters<T>,
+) -> Result<(), Error> {
+    let response_list_reader =
+        BufReader::new(File::open(response_list_filename).expect("should have opened the response list"));
+    let response_files = response_list_reader
+        .lines()
+        .map(|line| line.map(|line| parse_response_list_line(&line)))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("should have read the response list");
+
+    // A misgenerated list must not silently produce a bogus combined file
+    if response_files.is_empty() {
+        info!("The response list {} is empty", response_list_filename);
+        return Err(Error::InvalidParameters("there are no responses to combine"));
+    }
+
+    combine_files_with_compression(
+        response_files,
+        combined_filename,
+        combined_hash_filename,
+        curve_kind,
+        resume,
+        output_codec,
+        sort_by_hash,
+        verify_only,
+        hash_only,
+        parameters,
+    )
+}
+
+/// Parses a line of the response list, a file name optionally followed by whitespace and the
+/// compression of the response, which is otherwise the default compression of contributions
+fn parse_response_list_line(line: &str) -> (String, UseCompression) {
+    let line = line.trim_end();
+    if let Some(separator) = line.rfind(char::is_whitespace) {
+        if let Ok(compression) = use_compression_from_str(&line[separator + 1..]) {
+            return (line[..separator].trim_end().to_string(), compression);
+        }
+    }
+    (line.to_string(), CONTRIBUTION_IS_COMPRESSED)
+}
+
+/// Discovers the chunked responses matching the pattern, e.g. `response_*`, and sorts them
+/// by the chunk index which ends their names, following the naming of `split`. The indices
+/// must be contiguous from 0, so that a missing chunk is caught before combining.
+pub fn discover_chunk_files(pattern: &str) -> Vec<String> {
+    let mut chunk_files = glob::glob(pattern)
+        .expect("invalid chunk file pattern")
+        .map(|path| {
+            let path = path.expect("unable to read a chunk file path");
+            let filename = path.to_string_lossy().to_string();
+            let digits = filename.len() - filename.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+            let chunk_index = filename[filename.len() - digits..]
+                .parse::<usize>()
+                .unwrap_or_else(|_| panic!("The chunk file {} does not end with its chunk index", filename));
+            (chunk_index, filename)
+        })
+        .collect::<Vec<_>>();
+    if chunk_files.is_empty() {
+        panic!("No chunk file matches {}, there is nothing to combine", pattern);
+    }
+
+    chunk_files.sort();
+    for (expected_index, (chunk_index, filename)) in chunk_files.iter().enumerate() {
+        if *chunk_index != expected_index {
+            panic!(
+                "The chunk indices are not contiguous: expected chunk {}, but found {} with chunk index {}",
+                expected_index, filename, chunk_index
+            );
+        }
+    }
+
+    chunk_files.into_iter().map(|(_, filename)| filename).collect()
+}
+
+/// Checks that the response was produced for the curve of the combination, rather than
+/// letting its bytes misparse. The curve of framed responses is recorded in their header,
+/// while the curve of unframed responses is inferred from their length, which differs
+/// between the known curves.
+fn check_curve<T: Engine>(
+    filename: &str,
+    response: &[u8],
+    curve_kind: &CurveKind,
+    compression: UseCompression,
     parameters: &Phase1Parameters<T>,
 ) {
+    let known_curves = [
+        (CurveKind::Bls12_377, curve_sizes::<Bls12_377, T>()),
+        (CurveKind::BW6, curve_sizes::<BW6_761, T>()),
+    ];
+    let expected_header = ResponseHeader::new(curve_kind, compression, parameters);
+    let detected = match ResponseHeader::read(response).expect("invalid response header") {
+        Some(header) if header.curve_id != expected_header.curve_id => Some(
+            known_curves
+                .iter()
+                .map(|(kind, _)| kind)
+                .find(|kind| ResponseHeader::new(kind, compression, parameters).curve_id == header.curve_id)
+                .map(curve_name)
+                .unwrap_or("unknown"),
+        ),
+        Some(_) => None,
+        None if response.len() == parameters.get_response_length(compression) => None,
+        None => known_curves
+            .iter()
+            .find(|(_, curve)| {
+                let parameters = Phase1Parameters::<T>::new(
+                    parameters.contribution_mode,
+                    parameters.chunk_index,
+                    parameters.chunk_size,
+                    curve.clone(),
+                    parameters.proving_system,
+                    parameters.total_size_in_log2,
+                    parameters.batch_size,
+                );
+                response.len() == parameters.get_response_length(compression)
+            })
+            .map(|(kind, _)| curve_name(kind)),
+    };
+
+    if let Some(detected) = detected {
+        panic!(
+            "The response {} was produced for the {} curve, but the contributions are combined for the {} curve",
+            filename,
+            detected,
+            curve_name(curve_kind)
+        );
+    }
+}
+
+/// Returns the sizes of the elements of the curve `E`, tagged with the engine `T`
+fn curve_sizes<E: Engine, T>() -> CurveParameters<T> {
+    let curve = CurveParameters::<E>::new();
+    CurveParameters::from_sizes(
+        curve.g1_size,
+        curve.g2_size,
+        curve.g1_compressed_size,
+        curve.g2_compressed_size,
+    )
+}
+
+/// Returns the path of a response as the manifest of the combination lists it, relative to
+/// the directory of the manifest, which is the current directory unless it is made absolute
+fn manifest_path(filename: &str, manifest_filename: &str) -> String {
+    let in_current_directory = Path::new(manifest_filename)
+        .parent()
+        .map_or(true, |directory| directory.as_os_str().is_empty());
+    if in_current_directory || Path::new(filename).is_absolute() {
+        filename.to_string()
+    } else {
+        std::fs::canonicalize(filename)
+            .expect("unable to resolve the path of the response")
+            .display()
+            .to_string()
+    }
+}
+
+/// Reads the hashes of all the responses first and sorts the responses by them, so that
+/// they are processed in the same order whatever the order they were listed in. Combining
+/// is position sensitive, since each response holds a single chunk of the accumulator, so
+/// reordering is only sound when every response records its own chunk: the responses must
+/// be framed, and they are placed by the chunk index of their header rather than by their
+/// position in the list. Unframed responses can not be reordered.
+pub fn sort_by_hash(response_files: Vec<(String, UseCompression)>) -> Vec<(String, UseCompression)> {
+    let mut responses = response_files
+        .into_iter()
+        .map(|(filename, compression)| {
+            // Only one response is read at a time, to bound memory
+            let response = LocalFile::open(&filename)
+                .and_then(|storage| storage.read_all())
+                .expect("unable to read the response file in this directory");
+            let header = ResponseHeader::read(&response)
+                .expect("invalid response header")
+                .unwrap_or_else(|| {
+                    panic!(
+                        "The response {} is not framed, so its chunk is only known from its position in the list \
+                         and it can not be sorted by hash",
+                        filename
+                    )
+                });
+            (
+                calculate_hash(&response).to_vec(),
+                header.chunk_index as usize,
+                filename,
+                compression,
+            )
+        })
+        .collect::<Vec<_>>();
+    responses.sort();
+
+    let mut chunk_files = vec![None; responses.len()];
+    for (hash, chunk_index, filename, compression) in responses {
+        info!(
+            "{} holds chunk {} with hash {}",
+            filename,
+            chunk_index,
+            hex::encode(&hash)
+        );
+        if chunk_index >= chunk_files.len() {
+            panic!(
+                "The response {} holds chunk {}, but there are only {} responses",
+                filename,
+                chunk_index,
+                chunk_files.len()
+            );
+        }
+        if let Some((other, _)) = chunk_files[chunk_index].replace((filename, compression)) {
+            panic!(
+                "Chunk {} is held by more than one response, including {}",
+                chunk_index, other
+            );
+        }
+    }
+
+    chunk_files
+        .into_iter()
+        .map(|filename| filename.expect("every chunk is held by a response"))
+        .collect()
+}
+
+/// Same as `combine`, except that the chunked responses are provided directly, in the
+/// order of their chunk index unless they are sorted by hash, and are all compressed
+#[allow(clippy::too_many_arguments)]
+pub fn combine_files<T: Engine + Sync>(
+    response_filenames: Vec<String>,
+    combined_filename: &str,
+    combined_hash_filename: &str,
+    curve_kind: &CurveKind,
+    resume: bool,
+    output_codec: OutputCodec,
+    sort_by_hash: bool,
+    verify_only: bool,
+    hash_only: bool,
+    parameters: &Phase1Parameters<T>,
+) -> Result<(), Error> {
+    let response_files = response_filenames
+        .into_iter()
+        .map(|filename| (filename, CONTRIBUTION_IS_COMPRESSED))
+        .collect();
+    combine_files_with_compression(
+        response_files,
+        combined_filename,
+        combined_hash_filename,
+        curve_kind,
+        resume,
+        output_codec,
+        sort_by_hash,
+        verify_only,
+        hash_only,
+        parameters,
+    )
+}
+
+/// Same as `combine_files`, except that each response is provided with its own compression.
+///
+/// The hash of every response is computed as it is read, and recorded along with the hash of
+/// the combined file in `<combined>.manifest.json`, which `verify-manifest` can check, so that
+/// there is an auditable record of exactly which responses went into the combination.
+///
+/// With `verify_only`, the responses are combined in memory and the ratios of the combined
+/// accumulator are verified, without writing anything, so that a set of responses can be
+/// checked before committing to the large combined file. The hash the combined file would
+/// have is printed, to be compared with the one of the file once it is written.
+///
+/// With `hash_only`, the responses are combined in memory in the same way, and only the hash
+/// the combined file would have is printed, so that a coordinator can check the combination
+/// matches the expected hash before spending the disk and time to write it.
+#[allow(clippy::too_many_arguments)]
+pub fn combine_files_with_compression<T: Engine + Sync>(
+    response_files: Vec<(String, UseCompression)>,
+    combined_filename: &str,
+    combined_hash_filename: &str,
+    curve_kind: &CurveKind,
+    resume: bool,
+    output_codec: OutputCodec,
+    sort_by_hash: bool,
+    verify_only: bool,
+    hash_only: bool,
+    parameters: &Phase1Parameters<T>,
+) -> Result<(), Error> {
     info!("Will combine contributions",);
 
-    let mut readers = vec![];
+    let response_files = if sort_by_hash {
+        self::sort_by_hash(response_files)
+    } else {
+        response_files
+    };
+    let responses = response_files
+        .iter()
+        .map(|(filename, compression)| {
+            let storage = LocalFile::open(filename).expect("unable to open the response file in this directory");
+            (filename.clone(), storage, *compression)
+        })
+        .collect::<Vec<_>>();
 
-    let response_list_reader =
-        BufReader::new(File::open(response_list_filename).expect("should have opened the response list"));
-    for (chunk_index, line) in response_list_reader.lines().enumerate() {
-        let line = line.expect("should have read line");
-        let parameters =
-            parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
-        let response_reader = OpenOptions::new()
-            .read(true)
-            .open(line)
-            .expect("unable open response file in this directory");
-        {
-            let metadata = response_reader
-                .metadata()
-                .expect("unable to get filesystem metadata for response file");
-            let expected_response_length = match CONTRIBUTION_IS_COMPRESSED {
-                UseCompression::Yes => parameters.contribution_size,
-                UseCompression::No => parameters.accumulator_size + parameters.public_key_size,
-            };
-            if metadata.len() != (expected_response_length as u64) {
-                panic!(
-                    "The size of response file should be {}, but it's {}, so something isn't right.",
-                    expected_response_length,
-                    metadata.len()
-                );
+    if verify_only || hash_only {
+        let combined = combine_in_memory(responses, curve_kind, parameters)?;
+        if verify_only {
+            let full_parameters = parameters.into_chunk_parameters(ContributionMode::Full, 0, 0);
+            // The responses are untrusted, so every element of the combination is checked
+            match Phase1::aggregate_verification(
+                (&combined, COMPRESS_NEW_COMBINED, CheckForCorrectness::Full),
+                &full_parameters,
+            ) {
+                Ok(report) => info!("Verification of the combination succeeded, {}", report),
+                Err(e) => {
+                    info!("Verification of the combination failed: {}", e);
+                    return Err(e);
+                }
             }
         }
+        // The combination is hashed exactly as it would be written to the combined file
+        let mut hasher = HashWriter::new(io::sink());
+        hasher.write_all(&combined).expect("unable to hash the combination");
+        info!("The combined file would have the hash:");
+        print_hash(&hasher.into_hash());
+        return Ok(());
+    }
 
-        unsafe {
-            readers.push(
-                MmapOptions::new()
-                    .map(&response_reader)
-                    .expect("should have mapped the reader"),
-            );
+    // When resuming, the number of chunks already combined is recorded in a sidecar file.
+    // A corrupt progress file is an error rather than a reason to silently start over.
+    let progress_filename = format!("{}.progress", combined_filename);
+    let resume_from = if resume && Path::new(combined_filename).exists() && Path::new(&progress_filename).exists() {
+        let progress = std::fs::read_to_string(&progress_filename)?;
+        match progress.trim().parse::<usize>() {
+            Ok(resume_from) => resume_from,
+            Err(e) => {
+                info!("The progress file {} is corrupt: {}", progress_filename, e);
+                return Err(Error::InvalidParameters("the progress file is corrupt"));
+            }
         }
+    } else {
+        0
+    };
+
+    let mut output = if resume_from > 0 {
+        info!("Resuming the combination after {} chunks", resume_from);
+        LocalFile::open_writable(combined_filename)
+            .expect("unable to open the partially combined file in this directory")
+    } else if resume {
+        LocalFile::create(combined_filename).expect("unable to create new combined file in this directory")
+    } else {
+        LocalFile::create_new(combined_filename).expect("unable to create new combined file in this directory")
+    };
+
+    // Checkpoint after each group of chunks, so that an interruption loses at most one group
+    let mut checkpoint = |combined: usize| std::fs::write(&progress_filename, combined.to_string());
+    let (final_hash, response_hashes) = combine_storage(
+        responses,
+        &mut output,
+        curve_kind,
+        resume_from,
+        if resume { Some(&mut checkpoint) } else { None },
+        parameters,
+    )?;
+
+    // The combination is complete, so a later combine into the same file must start over
+    if resume && Path::new(&progress_filename).exists() {
+        std::fs::remove_file(&progress_filename).expect("unable to remove the progress file");
     }
 
-    let parameters_for_output = Phase1Parameters::<T>::new(
-        parameters.contribution_mode,
-        0,
-        parameters.powers_g1_length,
-        parameters.curve.clone(),
-        parameters.proving_system,
-        parameters.total_size_in_log2,
-        parameters.batch_size,
-    );
-    let writer = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create_new(true)
-        .open(combined_filename)
-        .expect("unable to create new combined file in this directory");
+    // The final hash commits to the combined parameters, so that anyone can check
+    // they are using the authentic output of the ceremony
+    File::create(combined_hash_filename)
+        .expect("unable to create the combined hash file")
+        .write_all(final_hash.as_slice())
+        .expect("unable to write the combined hash");
+
+    // The manifest of the combination records the hash of every response
+    let manifest_filename = format!("{}.manifest.json", combined_filename);
+    let manifest = Manifest {
+        num_chunks: response_hashes.len(),
+        curve: Some(curve_name(curve_kind).to_string()),
+        power: Some(parameters.total_size_in_log2),
+        chunks: response_files
+            .iter()
+            .zip(response_hashes)
+            .enumerate()
+            .map(|(chunk_index, ((filename, _), hash))| ManifestEntry {
+                chunk_index,
+                filename: manifest_path(filename, &manifest_filename),
+                hash,
+            })
+            .collect(),
+        combined_hash: Some(hex::encode(&final_hash)),
+    };
+    serde_json::to_writer_pretty(
+        File::create(&manifest_filename).expect("unable to create the manifest file"),
+        &manifest,
+    )
+    .expect("unable to write the manifest file");
+    info!("The responses which were combined are listed in {}", manifest_filename);
+
+    info!("==================================================================");
+    info!("FINAL HASH of the combined parameters: {}", hex::encode(&final_hash));
+    print_hash(&final_hash);
+    info!("==================================================================");
+
+    drop(output);
+    encode_file(combined_filename, output_codec).expect("unable to encode the combined file");
+
+    Ok(())
+}
+
+/// Combines the chunked responses, each read from its storage and listed with a name for the
+/// logs and its compression, into the `output` storage, and returns the hash of the combined
+/// accumulator along with the hex encoded hash of every response.
+///
+/// The accumulator is combined in memory and written to the output as a whole. The first
+/// `resume_from` chunks are taken from the output, which must already hold them, after
+/// checking them against their responses. With a `checkpoint`, the remaining chunks are
+/// combined in groups, and after the output was written each group the checkpoint is called
+/// with the number of chunks it holds.
+pub fn combine_storage<T: Engine + Sync, S: Storage>(
+    responses: Vec<(String, S, UseCompression)>,
+    output: &mut impl Storage,
+    curve_kind: &CurveKind,
+    resume_from: usize,
+    checkpoint: Option<&mut dyn FnMut(usize) -> io::Result<()>>,
+    parameters: &Phase1Parameters<T>,
+) -> Result<(GenericArray<u8, U64>, Vec<String>), Error> {
+    let num_responses = responses.len();
+    if resume_from > num_responses {
+        info!(
+            "The progress file claims {} chunks were combined, but there are only {}",
+            resume_from, num_responses
+        );
+        return Err(Error::InvalidLength {
+            expected: num_responses,
+            got: resume_from,
+        });
+    }
+
+    let (readers, response_hashes) = read_responses(responses, curve_kind, parameters)?;
+    let (parameters, parameters_for_output) = combination_parameters(parameters);
+    let inputs = readers
+        .iter()
+        .map(|(r, header_size, compression)| (&r[*header_size..], *compression))
+        .collect::<Vec<_>>();
 
     info!("parameters for output: {:?}", parameters_for_output);
 
-    writer
-        .set_len(parameters_for_output.accumulator_size as u64)
-        .expect("must make output file large enough");
+    // Never trust the partial output blindly, the chunks it claims to contain must match their contributions
+    let mut combined = if resume_from > 0 {
+        let partial = output.read_range(0, parameters_for_output.accumulator_size)?;
+        Phase1::verify_aggregation_from(
+            0,
+            &inputs[..resume_from],
+            (&partial, COMPRESS_NEW_COMBINED),
+            &parameters,
+        )
+        .map_err(|e| {
+            info!("The partially combined file does not match the contributions, remove it to start over");
+            e
+        })?;
+        info!("The {} chunks already combined are valid", resume_from);
+        partial
+    } else {
+        vec![0; parameters_for_output.accumulator_size]
+    };
 
-    let mut writable_map = unsafe {
-        MmapOptions::new()
-            .map_mut(&writer)
-            .expect("unable to create a memory map for output")
+    let res = match checkpoint {
+        Some(checkpoint) => (resume_from..inputs.len())
+            .step_by(RESUME_CHECKPOINT_CHUNKS)
+            .map(|first| {
+                let last = std::cmp::min(first + RESUME_CHECKPOINT_CHUNKS, inputs.len());
+                Phase1::aggregation_from(
+                    first,
+                    &inputs[first..last],
+                    (&mut combined, COMPRESS_NEW_COMBINED),
+                    &parameters,
+                )?;
+                output.write_range(0, &combined)?;
+                checkpoint(last)?;
+                info!("Combined {} out of {} chunks", last, inputs.len());
+                Ok(())
+            })
+            .collect::<Result<(), Error>>(),
+        None => Phase1::aggregation(&inputs, (&mut combined, COMPRESS_NEW_COMBINED), &parameters)
+            .and_then(|_| output.write_range(0, &combined).map_err(Error::from)),
     };
 
-    let parameters = Phase1Parameters::<T>::new(
+    if let Err(e) = res {
+        info!("Combining failed: {}", e);
+        return Err(e);
+    } else {
+        info!("Combining succeeded!");
+    }
+
+    Ok((calculate_hash(&combined), response_hashes))
+}
+
+/// Combines the chunked responses in memory, without writing the combination anywhere
+fn combine_in_memory<T: Engine + Sync, S: Storage>(
+    responses: Vec<(String, S, UseCompression)>,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) -> Result<Vec<u8>, Error> {
+    let (readers, _) = read_responses(responses, curve_kind, parameters)?;
+    let (parameters, parameters_for_output) = combination_parameters(parameters);
+    let inputs = readers
+        .iter()
+        .map(|(r, header_size, compression)| (&r[*header_size..], *compression))
+        .collect::<Vec<_>>();
+
+    let mut combined = vec![0; parameters_for_output.accumulator_size];
+    if let Err(e) = Phase1::aggregation(&inputs, (&mut combined, COMPRESS_NEW_COMBINED), &parameters) {
+        info!("Combining failed: {}", e);
+        return Err(e);
+    }
+
+    Ok(combined)
+}
+
+/// Returns the parameters the responses are read with, in which the options such as
+/// --strict are kept, and the parameters of the combined accumulator
+fn combination_parameters<T: Engine>(parameters: &Phase1Parameters<T>) -> (Phase1Parameters<T>, Phase1Parameters<T>) {
+    let parameters_for_output = Phase1Parameters::<T>::new(
         parameters.contribution_mode,
         0,
-        parameters.chunk_size,
+        parameters.powers_g1_length,
         parameters.curve.clone(),
         parameters.proving_system,
         parameters.total_size_in_log2,
         parameters.batch_size,
     );
-    let res = Phase1::aggregation(
-        &readers
-            .iter()
-            .map(|r| (r.as_ref(), CONTRIBUTION_IS_COMPRESSED))
-            .collect::<Vec<_>>()
-            .as_slice(),
-        (&mut writable_map, COMPRESS_NEW_COMBINED),
-        &parameters,
-    );
+    let parameters = parameters.into_chunk_parameters(parameters.contribution_mode, 0, parameters.chunk_size);
 
-    if let Err(e) = res {
-        info!("Combining failed: {}", e);
-        panic!("INVALID CONTRIBUTIONS!!!");
-    } else {
-        info!("Combining succeeded!");
+    (parameters, parameters_for_output)
+}
+
+/// Reads the chunked responses from their storage and checks that there is one per chunk,
+/// and that each of them was produced for the curve and the chunk it is combined for. Returns
+/// each response with the size of its header and its compression, and the hex encoded hash
+/// of every response.
+#[allow(clippy::type_complexity)]
+fn read_responses<T: Engine, S: Storage>(
+    responses: Vec<(String, S, UseCompression)>,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) -> Result<(Vec<(InputMap, usize, UseCompression)>, Vec<String>), Error> {
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+    if parameters.chunk_size == 0 {
+        return Err(Error::InvalidParameters("the chunk size must be greater than 0"));
+    }
+    let num_chunks = (upper_bound + parameters.chunk_size - 1) / parameters.chunk_size;
+    if responses.len() != num_chunks {
+        info!(
+            "The response list should contain {} responses, one per chunk, but it contains {}",
+            num_chunks,
+            responses.len()
+        );
+        return Err(Error::InvalidLength {
+            expected: num_chunks,
+            got: responses.len(),
+        });
     }
+
+    // The chunk size needn't divide the number of elements, in which case the last chunk is shorter
+    let last_chunk_size = upper_bound - (num_chunks - 1) * parameters.chunk_size;
+    if last_chunk_size != parameters.chunk_size {
+        info!(
+            "The last chunk holds {} elements rather than {}",
+            last_chunk_size, parameters.chunk_size
+        );
+    }
+
+    let mut readers = vec![];
+    let mut response_hashes = vec![];
+
+    for (chunk_index, (name, storage, compression)) in responses.into_iter().enumerate() {
+        let parameters =
+            parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
+        let response_readable_map = storage
+            .read_all()
+            .expect("unable to read the response file in this directory");
+        response_hashes.push(hex::encode(calculate_hash(&response_readable_map)));
+
+        check_curve(&name, &response_readable_map, curve_kind, compression, &parameters);
+
+        // Skip the header of framed responses, after checking it matches this chunk's parameters
+        let expected_header = ResponseHeader::new(curve_kind, compression, &parameters);
+        let header_size = {
+            let response =
+                ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+            let expected_response_length = parameters.get_response_length(compression);
+            if response.len() != expected_response_length {
+                panic!(
+                    "The size of response file should be {}, but it's {}, so something isn't right.",
+                    expected_response_length,
+                    response.len()
+                );
+            }
+            response_readable_map.len() - response.len()
+        };
+
+        readers.push((response_readable_map, header_size, compression));
+    }
+
+    Ok((readers, response_hashes))
 }
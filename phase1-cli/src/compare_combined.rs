@@ -0,0 +1,76 @@
+use crate::map_input;
+use phase1::{Phase1, Phase1Parameters};
+use setup_utils::{CheckForCorrectness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::fs::OpenOptions;
+use tracing::info;
+
+const COMBINED_IS_COMPRESSED: UseCompression = UseCompression::No;
+
+/// Checks that two independently combined files are valid and hold the same accumulator,
+/// so that parties cross-checking the combination can detect a tampered one. The hash at
+/// the start of the files is not compared.
+pub fn compare_combined<T: Engine + Sync>(
+    first_filename: &str,
+    second_filename: &str,
+    check_input_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!("Will compare {} and {}", first_filename, second_filename);
+
+    let expected_length = Phase1Parameters::<T>::new_chunk(
+        parameters.contribution_mode,
+        0,
+        parameters.powers_g1_length,
+        parameters.proving_system,
+        parameters.total_size_in_log2,
+        parameters.batch_size,
+    )
+    .accumulator_size;
+
+    let combined = [first_filename, second_filename]
+        .iter()
+        .map(|filename| {
+            let reader = OpenOptions::new()
+                .read(true)
+                .open(filename)
+                .expect("unable open combined file in this directory");
+            let readable_map = map_input(&reader).expect("unable to create a memory map for input");
+            if readable_map.len() != expected_length {
+                panic!(
+                    "The size of {} should be {}, but it's {}, so something isn't right.",
+                    filename,
+                    expected_length,
+                    readable_map.len()
+                );
+            }
+
+            info!("Verifying the ratios of {}...", filename);
+            let res = Phase1::aggregate_verification(
+                (&readable_map, COMBINED_IS_COMPRESSED, check_input_correctness),
+                parameters,
+            );
+            if let Err(e) = res {
+                info!("Verification of {} failed: {}", filename, e);
+                panic!("INVALID CONTRIBUTION!!!");
+            }
+
+            readable_map
+        })
+        .collect::<Vec<_>>();
+
+    let first = &combined[0][parameters.hash_size..];
+    let second = &combined[1][parameters.hash_size..];
+    if let Some(position) = first.iter().zip(second).position(|(a, b)| a != b) {
+        panic!(
+            "The combined files diverge at byte {}: {:02x} != {:02x}",
+            parameters.hash_size + position,
+            first[position],
+            second[position]
+        );
+    }
+
+    info!("Both combined files are valid and hold the same accumulator");
+}
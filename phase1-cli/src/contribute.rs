@@ -1,5 +1,9 @@
-use phase1::{Phase1, Phase1Parameters};
-use setup_utils::{calculate_hash, print_hash, BatchExpMode, CheckForCorrectness, UseCompression};
+use crate::map_input;
+use phase1::{
+    helpers::{bind_digest_to_chunk, CurveKind},
+    Phase1, Phase1Parameters, ResponseHeader, HEADER_SIZE,
+};
+use setup_utils::{calculate_hash, print_hash, rng_from_entropy, BatchExpMode, CheckForCorrectness, UseCompression};
 
 use zexe_algebra::PairingEngine as Engine;
 
@@ -10,10 +14,12 @@ use std::{
     io::{Read, Write},
 };
 use tracing::info;
+use zeroize::Zeroize;
 
 const COMPRESSED_INPUT: UseCompression = UseCompression::No;
 const COMPRESSED_OUTPUT: UseCompression = UseCompression::Yes;
 
+#[allow(clippy::too_many_arguments)]
 pub fn contribute<T: Engine + Sync>(
     challenge_filename: &str,
     challenge_hash_filename: &str,
@@ -21,6 +27,7 @@ pub fn contribute<T: Engine + Sync>(
     response_hash_filename: &str,
     check_input_correctness: CheckForCorrectness,
     batch_exp_mode: BatchExpMode,
+    framed_for_curve: Option<&CurveKind>,
     parameters: &Phase1Parameters<T>,
     mut rng: impl Rng,
 ) {
@@ -29,30 +36,19 @@ pub fn contribute<T: Engine + Sync>(
         .read(true)
         .open(challenge_filename)
         .expect("unable open challenge file");
+    let readable_map = map_input(&reader).expect("unable to create a memory map for input");
     {
-        let metadata = reader
-            .metadata()
-            .expect("unable to get filesystem metadata for challenge file");
-        let expected_challenge_length = match COMPRESSED_INPUT {
-            UseCompression::Yes => parameters.contribution_size,
-            UseCompression::No => parameters.accumulator_size,
-        };
-
-        if metadata.len() != (expected_challenge_length as u64) {
+        let expected_challenge_length = parameters.get_length(COMPRESSED_INPUT);
+
+        if readable_map.len() != expected_challenge_length {
             panic!(
                 "The size of challenge file should be {}, but it's {}, so something isn't right.",
                 expected_challenge_length,
-                metadata.len()
+                readable_map.len()
             );
         }
     }
 
-    let readable_map = unsafe {
-        MmapOptions::new()
-            .map(&reader)
-            .expect("unable to create a memory map for input")
-    };
-
     // Create response file in this directory
     let writer = OpenOptions::new()
         .read(true)
@@ -61,13 +57,13 @@ pub fn contribute<T: Engine + Sync>(
         .open(response_filename)
         .expect("unable to create response file");
 
-    let required_output_length = match COMPRESSED_OUTPUT {
-        UseCompression::Yes => parameters.contribution_size,
-        UseCompression::No => parameters.accumulator_size + parameters.public_key_size,
-    };
+    let required_output_length = parameters.get_response_length(COMPRESSED_OUTPUT);
+
+    // Framed responses start with a header describing the parameters they were produced with
+    let header_size = if framed_for_curve.is_some() { HEADER_SIZE } else { 0 };
 
     writer
-        .set_len(required_output_length as u64)
+        .set_len((header_size + required_output_length) as u64)
         .expect("must make output file large enough");
 
     let mut writable_map = unsafe {
@@ -76,6 +72,12 @@ pub fn contribute<T: Engine + Sync>(
             .expect("unable to create a memory map for output")
     };
 
+    if let Some(curve) = framed_for_curve {
+        ResponseHeader::new(curve, COMPRESSED_OUTPUT, parameters)
+            .write(&mut writable_map)
+            .expect("unable to write the response header");
+    }
+
     info!("Calculating previous contribution hash...");
 
     assert!(
@@ -92,7 +94,7 @@ pub fn contribute<T: Engine + Sync>(
             .write_all(current_accumulator_hash.as_slice())
             .expect("unable to write current accumulator hash");
 
-        (&mut writable_map[0..])
+        (&mut writable_map[header_size..])
             .write_all(current_accumulator_hash.as_slice())
             .expect("unable to write a challenge hash to mmap");
 
@@ -112,9 +114,10 @@ pub fn contribute<T: Engine + Sync>(
         print_hash(&challenge_hash);
     }
 
-    // Construct our keypair using the RNG we created above
+    // Construct our keypair using the RNG we created above, bound to the chunk being contributed to
     let (public_key, private_key) =
-        Phase1::key_generation(&mut rng, current_accumulator_hash.as_ref()).expect("could not generate keypair");
+        Phase1::key_generation(&mut rng, &bind_digest_to_chunk(&current_accumulator_hash, parameters))
+            .expect("could not generate keypair");
 
     // Perform the transformation
     info!("Computing and writing your contribution, this could take a while...");
@@ -122,7 +125,7 @@ pub fn contribute<T: Engine + Sync>(
     // this computes a transformation and writes it
     Phase1::computation(
         &readable_map,
-        &mut writable_map,
+        &mut writable_map[header_size..],
         COMPRESSED_INPUT,
         COMPRESSED_OUTPUT,
         check_input_correctness,
@@ -136,7 +139,7 @@ pub fn contribute<T: Engine + Sync>(
 
     // Write the public key
     public_key
-        .write(&mut writable_map, COMPRESSED_OUTPUT, &parameters)
+        .write(&mut writable_map[header_size..], COMPRESSED_OUTPUT, &parameters)
         .expect("unable to write public key");
 
     writable_map.flush().expect("must flush a memory map");
@@ -157,3 +160,36 @@ pub fn contribute<T: Engine + Sync>(
         .expect("unable to write contribution hash");
     info!("Thank you for your participation, much appreciated! :)");
 }
+
+/// Same as `contribute`, except that the randomness of the contribution comes from the 32
+/// bytes returned by `entropy`, so that an embedder controls where it is acquired, e.g. from
+/// the entropy source of its own framework. The seed and beacon contributions of the CLI are
+/// such providers. The provider is called once, and its output must be high-entropy and kept
+/// secret, as anyone who learns it can recompute the toxic waste of the contribution.
+#[allow(clippy::too_many_arguments)]
+pub fn contribute_with_entropy<T: Engine + Sync>(
+    challenge_filename: &str,
+    challenge_hash_filename: &str,
+    response_filename: &str,
+    response_hash_filename: &str,
+    check_input_correctness: CheckForCorrectness,
+    batch_exp_mode: BatchExpMode,
+    framed_for_curve: Option<&CurveKind>,
+    parameters: &Phase1Parameters<T>,
+    entropy: impl FnOnce() -> [u8; 32],
+) {
+    let mut entropy = entropy();
+    let rng = rng_from_entropy(entropy);
+    entropy.zeroize();
+    contribute(
+        challenge_filename,
+        challenge_hash_filename,
+        response_filename,
+        response_hash_filename,
+        check_input_correctness,
+        batch_exp_mode,
+        framed_for_curve,
+        parameters,
+        rng,
+    );
+}
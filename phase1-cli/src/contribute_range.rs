@@ -0,0 +1,314 @@
+use crate::{map_input, verify_manifest::curve_name, Manifest, ManifestEntry};
+use phase1::{helpers::CurveKind, ContributionMode, Phase1, Phase1Parameters, ProvingSystem, PublicKey};
+use setup_utils::{
+    calculate_hash, print_hash, strict_correctness_check_config, BatchExpMode, CheckForCorrectness, SubgroupCheckMode,
+    UseCompression,
+};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use rand::Rng;
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    ops::Range,
+    path::Path,
+};
+use tracing::info;
+
+const COMPRESSED_INPUT: UseCompression = UseCompression::No;
+const COMPRESSED_OUTPUT: UseCompression = UseCompression::Yes;
+
+/// Parses a range of chunks such as `0..4`, whose end is exclusive
+pub fn chunk_range_from_str(src: &str) -> Result<Range<usize>, String> {
+    let mut bounds = src.splitn(2, "..");
+    let start = bounds.next().and_then(|start| start.trim().parse::<usize>().ok());
+    let end = bounds.next().and_then(|end| end.trim().parse::<usize>().ok());
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => Ok(start..end),
+        _ => Err("the chunk range should be a non empty range such as 0..4".to_string()),
+    }
+}
+
+fn num_chunks<T: Engine>(parameters: &Phase1Parameters<T>) -> usize {
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+    (upper_bound + parameters.chunk_size - 1) / parameters.chunk_size
+}
+
+/// Contributes to the chunks of `chunk_range` of a full challenge, writing the contribution
+/// to each chunk to `{prefix}_{chunk_index}`, so that a single contribution can be computed
+/// on several machines, each processing a disjoint range of chunks. `merge_ranges` then
+/// stitches the chunks back into a full response.
+///
+/// SECURITY: every machine must derive the same key, so they must all be given the same
+/// seed, and no other randomness is used. The seed is as sensitive as the key itself, so it
+/// must only be shared between machines controlled by the contributor, over secure channels.
+#[allow(clippy::too_many_arguments)]
+pub fn contribute_range<T: Engine + Sync>(
+    challenge_filename: &str,
+    response_prefix: &str,
+    chunk_range: Range<usize>,
+    check_input_correctness: CheckForCorrectness,
+    batch_exp_mode: BatchExpMode,
+    parameters: &Phase1Parameters<T>,
+    rng: impl Rng,
+) {
+    contribute_to_chunks(
+        challenge_filename,
+        response_prefix,
+        Some(chunk_range),
+        check_input_correctness,
+        batch_exp_mode,
+        parameters,
+        rng,
+    );
+
+    info!("Done! Merge the chunks of every machine with merge-ranges");
+}
+
+/// Contributes to every chunk of a full challenge, writing the contribution to each chunk
+/// to `{prefix}_{chunk_index}` as soon as it is computed, and freeing it before the next
+/// chunk. Only a single chunk is held at a time, so the scratch space needed is one chunk
+/// rather than a whole response. A `{prefix}_manifest.json` listing the chunk responses is
+/// written alongside, and `merge_ranges` stitches them back into a full response.
+pub fn contribute_per_chunk<T: Engine + Sync>(
+    challenge_filename: &str,
+    response_prefix: &str,
+    check_input_correctness: CheckForCorrectness,
+    batch_exp_mode: BatchExpMode,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+    rng: impl Rng,
+) {
+    let entries = contribute_to_chunks(
+        challenge_filename,
+        response_prefix,
+        None,
+        check_input_correctness,
+        batch_exp_mode,
+        parameters,
+        rng,
+    );
+
+    let manifest_filename = format!("{}_manifest.json", response_prefix);
+    let manifest = Manifest {
+        num_chunks: entries.len(),
+        curve: Some(curve_name(curve_kind).to_string()),
+        power: Some(parameters.total_size_in_log2),
+        chunks: entries,
+        combined_hash: None,
+    };
+    serde_json::to_writer_pretty(
+        File::create(&manifest_filename).expect("unable to create the manifest file"),
+        &manifest,
+    )
+    .expect("unable to write the manifest file");
+
+    info!(
+        "Done! The chunk responses are listed in {}, merge them with merge-ranges",
+        manifest_filename
+    );
+}
+
+/// Contributes to the chunks of `chunk_range`, or to every chunk if none is provided, one
+/// chunk at a time, and returns the manifest entries of the chunk responses written
+fn contribute_to_chunks<T: Engine + Sync>(
+    challenge_filename: &str,
+    response_prefix: &str,
+    chunk_range: Option<Range<usize>>,
+    check_input_correctness: CheckForCorrectness,
+    batch_exp_mode: BatchExpMode,
+    parameters: &Phase1Parameters<T>,
+    mut rng: impl Rng,
+) -> Vec<ManifestEntry> {
+    let parameters = parameters.into_chunk_parameters(ContributionMode::Chunked, 0, parameters.chunk_size);
+    let full_parameters = parameters.into_chunk_parameters(ContributionMode::Full, 0, 0);
+    let num_chunks = num_chunks(&parameters);
+    let chunk_range = chunk_range.unwrap_or(0..num_chunks);
+    if chunk_range.end > num_chunks {
+        panic!(
+            "The chunk range {:?} is out of range for {} chunks",
+            chunk_range, num_chunks
+        );
+    }
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(challenge_filename)
+        .expect("unable open challenge file");
+    let challenge = map_input(&reader).expect("unable to create a memory map for input");
+    if challenge.len() != full_parameters.accumulator_size {
+        panic!(
+            "The size of challenge file should be {}, but it's {}, so something isn't right.",
+            full_parameters.accumulator_size,
+            challenge.len()
+        );
+    }
+
+    let challenge_hash = calculate_hash(&challenge);
+    info!("`challenge` file contains decompressed points and has a hash:");
+    print_hash(&challenge_hash);
+
+    // The key is bound to the full challenge, so that the merged response verifies as a whole
+    let (public_key, private_key) =
+        Phase1::key_generation(&mut rng, challenge_hash.as_slice()).expect("could not generate keypair");
+
+    chunk_range
+        .map(|chunk_index| {
+            info!("Computing your contribution to chunk {}...", chunk_index);
+
+            // Only the chunk being contributed to is split out of the challenge
+            let chunk_parameters =
+                parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, parameters.chunk_size);
+            let mut chunk_input = vec![0; chunk_parameters.accumulator_size];
+            Phase1::split_from(
+                chunk_index,
+                (&challenge, COMPRESSED_INPUT),
+                vec![(chunk_input.as_mut_slice(), COMPRESSED_INPUT)],
+                &parameters,
+            )
+            .expect("unable to split the chunk out of the challenge");
+
+            let mut chunk_output = vec![0; chunk_parameters.contribution_size];
+            chunk_output[..chunk_parameters.hash_size].copy_from_slice(challenge_hash.as_slice());
+            Phase1::computation(
+                &chunk_input,
+                &mut chunk_output,
+                COMPRESSED_INPUT,
+                COMPRESSED_OUTPUT,
+                check_input_correctness,
+                batch_exp_mode,
+                &private_key,
+                &chunk_parameters,
+            )
+            .expect("must contribute with the key");
+            public_key
+                .write(&mut chunk_output, COMPRESSED_OUTPUT, &chunk_parameters)
+                .expect("unable to write public key");
+
+            let filename = format!("{}_{}", response_prefix, chunk_index);
+            File::create(&filename)
+                .expect("unable to create the chunk response file")
+                .write_all(&chunk_output)
+                .expect("unable to write the chunk response file");
+
+            // The manifest lists the chunk files relative to its own directory
+            let relative_filename = Path::new(&filename)
+                .file_name()
+                .expect("the response prefix must end with a file name")
+                .to_string_lossy()
+                .to_string();
+            ManifestEntry {
+                chunk_index,
+                filename: relative_filename,
+                hash: hex::encode(calculate_hash(&chunk_output)),
+            }
+        })
+        .collect()
+}
+
+/// Merges the `{prefix}_{chunk_index}` responses of every chunk, as produced by
+/// `contribute_range`, into a full response, and verifies it as a whole against the
+/// challenge before writing it and its hash.
+pub fn merge_ranges<T: Engine + Sync>(
+    challenge_filename: &str,
+    response_prefix: &str,
+    response_filename: &str,
+    response_hash_filename: &str,
+    parameters: &Phase1Parameters<T>,
+) {
+    let parameters = parameters.into_chunk_parameters(ContributionMode::Chunked, 0, parameters.chunk_size);
+    let full_parameters = parameters.into_chunk_parameters(ContributionMode::Full, 0, 0);
+    let num_chunks = num_chunks(&parameters);
+    info!("Will merge the responses to {} chunks", num_chunks);
+
+    let chunk_outputs = (0..num_chunks)
+        .map(|chunk_index| {
+            let chunk_filename = format!("{}_{}", response_prefix, chunk_index);
+            let chunk_output = std::fs::read(&chunk_filename).expect("unable to read the chunk response file");
+            let chunk_parameters =
+                parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, parameters.chunk_size);
+            if chunk_output.len() != chunk_parameters.contribution_size {
+                panic!(
+                    "The size of {} should be {}, but it's {}, so something isn't right.",
+                    chunk_filename,
+                    chunk_parameters.contribution_size,
+                    chunk_output.len()
+                );
+            }
+            let public_key = PublicKey::<T>::read(&chunk_output, COMPRESSED_OUTPUT, &chunk_parameters)
+                .expect("wasn't able to deserialize the chunk response's public key");
+            (chunk_output, public_key)
+        })
+        .collect::<Vec<_>>();
+
+    let public_key = &chunk_outputs[0].1;
+    if let Some(chunk_index) = chunk_outputs.iter().position(|(_, key)| key != public_key) {
+        panic!(
+            "Chunk {} was contributed to with a different key than chunk 0, every machine must use the same seed",
+            chunk_index
+        );
+    }
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(challenge_filename)
+        .expect("unable open challenge file");
+    let challenge = map_input(&reader).expect("unable to create a memory map for input");
+    if challenge.len() != full_parameters.accumulator_size {
+        panic!(
+            "The size of challenge file should be {}, but it's {}, so something isn't right.",
+            full_parameters.accumulator_size,
+            challenge.len()
+        );
+    }
+    let challenge_hash = calculate_hash(&challenge);
+
+    let mut response = vec![0; full_parameters.contribution_size];
+    response[..full_parameters.hash_size].copy_from_slice(challenge_hash.as_slice());
+    let inputs = chunk_outputs
+        .iter()
+        .map(|(chunk_output, _)| (chunk_output.as_slice(), COMPRESSED_OUTPUT))
+        .collect::<Vec<_>>();
+    Phase1::aggregation(&inputs, (&mut response, COMPRESSED_OUTPUT), &parameters)
+        .expect("unable to merge the chunk responses");
+    public_key
+        .write(&mut response, COMPRESSED_OUTPUT, &full_parameters)
+        .expect("unable to write public key");
+
+    info!("Verifying the merged response as a whole...");
+    let mut new_challenge = vec![0; full_parameters.accumulator_size];
+    let res = Phase1::verification(
+        &challenge,
+        &response,
+        &mut new_challenge,
+        public_key,
+        challenge_hash.as_slice(),
+        COMPRESSED_INPUT,
+        COMPRESSED_OUTPUT,
+        UseCompression::No,
+        strict_correctness_check_config(CheckForCorrectness::No, parameters.strict_correctness_checks),
+        CheckForCorrectness::Full,
+        SubgroupCheckMode::Auto,
+        &full_parameters,
+    );
+    if let Err(e) = res {
+        info!("Verification failed: {}", e);
+        panic!("INVALID MERGED CONTRIBUTION!!!");
+    }
+
+    std::fs::File::create(response_filename)
+        .expect("unable to create response file")
+        .write_all(&response)
+        .expect("unable to write response file");
+    let response_hash = calculate_hash(&response);
+    info!("The merged response is valid, and its BLAKE2b hash is:");
+    print_hash(&response_hash);
+    std::fs::File::create(response_hash_filename)
+        .expect("unable to open contribution hash file")
+        .write_all(response_hash.as_slice())
+        .expect("unable to write contribution hash");
+}
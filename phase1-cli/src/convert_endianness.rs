@@ -0,0 +1,53 @@
+use crate::map_input;
+use phase1::{Phase1Parameters, ResponseHeader, HEADER_SIZE};
+use setup_utils::{buffer_size, calculate_hash, convert_endianness, print_hash, Endianness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+};
+use tracing::info;
+
+/// Converts the elements of a challenge or response file between the canonical little endian
+/// byte order and `endianness`, e.g. to read files produced by tools which use big endian.
+/// The header of framed files and the hash of the previous contribution are copied as-is.
+pub fn convert_endianness_file<T: Engine>(
+    input_filename: &str,
+    output_filename: &str,
+    endianness: Endianness,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!("Will convert {} to {:?} endian", input_filename, endianness);
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(input_filename)
+        .expect("unable open input file in this directory");
+    let readable_map = map_input(&reader).expect("unable to create a memory map for input");
+
+    let mut output = readable_map.to_vec();
+    let header_size = match ResponseHeader::read(&output).expect("invalid response header") {
+        Some(_) => HEADER_SIZE,
+        None => 0,
+    };
+
+    // Every element, including those of the public key, is made of base field coordinates,
+    // which are as large as a compressed G1 element
+    let coordinate_size = buffer_size::<T::G1Affine>(UseCompression::Yes);
+    convert_endianness(
+        &mut output[header_size + parameters.hash_size..],
+        coordinate_size,
+        endianness,
+    )
+    .expect("the file does not consist of whole coordinates");
+
+    File::create(output_filename)
+        .expect("unable to create the output file")
+        .write_all(&output)
+        .expect("unable to write the output file");
+
+    info!("Hash of the converted file:");
+    print_hash(&calculate_hash(&output));
+}
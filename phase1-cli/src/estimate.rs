@@ -0,0 +1,75 @@
+use phase1::{ContributionMode, Phase1, Phase1Parameters, ProvingSystem};
+use setup_utils::{blank_hash, BatchExpMode, CheckForCorrectness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use rand::Rng;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const COMPRESSED_INPUT: UseCompression = UseCompression::No;
+const COMPRESSED_OUTPUT: UseCompression = UseCompression::Yes;
+
+/// Estimates how long contributing with the provided parameters takes on this machine, by
+/// contributing to the first `sample_batches` batches of a fresh accumulator and extrapolating
+/// the measured throughput. The first batches contain every section of the accumulator, so
+/// the estimate errs on the side of overestimating for the later chunks of Groth16.
+pub fn estimate<T: Engine + Sync>(
+    sample_batches: usize,
+    check_input_correctness: CheckForCorrectness,
+    batch_exp_mode: BatchExpMode,
+    parameters: &Phase1Parameters<T>,
+    mut rng: impl Rng,
+) {
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+    let total_elements = match parameters.contribution_mode {
+        ContributionMode::Chunked => {
+            let start = parameters.chunk_index * parameters.chunk_size;
+            std::cmp::min(start + parameters.chunk_size, upper_bound) - start
+        }
+        ContributionMode::Full => upper_bound,
+    };
+    let sample_elements = std::cmp::min(sample_batches * parameters.batch_size, total_elements);
+
+    info!(
+        "Will estimate the contribution time from a sample of {} out of {} elements",
+        sample_elements, total_elements
+    );
+
+    let sample_parameters = parameters.into_chunk_parameters(ContributionMode::Chunked, 0, sample_elements);
+    let mut input = vec![0; sample_parameters.get_length(COMPRESSED_INPUT)];
+    Phase1::initialization(&mut input, COMPRESSED_INPUT, &sample_parameters)
+        .expect("generation of initial accumulator is successful");
+    let mut output = vec![0; sample_parameters.get_length(COMPRESSED_OUTPUT)];
+
+    let (_, private_key) = Phase1::key_generation(&mut rng, blank_hash().as_ref()).expect("could not generate keypair");
+
+    let now = Instant::now();
+    Phase1::computation(
+        &input,
+        &mut output,
+        COMPRESSED_INPUT,
+        COMPRESSED_OUTPUT,
+        check_input_correctness,
+        batch_exp_mode,
+        &private_key,
+        &sample_parameters,
+    )
+    .expect("must contribute to the sample with the key");
+    let elapsed = now.elapsed();
+
+    let estimate = Duration::from_secs_f64(elapsed.as_secs_f64() * total_elements as f64 / sample_elements as f64);
+    info!(
+        "Contributed to the sample of {} elements in {:?} ({:.1} elements/s)",
+        sample_elements,
+        elapsed,
+        sample_elements as f64 / elapsed.as_secs_f64()
+    );
+    info!(
+        "ESTIMATE: contributing to {} elements should take about {:?} on this machine",
+        total_elements, estimate
+    );
+}
@@ -0,0 +1,72 @@
+use crate::map_input;
+use phase1::{Phase1, Phase1Parameters, ResponseHeader, HEADER_SIZE};
+use setup_utils::{calculate_hash, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+};
+use tracing::info;
+
+const FINGERPRINTS_CSV_HEADER: &str = "section,index,hash";
+
+/// The number of leading bytes of the hash of each element which are exported
+const FINGERPRINT_SIZE: usize = 8;
+
+/// Writes a `section,index,hash` CSV row with a short hash of every element of a challenge
+/// or response, so that auditors can spot-check elements across files without comparing
+/// the elements themselves. The hashes are over the serialized elements, so files must
+/// use the same compression for their fingerprints to be comparable. The input is memory
+/// mapped and the rows are written as they are computed, so memory usage stays bounded.
+pub fn export_fingerprints<T: Engine + Sync>(
+    input_filename: &str,
+    output_filename: &str,
+    compressed: UseCompression,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!(
+        "Will export the fingerprints of {} to {}",
+        input_filename, output_filename
+    );
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(input_filename)
+        .expect("unable open input file in this directory");
+    let readable_map = map_input(&reader).expect("unable to create a memory map for input");
+    let input = match ResponseHeader::read(&readable_map).expect("invalid response header") {
+        Some(_) => &readable_map[HEADER_SIZE..],
+        None => &readable_map[..],
+    };
+    // Responses additionally end with the public key, which is not fingerprinted
+    let expected_length = parameters.get_length(compressed);
+    if input.len() < expected_length {
+        panic!(
+            "The size of {} should be at least {}, but it's {}, so something isn't right.",
+            input_filename,
+            expected_length,
+            input.len()
+        );
+    }
+
+    let mut writer = BufWriter::new(File::create(output_filename).expect("unable to create the fingerprints file"));
+    writeln!(writer, "{}", FINGERPRINTS_CSV_HEADER).expect("unable to write the fingerprints file");
+
+    let mut num_elements = 0;
+    Phase1::for_each_element(input, compressed, parameters, |element_type, index, element| {
+        let hash = calculate_hash(element);
+        let fingerprint = hash[..FINGERPRINT_SIZE]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        writeln!(writer, "{},{},{}", element_type, index, fingerprint).expect("unable to write the fingerprints file");
+        num_elements += 1;
+        Ok(())
+    })
+    .expect("unable to read the elements of the input file");
+    writer.flush().expect("unable to write the fingerprints file");
+
+    info!("Exported the fingerprints of {} elements", num_elements);
+}
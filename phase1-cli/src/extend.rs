@@ -0,0 +1,105 @@
+use crate::{map_input, LocalFile, Storage};
+use phase1::{Phase1, Phase1Parameters, ProvingSystem};
+use setup_utils::{calculate_hash, print_hash, CheckForCorrectness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::{fs::OpenOptions, io::Write};
+use tracing::{info, warn};
+
+const COMPRESSED_ACCUMULATOR: UseCompression = UseCompression::No;
+
+/// Extends a full accumulator of 2^`power` powers of tau to 2^(`power` + 1) powers, so that a
+/// ceremony can grow to support larger circuits without starting over. The existing elements
+/// are copied to the start of each section, and the new high order elements are set to the
+/// generators, like in a fresh accumulator.
+///
+/// SECURITY: the new elements are not powers of the tau accumulated so far, so none of the
+/// previous contributions protect them. Contributions to the extended accumulator must be
+/// verified against it as a new starting point, and the new powers are only as secure as
+/// the contributions made after the extension.
+pub fn extend<T: Engine + Sync>(
+    input_filename: &str,
+    output_filename: &str,
+    output_hash_filename: &str,
+    check_input_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    if parameters.proving_system != ProvingSystem::Groth16 {
+        panic!("Only Groth16 accumulators can be extended, the Marlin layout depends on the power");
+    }
+    let parameters = Phase1Parameters::<T>::new_full(
+        parameters.proving_system,
+        parameters.total_size_in_log2,
+        parameters.batch_size,
+    );
+    let extended_parameters = Phase1Parameters::<T>::new_full(
+        parameters.proving_system,
+        parameters.total_size_in_log2 + 1,
+        parameters.batch_size,
+    );
+
+    info!(
+        "Will extend the accumulator in {} from 2^{} to 2^{} powers of tau",
+        input_filename, parameters.total_size_in_log2, extended_parameters.total_size_in_log2
+    );
+    warn!("The new powers are not protected by any previous contribution and need fresh contributions");
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(input_filename)
+        .expect("unable open accumulator file in this directory");
+    let input = map_input(&reader).expect("unable to create a memory map for input");
+    if input.len() != parameters.accumulator_size {
+        panic!(
+            "The size of the accumulator file should be {}, but it's {}, so something isn't right.",
+            parameters.accumulator_size,
+            input.len()
+        );
+    }
+    let previous = Phase1::deserialize(&input, COMPRESSED_ACCUMULATOR, check_input_correctness, &parameters)
+        .expect("unable to read the accumulator");
+
+    // Start from a fresh accumulator, which has generators everywhere
+    let mut output = vec![0; extended_parameters.accumulator_size];
+    Phase1::initialization(&mut output, COMPRESSED_ACCUMULATOR, &extended_parameters)
+        .expect("generation of initial accumulator is successful");
+    let mut extended = Phase1::deserialize(
+        &output,
+        COMPRESSED_ACCUMULATOR,
+        CheckForCorrectness::No,
+        &extended_parameters,
+    )
+    .expect("unable to read the initial accumulator");
+
+    let tau_g1_length = previous.tau_powers_g1.len();
+    let other_length = previous.tau_powers_g2.len();
+    extended.tau_powers_g1[..tau_g1_length].copy_from_slice(&previous.tau_powers_g1);
+    extended.tau_powers_g2[..other_length].copy_from_slice(&previous.tau_powers_g2);
+    extended.alpha_tau_powers_g1[..other_length].copy_from_slice(&previous.alpha_tau_powers_g1);
+    extended.beta_tau_powers_g1[..other_length].copy_from_slice(&previous.beta_tau_powers_g1);
+    extended.beta_g2 = previous.beta_g2;
+
+    // The extended accumulator is chained to the one it extends
+    let input_hash = calculate_hash(&input);
+    (&mut output[..extended_parameters.hash_size])
+        .write_all(input_hash.as_slice())
+        .expect("unable to write the hash of the extended accumulator");
+    extended
+        .serialize(&mut output, COMPRESSED_ACCUMULATOR, &extended_parameters)
+        .expect("unable to write the extended accumulator");
+
+    let mut storage = LocalFile::create_new(output_filename).expect("unable to create the extended accumulator file");
+    storage
+        .write_range(0, &output)
+        .expect("unable to write the extended accumulator to storage");
+
+    let output_hash = calculate_hash(&output);
+    std::fs::File::create(output_hash_filename)
+        .expect("unable to open the extended accumulator hash file")
+        .write_all(output_hash.as_slice())
+        .expect("unable to write the extended accumulator hash");
+
+    info!("Extended accumulator is formed with a hash:");
+    print_hash(&output_hash);
+}
@@ -0,0 +1,61 @@
+use crate::map_input;
+use phase1::{helpers::CurveKind, Phase1Parameters, PublicKey, ResponseHeader};
+use setup_utils::{calculate_hash, print_hash, UseCompression};
+
+use zexe_algebra::{CanonicalSerialize, PairingEngine as Engine};
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+};
+use tracing::info;
+
+/// Extracts the public key written at the end of a response, without verifying the
+/// response, so that the keys of the contributors can be collected into a transcript. The
+/// points of the key are checked to be valid, and the key is written to `public_key_filename`
+/// in the encoding `verify-my-contribution` expects of a published key, then its hash printed.
+pub fn extract_public_key<T: Engine + Sync>(
+    response_filename: &str,
+    public_key_filename: &str,
+    compressed: UseCompression,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!("Extracting the public key of {}", response_filename);
+
+    let response_reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+    let response_readable_map = map_input(&response_reader).expect("unable to create a memory map for the response");
+
+    let expected_header = ResponseHeader::new(curve_kind, compressed, parameters);
+    let response = ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+    let expected_length = parameters.get_response_length(compressed);
+    if response.len() != expected_length {
+        panic!(
+            "The size of response file should be {}, but it's {}, so something isn't right.",
+            expected_length,
+            response.len()
+        );
+    }
+
+    let public_key = PublicKey::<T>::read(response, compressed, parameters)
+        .expect("wasn't able to deserialize the response file's public key");
+    public_key
+        .validate()
+        .expect("the public key of the response has invalid points");
+
+    let mut public_key_bytes = vec![];
+    public_key
+        .serialize(&mut public_key_bytes)
+        .expect("unable to serialize the public key");
+    File::create(public_key_filename)
+        .expect("unable to create the public key file")
+        .write_all(&public_key_bytes)
+        .expect("unable to write the public key");
+
+    info!("The public key is {}", hex::encode(&public_key_bytes));
+    info!("Wrote the public key to {}, its BLAKE2b hash is:", public_key_filename);
+    print_hash(&calculate_hash(&public_key_bytes));
+}
@@ -0,0 +1,36 @@
+use crate::{LocalFile, Storage};
+use phase1::{Phase1, Phase1Parameters};
+use setup_utils::{calculate_hash, print_hash, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use tracing::info;
+
+const COMPRESS_ACCUMULATOR: UseCompression = UseCompression::No;
+
+/// Writes the initial accumulator, which contains the generators everywhere, to a file.
+/// Unlike `new_challenge`, the leading hash of the previous contribution is omitted and
+/// no hash file is written, so the file only depends on the parameters and anyone can
+/// reproduce it independently.
+pub fn init<T: Engine + Sync>(output_filename: &str, parameters: &Phase1Parameters<T>) {
+    info!(
+        "Will generate the initial accumulator for 2^{} powers of tau",
+        parameters.total_size_in_log2
+    );
+
+    let mut accumulator = vec![0; parameters.accumulator_size];
+    Phase1::initialization(&mut accumulator, COMPRESS_ACCUMULATOR, parameters)
+        .expect("generation of initial accumulator is successful");
+    let accumulator = &accumulator[parameters.hash_size..];
+
+    let mut storage = LocalFile::create_new(output_filename).expect("unable to create the accumulator file");
+    storage
+        .write_range(0, accumulator)
+        .expect("unable to write the accumulator to storage");
+
+    let hash = calculate_hash(accumulator);
+    info!("Initial accumulator is formed with a hash:");
+    print_hash(&hash);
+
+    info!("Wrote the initial accumulator to {}", output_filename);
+}
@@ -0,0 +1,135 @@
+use crate::map_input;
+use phase1::{ContributionMode, Phase1, Phase1Parameters, ResponseHeader, HEADER_SIZE};
+use setup_utils::{buffer_size, convert_endianness, CheckForCorrectness, Endianness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::fs::OpenOptions;
+use tracing::info;
+
+/// The documented differences between the layout of the accumulators written by this
+/// implementation and by a reference implementation. They are normalized away before
+/// comparing, so that only differences in the elements themselves are reported.
+#[derive(Debug, Clone, Copy)]
+pub struct ReferenceFormat {
+    /// The byte order of the base field coordinates of the reference
+    pub endianness: Endianness,
+    /// Whether the reference starts with the hash of the previous contribution
+    pub with_hash: bool,
+}
+
+/// Checks that re-serializing the accumulator of `accumulator_filename` yields exactly the
+/// reference accumulator of another implementation, once the reference is normalized to the
+/// layout of this implementation: its header is skipped, its coordinates are converted to
+/// little endian, and the hash is only compared if the reference has one. The accumulator
+/// is expected to have been verified already. The first divergence is reported with the
+/// element it falls in.
+pub fn interop_check<T: Engine + Sync>(
+    accumulator_filename: &str,
+    reference_filename: &str,
+    reference_format: ReferenceFormat,
+    compressed: UseCompression,
+    check_input_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!(
+        "Will check the accumulator of {} against the reference {}",
+        accumulator_filename, reference_filename
+    );
+
+    let parameters = parameters.into_chunk_parameters(ContributionMode::Full, 0, 0);
+    let length = parameters.get_length(compressed);
+
+    let accumulator_reader = OpenOptions::new()
+        .read(true)
+        .open(accumulator_filename)
+        .expect("unable open accumulator file in this directory");
+    let accumulator_map = map_input(&accumulator_reader).expect("unable to create a memory map for input");
+    let accumulator = skip_header(&accumulator_map);
+    // Responses additionally end with the public key, which is not part of the accumulator
+    if accumulator.len() < length {
+        panic!(
+            "The size of {} should be at least {}, but it's {}, so something isn't right.",
+            accumulator_filename,
+            length,
+            accumulator.len()
+        );
+    }
+    let accumulator = &accumulator[..length];
+
+    info!("Re-serializing the accumulator...");
+    let mut reserialized = vec![0; length];
+    reserialized[..parameters.hash_size].copy_from_slice(&accumulator[..parameters.hash_size]);
+    Phase1::deserialize(accumulator, compressed, check_input_correctness, &parameters)
+        .and_then(|deserialized| deserialized.serialize(&mut reserialized, compressed, &parameters))
+        .expect("unable to re-serialize the accumulator");
+
+    let reference_reader = OpenOptions::new()
+        .read(true)
+        .open(reference_filename)
+        .expect("unable open reference file in this directory");
+    let reference_map = map_input(&reference_reader).expect("unable to create a memory map for the reference");
+    let reference = normalize_reference::<T>(skip_header(&reference_map), reference_format, parameters.hash_size);
+
+    // Without a hash in the reference, only the elements are compared
+    let offset = if reference_format.with_hash {
+        0
+    } else {
+        parameters.hash_size
+    };
+    let ours = &reserialized[offset..];
+    if ours.len() != reference.len() {
+        panic!(
+            "The normalized reference should be {} bytes long, but it's {} bytes long",
+            ours.len(),
+            reference.len()
+        );
+    }
+
+    if let Some(position) = ours.iter().zip(&reference).position(|(a, b)| a != b) {
+        let position = offset + position;
+        let mut element = None;
+        Phase1::for_each_element(&reserialized, compressed, &parameters, |element_type, index, bytes| {
+            let start = bytes.as_ptr() as usize - reserialized.as_ptr() as usize;
+            if element.is_none() && (start..start + bytes.len()).contains(&position) {
+                element = Some(format!("{}[{}]", element_type, index));
+            }
+            Ok(())
+        })
+        .expect("unable to iterate over the elements");
+        panic!(
+            "The re-serialized accumulator and the normalized reference diverge at byte {} ({}): {:02x} != {:02x}",
+            position,
+            element.unwrap_or_else(|| "the hash".to_string()),
+            reserialized[position],
+            reference[position - offset]
+        );
+    }
+
+    info!("The re-serialized accumulator is identical to the normalized reference");
+}
+
+fn skip_header(input: &[u8]) -> &[u8] {
+    match ResponseHeader::read(input).expect("invalid response header") {
+        Some(_) => &input[HEADER_SIZE..],
+        None => input,
+    }
+}
+
+/// Rewrites the reference in the layout of this implementation, apart from a missing hash
+fn normalize_reference<T: Engine>(reference: &[u8], reference_format: ReferenceFormat, hash_size: usize) -> Vec<u8> {
+    let elements_start = if reference_format.with_hash { hash_size } else { 0 };
+    if reference.len() < elements_start {
+        panic!("The reference is shorter than the hash it should start with");
+    }
+    let mut normalized = reference.to_vec();
+    // Every element is made of base field coordinates, which are as large as a compressed G1 element
+    let coordinate_size = buffer_size::<T::G1Affine>(UseCompression::Yes);
+    convert_endianness(
+        &mut normalized[elements_start..],
+        coordinate_size,
+        reference_format.endianness,
+    )
+    .expect("the reference does not consist of whole coordinates");
+    normalized
+}
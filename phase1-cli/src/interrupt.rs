@@ -0,0 +1,54 @@
+use std::{
+    fs,
+    path::Path,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tracing::{info, warn};
+
+/// The conventional exit code of a process interrupted by SIGINT
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Removes an output file if the process is interrupted before `finish` is called, so that
+/// a half written file does not linger around and fail size checks later. Files which
+/// already existed when the handler was installed are left alone.
+pub struct InterruptCleanup {
+    finished: Arc<AtomicBool>,
+}
+
+impl InterruptCleanup {
+    /// Installs the interrupt handler for the output file. Only one handler can be
+    /// installed per process.
+    pub fn install(filename: &str) -> Self {
+        let finished = Arc::new(AtomicBool::new(false));
+        let preexisting = Path::new(filename).exists();
+
+        let handler_finished = finished.clone();
+        let filename = filename.to_string();
+        let res = ctrlc::set_handler(move || {
+            if !preexisting && !handler_finished.load(Ordering::SeqCst) {
+                // The output may be in the middle of being encoded
+                for partial in &[filename.clone(), format!("{}.encoding", filename)] {
+                    if fs::remove_file(partial).is_ok() {
+                        info!("Removed the partially written {}", partial);
+                    }
+                }
+            }
+            info!("Interrupted, exiting");
+            process::exit(INTERRUPTED_EXIT_CODE);
+        });
+        if let Err(e) = res {
+            warn!("Unable to install the interrupt handler: {}", e);
+        }
+
+        Self { finished }
+    }
+
+    /// Marks the output file as complete, so that it is kept on interrupt
+    pub fn finish(self) {
+        self.finished.store(true, Ordering::SeqCst);
+    }
+}
@@ -2,17 +2,107 @@
 #![cfg_attr(nightly, feature(doc_cfg, external_doc))]
 #![cfg_attr(nightly, doc(include = "../README.md"))]
 
+mod bench_read;
+pub use bench_read::bench_read;
+
+mod bench_verify;
+pub use bench_verify::bench_verify;
+
+mod chunk_names;
+pub use chunk_names::chunk_filenames;
+
+mod chunk_map;
+pub use chunk_map::chunk_map;
+
+mod codec;
+pub use codec::{
+    encode_file, is_encoded, map_input, open_input, output_codec_from_str, DecodedFile, InputFile, InputMap,
+    OutputCodec,
+};
+
 mod combine;
-pub use combine::combine;
+pub use combine::{
+    combine, combine_files, combine_files_with_compression, combine_storage, discover_chunk_files, sort_by_hash,
+    DETERMINISTIC_VERIFICATION_SEED,
+};
+
+mod compare_combined;
+pub use compare_combined::compare_combined;
+
+mod contribute_range;
+pub use contribute_range::{chunk_range_from_str, contribute_per_chunk, contribute_range, merge_ranges};
+
+mod convert_endianness;
+pub use convert_endianness::convert_endianness_file;
 
 mod contribute;
-pub use contribute::contribute;
+pub use contribute::{contribute, contribute_with_entropy};
+
+mod estimate;
+pub use estimate::estimate;
+
+mod export_fingerprints;
+pub use export_fingerprints::export_fingerprints;
+
+mod extend;
+pub use extend::extend;
+
+mod extract_public_key;
+pub use extract_public_key::extract_public_key;
+
+mod init;
+pub use init::init;
+
+mod interop_check;
+pub use interop_check::{interop_check, ReferenceFormat};
+
+mod interrupt;
+pub use interrupt::InterruptCleanup;
+
+mod prepare_phase1;
+pub use prepare_phase1::prepare_phase1;
+
+mod receipt;
+pub use receipt::{verify_receipt, write_receipt, Receipt};
+
+mod rechunk;
+pub use rechunk::rechunk;
+
+mod seed;
+pub use seed::{
+    check_seed, decrypt_seed, encrypt_seed, encrypt_seed_file, load_seed, mix_token_entropy, SeedEntropy,
+    DEFAULT_MIN_SEED_LENGTH, SEED_ENV_VAR,
+};
+
+mod sha256;
+pub use sha256::write_sha256;
 
 mod split;
 pub use split::split;
 
+mod storage;
+pub use storage::{LocalFile, Storage};
+
 mod new_challenge;
-pub use new_challenge::new_challenge;
+pub use new_challenge::{new_challenge, write_new_challenge};
+
+mod new_and_beacon;
+pub use new_and_beacon::new_and_beacon;
+
+mod peek;
+pub use peek::peek;
+
+mod test_subgroup;
+pub use test_subgroup::test_subgroup;
+
+mod timings;
+pub use timings::spawn_timings_writer;
+
+mod transcript;
+pub use transcript::{
+    append_to_transcript, chain_hash, hash_algorithm_from_str, resign_transcript, verify_transcript_chain,
+    HashAlgorithm, Transcript, TranscriptEntry,
+};
 
 mod transform_pok_and_correctness;
 pub use transform_pok_and_correctness::transform_pok_and_correctness;
@@ -20,23 +110,64 @@ pub use transform_pok_and_correctness::transform_pok_and_correctness;
 mod transform_ratios;
 pub use transform_ratios::transform_ratios;
 
+mod validate_generators;
+pub use validate_generators::validate_generators;
+
+mod validate_lengths;
+pub use validate_lengths::{validate_lengths, LengthEntry};
+
+mod verify_chain;
+pub use verify_chain::verify_chain;
+
+mod verify_compression;
+pub use verify_compression::verify_compression;
+
+mod verify_final;
+pub use verify_final::verify_final;
+
+mod verify_manifest;
+pub use verify_manifest::{manifest_chunk_files, verify_manifest, Manifest, ManifestEntry};
+
+mod verify_my_contribution;
+pub use verify_my_contribution::verify_my_contribution;
+
+mod verify_pok_only;
+pub use verify_pok_only::verify_pok_only;
+
 use phase1::{
     helpers::{
-        batch_exp_mode_from_str, contribution_mode_from_str, curve_from_str, proving_system_from_str,
-        subgroup_check_mode_from_str, CurveKind,
+        batch_exp_mode_from_str, contribution_mode_from_str, curve_from_str, element_type_from_str,
+        endianness_from_str, proving_system_from_str, section_mask_from_str, subgroup_check_mode_from_str,
+        use_compression_from_str, CurveKind,
     },
     ContributionMode, ProvingSystem,
 };
 
 use gumdrop::Options;
-use setup_utils::{BatchExpMode, SubgroupCheckMode};
-use std::default::Default;
+use setup_utils::{BatchExpMode, ElementType, Endianness, SectionMask, SubgroupCheckMode, UseCompression};
+use std::{default::Default, fmt};
 
-#[derive(Debug, Options, Clone)]
+#[derive(Options, Clone)]
 pub struct Phase1Opts {
     help: bool,
-    #[options(help = "the seed to derive private elements from")]
+    #[options(help = "the seed to derive private elements from (insecure if not a file, prefer --seed-file)")]
     pub seed: String,
+    #[options(help = "the file containing the hex encoded seed to derive private elements from")]
+    pub seed_file: String,
+    #[options(help = "the file containing the seed encrypted with encrypt-seed, whose passphrase is prompted for")]
+    pub encrypted_seed_file: String,
+    #[options(help = "the PKCS#11 library of a token to mix hardware entropy into the seed from (hsm feature)")]
+    pub pkcs11: String,
+    #[options(
+        help = "the minimum length of the seed in bytes, below which it is rejected as weak",
+        default = "32"
+    )]
+    pub min_seed_length: usize,
+    #[options(
+        help = "contribute with a seed which is too short or not random enough anyway",
+        default = "false"
+    )]
+    pub allow_weak_seed: bool,
     #[options(
         help = "the contribution mode",
         default = "chunked",
@@ -45,8 +176,8 @@ pub struct Phase1Opts {
     pub contribution_mode: ContributionMode,
     #[options(help = "the chunk index to process")]
     pub chunk_index: usize,
-    #[options(help = "the chunk size")]
-    pub chunk_size: usize,
+    #[options(help = "the chunk size in chunked mode, which defaults to a single chunk holding every power")]
+    pub chunk_size: Option<usize>,
     #[options(
         help = "the elliptic curve to use",
         default = "bls12_377",
@@ -82,6 +213,70 @@ pub struct Phase1Opts {
         parse(try_from_str = "subgroup_check_mode_from_str")
     )]
     pub subgroup_check_mode: SubgroupCheckMode,
+    #[options(help = "the number of milliseconds to sleep between batches", default = "0")]
+    pub throttle_ms: u64,
+    #[options(help = "the number of elements of a batch processed together, in parallel (defaults to batch size - 1)")]
+    pub parallel_grain: Option<usize>,
+    #[options(help = "the maximum number of sections verified at once, to bound memory (defaults to unlimited)")]
+    pub max_inflight: Option<usize>,
+    #[options(
+        help = "the number of idle buffers of each size kept for reuse when reading batches",
+        default = "0"
+    )]
+    pub buffer_pool_size: usize,
+    #[options(
+        help = "whether to write a header describing the parameters at the start of responses",
+        default = "false"
+    )]
+    pub framed: bool,
+    #[options(
+        help = "keep the partially written output of new and combine when interrupted",
+        default = "false"
+    )]
+    pub no_cleanup: bool,
+    #[options(
+        help = "fully check every element read, which is slower but recommended for untrusted inputs",
+        default = "false"
+    )]
+    pub strict: bool,
+    #[options(
+        help = "bind the proofs of knowledge of chunks to their index, contributors and verifiers must agree on it",
+        default = "false"
+    )]
+    pub bind_chunk_index: bool,
+}
+
+// The seed is redacted, so that the options can be logged without leaking the private elements
+impl fmt::Debug for Phase1Opts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Phase1Opts")
+            .field("seed", &if self.seed.is_empty() { "" } else { "<redacted>" })
+            .field("seed_file", &self.seed_file)
+            .field("encrypted_seed_file", &self.encrypted_seed_file)
+            .field("pkcs11", &self.pkcs11)
+            .field("min_seed_length", &self.min_seed_length)
+            .field("allow_weak_seed", &self.allow_weak_seed)
+            .field("contribution_mode", &self.contribution_mode)
+            .field("chunk_index", &self.chunk_index)
+            .field("chunk_size", &self.chunk_size)
+            .field("curve_kind", &self.curve_kind)
+            .field("proving_system", &self.proving_system)
+            .field("batch_size", &self.batch_size)
+            .field("power", &self.power)
+            .field("command", &self.command)
+            .field("force_correctness_checks", &self.force_correctness_checks)
+            .field("batch_exp_mode", &self.batch_exp_mode)
+            .field("subgroup_check_mode", &self.subgroup_check_mode)
+            .field("throttle_ms", &self.throttle_ms)
+            .field("parallel_grain", &self.parallel_grain)
+            .field("max_inflight", &self.max_inflight)
+            .field("buffer_pool_size", &self.buffer_pool_size)
+            .field("framed", &self.framed)
+            .field("no_cleanup", &self.no_cleanup)
+            .field("strict", &self.strict)
+            .field("bind_chunk_index", &self.bind_chunk_index)
+            .finish()
+    }
 }
 
 // The supported commands
@@ -90,6 +285,9 @@ pub enum Command {
     // this creates a new challenge
     #[options(help = "creates a new challenge for the ceremony")]
     New(NewOpts),
+    // this writes the initial accumulator, without the hash of a previous contribution
+    #[options(help = "writes the initial accumulator (generators everywhere) and prints its hash")]
+    Init(InitOpts),
     #[options(
         help = "contribute to ceremony by producing a response to a challenge (or create a new challenge if this is the first contribution)"
     )]
@@ -107,6 +305,93 @@ pub enum Command {
     Combine(CombineOpts),
     #[options(help = "receive a full contribution and splits it into chunks")]
     Split(SplitOpts),
+    // this creates a new challenge and applies a beacon to it, which is NOT secure.
+    #[options(help = "creates a new challenge and finalizes it with a beacon (INSECURE, for testing only)")]
+    NewAndBeacon(NewAndBeaconOpts),
+    // this receives a compressed and an uncompressed response and checks that they contain the same elements.
+    #[options(help = "verify that a compressed response decompresses to the provided uncompressed response")]
+    VerifyCompression(VerifyCompressionOpts),
+    // this recomputes the final hash of a combined file and checks it against the published one.
+    #[options(help = "verify that a combined file matches the published final hash")]
+    VerifyFinal(VerifyFinalOpts),
+    // this prints the element ranges covered by each chunk.
+    #[options(help = "list the element ranges each chunk covers for the given power and chunk size")]
+    ChunkMap(ChunkMapOpts),
+    // this receives a challenge + response file and verifies the response extends the challenge's hash.
+    #[options(help = "verify a response with the digest recomputed from its challenge, without a new challenge")]
+    VerifyChain(VerifyChainOpts),
+    // this receives two independently combined files and checks they are valid and identical.
+    #[options(help = "verify two independently combined files and check they hold the same accumulator")]
+    CompareCombined(CompareCombinedOpts),
+    // this contributes to a sample of batches and extrapolates the time of a full contribution.
+    #[options(help = "estimate the contribution time on this machine by contributing to a sample of batches")]
+    Estimate(EstimateOpts),
+    // this converts the byte order of the elements of a file, to interoperate with other tools.
+    #[options(help = "convert the elements of a challenge or response between little and big endian")]
+    ConvertEndianness(ConvertEndiannessOpts),
+    // this writes a short hash of every element of a file, for auditors to spot-check.
+    #[options(help = "export a CSV with a short hash of every element of a challenge or response")]
+    ExportFingerprints(ExportFingerprintsOpts),
+    // this grows a full accumulator to the next power, setting the new powers to the generators.
+    #[options(help = "extend a full Groth16 accumulator to the next power (the new powers need fresh contributions)")]
+    Extend(ExtendOpts),
+    // this checks the hashes of a downloaded set of chunk files against a manifest.
+    #[options(help = "verify that the chunk files listed in a manifest are complete and match their hashes")]
+    VerifyManifest(VerifyManifestOpts),
+    // this receives a response and the hash of its challenge, and only checks the proofs of knowledge.
+    #[options(help = "quickly check only the proofs of knowledge of a response (NOT a full verification)")]
+    VerifyPok(VerifyPokOpts),
+    // this receives a local response and its published transcript entry, and checks that they match.
+    #[options(help = "verify that a published hash and public key match your local response")]
+    VerifyMyContribution(VerifyMyContributionOpts),
+    // this encrypts a seed file under a passphrase, for use with --encrypted-seed-file.
+    #[options(help = "encrypt a seed file under a passphrase prompted for, for use with --encrypted-seed-file")]
+    EncryptSeed(EncryptSeedOpts),
+    // this checks only the lengths of the responses in a directory, as a cheap preflight.
+    #[options(help = "check that every response.<chunk index> file in a directory has the right length")]
+    ValidateLengths(ValidateLengthsOpts),
+    // this contributes to a range of chunks of a full challenge, to spread a contribution over machines.
+    #[options(help = "contribute to a range of chunks of a full challenge, with the same seed on every machine")]
+    ContributeRange(ContributeRangeOpts),
+    // this merges the chunks contributed to with contribute-range into a full response, and verifies it.
+    #[options(help = "merge the chunks contributed to with contribute-range into a full response and verify it")]
+    MergeRanges(MergeRangesOpts),
+    // this only checks that the first tau elements of a file are the generators.
+    #[options(help = "quickly check that the first TauG1 and TauG2 elements are the generators (NOT a verification)")]
+    ValidateGenerators(ValidateGeneratorsOpts),
+    // this times reading batches into new and into preallocated vectors, over random points.
+    #[options(help = "benchmark read_batch against read_batch_preallocated (needs the bench feature)")]
+    BenchRead(BenchReadOpts),
+    // this rewrites a combined accumulator into the coefficients phase 2 reads its phase 1 input from.
+    #[options(help = "convert a combined accumulator into the phase 1 input of phase 2, and check that it reads back")]
+    PreparePhase1(PreparePhase1Opts),
+    // this checks that the seed is long and random enough, as is done before contributing with it.
+    #[options(help = "check that the seed is long and random enough to contribute with, and report its entropy")]
+    CheckSeed(CheckSeedOpts),
+    // this extracts the public key of a response without verifying it, e.g. to build a transcript.
+    #[options(help = "extract and check the public key at the end of a response, and print its hash")]
+    ExtractPubkey(ExtractPubkeyOpts),
+    // this writes a receipt of a contribution, or reproduces the contribution of a receipt from the revealed seed.
+    #[options(help = "write a receipt from which a contribution can be reproduced, or verify one with the seed")]
+    Receipt(ReceiptOpts),
+    // this appends the contribution of a response to the transcript, extending its hash chain.
+    #[options(help = "append the hash and public key of a response to the transcript, and print its chain hash")]
+    AppendTranscript(AppendTranscriptOpts),
+    // this recomputes the hash chain of the transcript, to check that no entry was tampered with.
+    #[options(help = "verify the hash chain of the transcript against a published chain hash")]
+    VerifyTranscriptChain(VerifyTranscriptChainOpts),
+    // this recomputes the transcript under another hash algorithm, optionally signing it, e.g. to migrate its format.
+    #[options(help = "re-hash the transcript with another hash algorithm and optionally sign its entries")]
+    ResignTranscript(ResignTranscriptOpts),
+    // this checks the subgroup check against points known to be inside and outside the subgroup.
+    #[options(help = "check that the subgroup check rejects points outside the prime order subgroup, in G1 and G2")]
+    TestSubgroup(TestSubgroupOpts),
+    // this times the verification of a small contribution on every curve, to catch performance regressions.
+    #[options(help = "time the verification of a contribution to a small accumulator on every supported curve")]
+    BenchVerify(BenchVerifyOpts),
+    // this prints a single element of an accumulator, without reading the rest of the file.
+    #[options(help = "print the hex serialization of the element at an index of a section of a challenge or response")]
+    Peek(PeekOpts),
 }
 
 // Options for the Contribute command
@@ -117,6 +402,22 @@ pub struct NewOpts {
     pub challenge_fname: String,
     #[options(help = "the new challenge file hash", default = "challenge.verified.hash")]
     pub challenge_hash_fname: String,
+    #[options(
+        help = "the codec to wrap the challenge file with (none, gzip or zstd)",
+        default = "none",
+        parse(try_from_str = "output_codec_from_str")
+    )]
+    pub output_codec: OutputCodec,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct InitOpts {
+    help: bool,
+    #[options(
+        help = "the initial accumulator file name to be created",
+        default = "initial_accumulator"
+    )]
+    pub output_fname: String,
 }
 
 // Options for the Contribute command
@@ -136,6 +437,11 @@ pub struct ContributeOpts {
         default = "0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620"
     )]
     pub beacon_hash: String,
+    #[options(
+        help = "write the response to each chunk of a full challenge to <response>_<index> as soon as it is computed",
+        default = "false"
+    )]
+    pub per_chunk_output: bool,
 }
 
 #[derive(Debug, Options, Clone)]
@@ -159,6 +465,49 @@ pub struct VerifyPokAndCorrectnessOpts {
         default = "new_challenge.verified.hash"
     )]
     pub new_challenge_hash_fname: String,
+    #[options(
+        help = "only warn on subgroup check failures, to audit legacy files (NOT a full-security verification)",
+        default = "false"
+    )]
+    pub continue_on_subgroup_warning: bool,
+    #[options(help = "a CSV file to write the timing of each verified batch to")]
+    pub timings_csv: Option<String>,
+    #[options(help = "a file caching the ranges of elements which already passed the subgroup check")]
+    pub validation_cache: Option<String>,
+    #[options(
+        help = "whether the provided challenge was written compressed (yes or no)",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub challenge_compressed: UseCompression,
+    #[options(
+        help = "whether the provided response was written compressed (yes or no)",
+        default = "yes",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub response_compressed: UseCompression,
+    #[options(help = "the hex encoded hash the response is expected to have, checked before any other work")]
+    pub expected_response_hash: Option<String>,
+    #[options(
+        help = "hash the response on a background thread while it is verified, rather than before",
+        default = "false"
+    )]
+    pub parallel_hash: bool,
+    #[options(
+        help = "reject a contribution with a secret of 1, which adds no randomness, instead of only warning",
+        default = "false"
+    )]
+    pub reject_identity: bool,
+    #[options(
+        help = "also write the SHA-256 digest of the challenge, response and new challenge to .sha256 files",
+        default = "false"
+    )]
+    pub sha256: bool,
+    #[options(
+        help = "verify one batch at a time, with memory bounded by the batch size rather than the power (Groth16 only)",
+        default = "false"
+    )]
+    pub low_memory: bool,
 }
 
 #[derive(Debug, Options, Clone)]
@@ -166,15 +515,98 @@ pub struct VerifyRatiosOpts {
     help: bool,
     #[options(help = "the provided response file which will be verified", default = "response")]
     pub response_fname: String,
+    #[options(
+        help = "map only the current batch of the response in memory, to verify with bounded memory (Groth16 only)",
+        default = "false"
+    )]
+    pub mmap_window: bool,
+    #[options(
+        help = "the comma separated sections whose ratios are verified, e.g. tau_g1,alpha_g1",
+        default = "all",
+        parse(try_from_str = "section_mask_from_str")
+    )]
+    pub sections: SectionMask,
+    #[options(
+        help = "verify only the expected prefix of a response which is larger than expected, instead of failing",
+        default = "false"
+    )]
+    pub truncate_extra: bool,
+    #[options(
+        help = "verify a compressed response, decompressing each batch in memory instead of to a file",
+        default = "false"
+    )]
+    pub decompress_verify: bool,
+    #[options(help = "a reference accumulator of another implementation, which the re-serialized one must match")]
+    pub interop_check: Option<String>,
+    #[options(
+        help = "the byte order of the coordinates of the reference accumulator (little or big)",
+        default = "little",
+        parse(try_from_str = "endianness_from_str")
+    )]
+    pub interop_endianness: Endianness,
+    #[options(
+        help = "the reference accumulator does not start with the hash of the previous contribution",
+        default = "false"
+    )]
+    pub interop_without_hash: bool,
 }
 
 #[derive(Debug, Options, Clone)]
 pub struct CombineOpts {
     help: bool,
-    #[options(help = "the response files which will be combined", default = "response_list")]
+    #[options(
+        help = "the response files which will be combined, each optionally followed by whether it is compressed",
+        default = "response_list"
+    )]
     pub response_list_fname: String,
     #[options(help = "the combined response file", default = "combined")]
     pub combined_fname: String,
+    #[options(help = "the final hash of the combined response file", default = "combined.hash")]
+    pub combined_hash_fname: String,
+    #[options(
+        help = "checkpoint the progress and resume from a partially combined file if there is one",
+        default = "false"
+    )]
+    pub resume: bool,
+    #[options(
+        help = "the codec to wrap the combined file with (none, gzip or zstd)",
+        default = "none",
+        parse(try_from_str = "output_codec_from_str")
+    )]
+    pub output_codec: OutputCodec,
+    #[options(help = "discover the response files by a pattern such as 'response_*' instead of a list")]
+    pub chunk_glob: Option<String>,
+    #[options(help = "read the response files and their hashes from a JSON manifest instead of a list")]
+    pub manifest: Option<String>,
+    #[options(
+        help = "combine the responses in the order of their hashes, placed by their header (framed only)",
+        default = "false"
+    )]
+    pub sort_by_hash: bool,
+    #[options(help = "also split the combined file into challenges of this chunk size, for the next round")]
+    pub rechunk_size: Option<usize>,
+    #[options(help = "the prefix for the re-chunked challenge files", default = "challenge")]
+    pub rechunk_prefix: String,
+    #[options(
+        help = "the template of the re-chunked challenge filenames, with the {base} prefix and the chunk {index}",
+        default = "{base}_{index}"
+    )]
+    pub rechunk_name_template: String,
+    #[options(
+        help = "combine the responses in memory and verify the combination, without writing it",
+        default = "false"
+    )]
+    pub verify_only: bool,
+    #[options(
+        help = "combine the responses in memory and print the hash of the combination, without writing it",
+        default = "false"
+    )]
+    pub hash_only: bool,
+    #[options(
+        help = "seed the randomness of the --verify-only verification with a fixed value, to reproduce it exactly",
+        default = "false"
+    )]
+    pub deterministic: bool,
 }
 
 #[derive(Debug, Options, Clone)]
@@ -184,4 +616,391 @@ pub struct SplitOpts {
     pub chunk_fname_prefix: String,
     #[options(help = "the full response file", default = "full")]
     pub full_fname: String,
+    #[options(help = "only split out the challenge of this chunk, e.g. to split the chunks on several machines")]
+    pub only_chunk: Option<usize>,
+    #[options(
+        help = "the template of the chunk filenames, with the {base} prefix and the chunk {index}",
+        default = "{base}_{index}"
+    )]
+    pub name_template: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct NewAndBeaconOpts {
+    help: bool,
+    #[options(help = "the challenge file name to be created", default = "challenge")]
+    pub challenge_fname: String,
+    #[options(help = "the new challenge file hash", default = "challenge.verified.hash")]
+    pub challenge_hash_fname: String,
+    #[options(help = "the beacon response file which will be generated", default = "response")]
+    pub response_fname: String,
+    #[options(help = "the beacon response file hash", default = "response.hash")]
+    pub response_hash_fname: String,
+    #[options(
+        help = "the beacon hash to be used for the contribution",
+        default = "0000000000000000000a558a61ddc8ee4e488d647a747fe4dcc362fe2026c620"
+    )]
+    pub beacon_hash: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyCompressionOpts {
+    help: bool,
+    #[options(help = "the compressed response file", default = "response")]
+    pub compressed_fname: String,
+    #[options(help = "the uncompressed response file", default = "response_uncompressed")]
+    pub uncompressed_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyFinalOpts {
+    help: bool,
+    #[options(help = "the published combined response file", default = "combined")]
+    pub combined_fname: String,
+    #[options(help = "the hex encoded final hash which was published for the combined file")]
+    pub final_hash: String,
+    #[options(
+        help = "also write the SHA-256 digest of the combined file to a .sha256 file",
+        default = "false"
+    )]
+    pub sha256: bool,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ChunkMapOpts {
+    help: bool,
+    #[options(help = "print the chunk map as JSON", default = "false")]
+    pub json: bool,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyChainOpts {
+    help: bool,
+    #[options(
+        help = "the challenge file the response claims to be based on",
+        default = "challenge"
+    )]
+    pub challenge_fname: String,
+    #[options(help = "the response file which will be verified", default = "response")]
+    pub response_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct CompareCombinedOpts {
+    help: bool,
+    #[options(help = "the first combined file", default = "combined")]
+    pub first_fname: String,
+    #[options(help = "the second combined file")]
+    pub second_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct EstimateOpts {
+    help: bool,
+    #[options(help = "the number of batches to contribute to for the estimate", default = "4")]
+    pub sample_batches: usize,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ConvertEndiannessOpts {
+    help: bool,
+    #[options(help = "the file whose elements will be converted")]
+    pub input_fname: String,
+    #[options(help = "the converted file which will be created")]
+    pub output_fname: String,
+    #[options(
+        help = "the byte order to convert from or to, the canonical one being little endian (little or big)",
+        default = "big",
+        parse(try_from_str = "endianness_from_str")
+    )]
+    pub endianness: Endianness,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ExportFingerprintsOpts {
+    help: bool,
+    #[options(help = "the challenge or response file whose elements will be fingerprinted")]
+    pub input_fname: String,
+    #[options(
+        help = "the CSV file the fingerprints will be written to",
+        default = "fingerprints.csv"
+    )]
+    pub output_fname: String,
+    #[options(
+        help = "whether the elements of the file are compressed (yes or no)",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ExtendOpts {
+    help: bool,
+    #[options(help = "the full accumulator file which will be extended", default = "combined")]
+    pub input_fname: String,
+    #[options(help = "the extended accumulator file which will be created", default = "extended")]
+    pub output_fname: String,
+    #[options(help = "the extended accumulator file hash", default = "extended.hash")]
+    pub output_hash_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyManifestOpts {
+    help: bool,
+    #[options(help = "the manifest JSON file listing the chunk files", default = "manifest.json")]
+    pub manifest_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyPokOpts {
+    help: bool,
+    #[options(
+        help = "the response file whose proofs of knowledge will be verified",
+        default = "response"
+    )]
+    pub response_fname: String,
+    #[options(
+        help = "the hash of the challenge the response should be based on",
+        default = "challenge.verified.hash"
+    )]
+    pub challenge_hash_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyMyContributionOpts {
+    help: bool,
+    #[options(help = "your local response file", default = "response")]
+    pub response_fname: String,
+    #[options(help = "the published hash of the response, hex encoded")]
+    pub published_hash: String,
+    #[options(help = "the published public key of the response, hex encoded")]
+    pub published_public_key: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct EncryptSeedOpts {
+    help: bool,
+    #[options(help = "the file containing the hex encoded seed to encrypt", default = "seed")]
+    pub seed_fname: String,
+    #[options(help = "the encrypted seed file to be created", default = "seed.encrypted")]
+    pub encrypted_seed_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ValidateLengthsOpts {
+    help: bool,
+    #[options(help = "the directory holding the response.<chunk index> files", default = ".")]
+    pub directory: String,
+    #[options(help = "also write the results to this JSON file")]
+    pub json_fname: Option<String>,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ContributeRangeOpts {
+    help: bool,
+    #[options(help = "the provided full challenge file", default = "challenge")]
+    pub challenge_fname: String,
+    #[options(
+        help = "the prefix of the chunk responses, written to <prefix>_<chunk index>",
+        default = "response_range"
+    )]
+    pub response_prefix: String,
+    #[options(
+        help = "the range of chunks to contribute to, such as 0..4 (end exclusive)",
+        parse(try_from_str = "chunk_range_from_str")
+    )]
+    pub chunk_range: Option<std::ops::Range<usize>>,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct MergeRangesOpts {
+    help: bool,
+    #[options(help = "the provided full challenge file", default = "challenge")]
+    pub challenge_fname: String,
+    #[options(
+        help = "the prefix of the chunk responses, read from <prefix>_<chunk index>",
+        default = "response_range"
+    )]
+    pub response_prefix: String,
+    #[options(help = "the merged response file which will be generated", default = "response")]
+    pub response_fname: String,
+    #[options(help = "the merged response file hash", default = "response.hash")]
+    pub response_hash_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ValidateGeneratorsOpts {
+    help: bool,
+    #[options(
+        help = "the challenge or response file whose generators will be checked",
+        default = "challenge"
+    )]
+    pub input_fname: String,
+    #[options(
+        help = "whether the input file is compressed (yes or no)",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct BenchReadOpts {
+    help: bool,
+    #[options(help = "the number of batches to read with each method", default = "10")]
+    pub iterations: usize,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct PreparePhase1Opts {
+    help: bool,
+    #[options(help = "the combined accumulator to convert", default = "combined")]
+    pub combined_fname: String,
+    #[options(
+        help = "whether the combined accumulator is compressed (yes or no)",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
+    #[options(help = "the phase 1 input of phase 2 which will be generated", default = "phase1")]
+    pub phase2_fname: String,
+    #[options(
+        help = "whether to write the phase 1 input of phase 2 compressed (yes or no), which phase 2 must be told",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub phase2_compressed: UseCompression,
+    #[options(help = "the size (in powers) of the phase 2 circuit")]
+    pub phase2_size: usize,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct CheckSeedOpts {
+    help: bool,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ExtractPubkeyOpts {
+    help: bool,
+    #[options(help = "the response file to extract the public key of", default = "response")]
+    pub response_fname: String,
+    #[options(
+        help = "whether the response is compressed (yes or no)",
+        default = "yes",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
+    #[options(help = "the public key file which will be created", default = "response.public_key")]
+    pub public_key_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ReceiptOpts {
+    help: bool,
+    #[options(help = "the challenge file which was contributed to", default = "challenge")]
+    pub challenge_fname: String,
+    #[options(help = "the response file of the contribution", default = "response")]
+    pub response_fname: String,
+    #[options(help = "the receipt file", default = "receipt.json")]
+    pub receipt_fname: String,
+    #[options(help = "commit to the seed in the receipt, by recording its hash", default = "false")]
+    pub commit_seed: bool,
+    #[options(
+        help = "reproduce the contribution of the receipt from the seed instead of writing a receipt",
+        default = "false"
+    )]
+    pub verify_receipt: bool,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct AppendTranscriptOpts {
+    help: bool,
+    #[options(
+        help = "the transcript file, which is created if it does not exist",
+        default = "transcript.json"
+    )]
+    pub transcript_fname: String,
+    #[options(help = "the response file to append to the transcript", default = "response")]
+    pub response_fname: String,
+    #[options(
+        help = "whether the response is compressed (yes or no)",
+        default = "yes",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyTranscriptChainOpts {
+    help: bool,
+    #[options(help = "the transcript file to verify", default = "transcript.json")]
+    pub transcript_fname: String,
+    #[options(help = "the published final chain hash of the transcript, hex encoded")]
+    pub chain_hash: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ResignTranscriptOpts {
+    help: bool,
+    #[options(help = "the transcript file to re-sign", default = "transcript.json")]
+    pub transcript_fname: String,
+    #[options(help = "a file listing the responses of the transcript in order, one per line")]
+    pub response_list_fname: String,
+    #[options(help = "the re-signed transcript file", default = "transcript.resigned.json")]
+    pub new_transcript_fname: String,
+    #[options(
+        help = "the hash algorithm of the re-signed transcript (blake2b or sha256)",
+        default = "sha256",
+        parse(try_from_str = "hash_algorithm_from_str")
+    )]
+    pub hash_algorithm: HashAlgorithm,
+    #[options(help = "a file with the hex encoded Ed25519 secret key which signs the entries, if any")]
+    pub signing_key_fname: Option<String>,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct TestSubgroupOpts {
+    help: bool,
+    #[options(
+        help = "the number of valid and of invalid points checked in each group",
+        default = "16"
+    )]
+    pub samples: usize,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct BenchVerifyOpts {
+    help: bool,
+    #[options(
+        help = "the power of the accumulator contributed to and verified on each curve",
+        default = "8"
+    )]
+    pub power: usize,
+    #[options(help = "also write the verification times to this CSV file")]
+    pub csv: Option<String>,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct PeekOpts {
+    help: bool,
+    #[options(
+        help = "the challenge or response file to read the element from",
+        default = "challenge"
+    )]
+    pub input_fname: String,
+    #[options(
+        help = "the section of the element (tau_g1, tau_g2, alpha_g1, beta_g1 or beta_g2)",
+        default = "tau_g1",
+        parse(try_from_str = "element_type_from_str")
+    )]
+    pub section: ElementType,
+    #[options(help = "the index of the element in its section")]
+    pub index: usize,
+    #[options(
+        help = "whether the elements of the file are compressed (yes or no)",
+        default = "no",
+        parse(try_from_str = "use_compression_from_str")
+    )]
+    pub compressed: UseCompression,
 }
@@ -0,0 +1,46 @@
+use crate::{contribute_with_entropy, new_challenge, OutputCodec};
+use phase1::Phase1Parameters;
+use setup_utils::{derive_entropy_from_seed, from_slice, BatchExpMode, CheckForCorrectness};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use tracing::{info, warn};
+
+/// Creates a fresh challenge and immediately applies a beacon contribution to it.
+///
+/// The beacon is the only contribution to the resulting accumulator, so its toxic waste
+/// is derivable by anyone who knows the beacon hash. This is only meant for tests and
+/// small development ceremonies, never for production parameters.
+#[allow(clippy::too_many_arguments)]
+pub fn new_and_beacon<T: Engine + Sync>(
+    challenge_filename: &str,
+    challenge_hash_filename: &str,
+    response_filename: &str,
+    response_hash_filename: &str,
+    beacon_hash: &[u8],
+    check_input_correctness: CheckForCorrectness,
+    batch_exp_mode: BatchExpMode,
+    parameters: &Phase1Parameters<T>,
+) {
+    warn!("NewAndBeacon produces INSECURE parameters and must only be used for testing!");
+
+    new_challenge(
+        challenge_filename,
+        challenge_hash_filename,
+        OutputCodec::None,
+        parameters,
+    );
+
+    info!("Applying the beacon contribution to the fresh challenge...");
+    contribute_with_entropy(
+        challenge_filename,
+        challenge_hash_filename,
+        response_filename,
+        response_hash_filename,
+        check_input_correctness,
+        batch_exp_mode,
+        None,
+        parameters,
+        || derive_entropy_from_seed(&from_slice(beacon_hash)),
+    );
+}
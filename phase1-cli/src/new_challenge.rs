@@ -1,10 +1,10 @@
+use crate::{encode_file, LocalFile, OutputCodec, Storage};
 use phase1::{Phase1, Phase1Parameters};
-use setup_utils::{blank_hash, calculate_hash, print_hash, UseCompression};
+use setup_utils::{blank_hash, print_hash, GenericArray, HashWriter, UseCompression, U64};
 
 use zexe_algebra::PairingEngine as Engine;
 
-use memmap::*;
-use std::{fs::OpenOptions, io::Write};
+use std::io::{self, Write};
 use tracing::info;
 
 const COMPRESS_NEW_CHALLENGE: UseCompression = UseCompression::No;
@@ -12,6 +12,7 @@ const COMPRESS_NEW_CHALLENGE: UseCompression = UseCompression::No;
 pub fn new_challenge<T: Engine + Sync>(
     challenge_filename: &str,
     challenge_hash_filename: &str,
+    output_codec: OutputCodec,
     parameters: &Phase1Parameters<T>,
 ) {
     info!(
@@ -20,53 +21,48 @@ pub fn new_challenge<T: Engine + Sync>(
     );
     info!("In total will generate up to {} powers", parameters.powers_g1_length);
 
-    let file = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create_new(true)
-        .open(challenge_filename)
-        .expect("unable to create challenge file");
+    let mut storage = LocalFile::create_new(challenge_filename).expect("unable to create challenge file");
+    let contribution_hash = write_new_challenge(&mut storage, parameters);
 
-    let expected_challenge_length = match COMPRESS_NEW_CHALLENGE {
-        UseCompression::Yes => parameters.contribution_size - parameters.public_key_size,
-        UseCompression::No => parameters.accumulator_size,
-    };
+    std::fs::File::create(challenge_hash_filename)
+        .expect("unable to open new challenge hash file")
+        .write_all(contribution_hash.as_slice())
+        .expect("unable to write new challenge hash");
 
-    file.set_len(expected_challenge_length as u64)
-        .expect("unable to allocate large enough file");
+    info!("Empty contribution is formed with a hash:");
+    print_hash(&contribution_hash);
 
-    let mut writable_map = unsafe {
-        MmapOptions::new()
-            .map_mut(&file)
-            .expect("unable to create a memory map")
-    };
+    // The hash is of the raw accumulator, which is what readers see after decoding
+    drop(storage);
+    encode_file(challenge_filename, output_codec).expect("unable to encode the challenge file");
+    info!("Wrote a fresh accumulator to challenge file");
+}
 
+/// Writes a fresh accumulator to the storage and returns its hash. The accumulator is
+/// written one batch at a time, so that it is never held in memory as a whole.
+pub fn write_new_challenge<T: Engine + Sync>(
+    storage: &mut impl Storage,
+    parameters: &Phase1Parameters<T>,
+) -> GenericArray<u8, U64> {
     // Write a blank BLAKE2b hash:
     let hash = blank_hash();
-    (&mut writable_map[0..])
-        .write_all(hash.as_slice())
-        .expect("unable to write a default hash to mmap");
-    writable_map
-        .flush()
-        .expect("unable to write blank hash to challenge file");
+    storage
+        .write_range(0, hash.as_slice())
+        .expect("unable to write a default hash to the challenge");
 
     info!("Blank hash for an empty challenge:");
     print_hash(&hash);
 
-    Phase1::initialization(&mut writable_map, COMPRESS_NEW_CHALLENGE, &parameters)
-        .expect("generation of initial accumulator is successful");
-    writable_map.flush().expect("unable to flush memmap to disk");
+    // The batches are written in order, so the challenge is hashed as it is written
+    let mut hasher = HashWriter::new(io::sink());
+    hasher.write_all(hash.as_slice()).expect("unable to hash the challenge");
+    Phase1::initialization_in_batches(COMPRESS_NEW_CHALLENGE, parameters, |offset, batch| {
+        storage.write_range(offset as u64, batch)?;
+        hasher.write_all(batch)?;
+        Ok(())
+    })
+    .expect("generation of initial accumulator is successful");
 
     // Get the hash of the contribution, so the user can compare later
-    let output_readonly = writable_map.make_read_only().expect("must make a map readonly");
-    let contribution_hash = calculate_hash(&output_readonly);
-
-    std::fs::File::create(challenge_hash_filename)
-        .expect("unable to open new challenge hash file")
-        .write_all(contribution_hash.as_slice())
-        .expect("unable to write new challenge hash");
-
-    info!("Empty contribution is formed with a hash:");
-    print_hash(&contribution_hash);
-    info!("Wrote a fresh accumulator to challenge file");
+    hasher.into_hash()
 }
@@ -0,0 +1,66 @@
+use crate::is_encoded;
+use phase1::{Phase1, Phase1Parameters, ResponseHeader, HEADER_SIZE};
+use setup_utils::{CheckForCorrectness, Deserializer, ElementType, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use memmap::*;
+use std::fs::OpenOptions;
+use tracing::info;
+
+/// Prints the hex serialization of the element at `index` of a section of a challenge or
+/// response, after checking that it is a valid point. Only the element is read from the
+/// memory mapped input, so peeking into large accumulators is cheap. Files written with an
+/// output codec can't be read at an offset without decoding them in full, so they are
+/// rejected and should be decoded first.
+pub fn peek<T: Engine>(
+    input_filename: &str,
+    element_type: ElementType,
+    index: usize,
+    compressed: UseCompression,
+    parameters: &Phase1Parameters<T>,
+) {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(input_filename)
+        .expect("unable open input file in this directory");
+    let readable_map = unsafe {
+        MmapOptions::new()
+            .map(&reader)
+            .expect("unable to create a memory map for input")
+    };
+    if is_encoded(&readable_map) {
+        panic!(
+            "{} was written with an output codec, decode it before peeking into it",
+            input_filename
+        );
+    }
+    let input = match ResponseHeader::read(&readable_map).expect("invalid response header") {
+        Some(_) => &readable_map[HEADER_SIZE..],
+        None => &readable_map[..],
+    };
+    // Responses additionally end with the public key, which is not part of any section
+    let expected_length = parameters.get_length(compressed);
+    if input.len() < expected_length {
+        panic!(
+            "The size of {} should be at least {}, but it's {}, so something isn't right.",
+            input_filename,
+            expected_length,
+            input.len()
+        );
+    }
+
+    let bytes = Phase1::element(input, compressed, parameters, element_type, index)
+        .unwrap_or_else(|e| panic!("unable to peek into {}: {}", input_filename, e));
+    match element_type {
+        ElementType::TauG2 | ElementType::BetaG2 => (&*bytes)
+            .read_element::<T::G2Affine>(compressed, CheckForCorrectness::Full)
+            .map(|_| ()),
+        _ => (&*bytes)
+            .read_element::<T::G1Affine>(compressed, CheckForCorrectness::Full)
+            .map(|_| ()),
+    }
+    .unwrap_or_else(|e| panic!("{}[{}] is not a valid point: {}", element_type, index, e));
+
+    info!("{}[{}] = {}", element_type, index, hex::encode(bytes));
+}
@@ -0,0 +1,104 @@
+use crate::map_input;
+use phase1::{ContributionMode, Phase1, Phase1Parameters};
+use setup_utils::{CheckForCorrectness, Groth16Params, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::fs::OpenOptions;
+use tracing::info;
+
+/// Rewrites a combined phase 1 accumulator into the Lagrange coefficients of a phase 2 of
+/// `2^phase2_size` constraints, in exactly the layout `MPCParameters::new_from_buffer` reads.
+/// The written file is read back and compared with the coefficients, so that a file which
+/// phase 2 would not accept is caught here rather than when the phase 2 ceremony starts.
+/// The file is written with the `phase2_compressed` compression, which phase 2 must be given
+/// when reading it.
+pub fn prepare_phase1<T: Engine + Sync>(
+    combined_filename: &str,
+    phase2_filename: &str,
+    combined_compressed: UseCompression,
+    phase2_compressed: UseCompression,
+    phase2_size: usize,
+    check_input_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    let parameters = parameters.into_chunk_parameters(ContributionMode::Full, 0, 0);
+    let phase2_size = 2usize.pow(phase2_size as u32);
+    if phase2_size > parameters.powers_length {
+        panic!(
+            "A phase 2 of size {} needs at least as many powers of tau, but the accumulator has {}",
+            phase2_size, parameters.powers_length
+        );
+    }
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(combined_filename)
+        .expect("unable open combined file in this directory");
+    let combined = map_input(&reader).expect("unable to create a memory map for input");
+    // The public key of the last contributor may or may not have been kept
+    let expected_length = parameters.get_length(combined_compressed);
+    if combined.len() != expected_length && combined.len() != parameters.get_response_length(combined_compressed) {
+        panic!(
+            "The size of the combined file should be {}, but it's {}, so something isn't right.",
+            expected_length,
+            combined.len()
+        );
+    }
+
+    info!("Reading the accumulator of {}...", combined_filename);
+    let accumulator = Phase1::deserialize(&combined, combined_compressed, check_input_correctness, &parameters)
+        .expect("unable to read the combined accumulator");
+
+    info!(
+        "Computing the Lagrange coefficients of a phase 2 of size {}...",
+        phase2_size
+    );
+    let groth16_params = Groth16Params::<T>::new(
+        phase2_size,
+        accumulator.tau_powers_g1,
+        accumulator.tau_powers_g2,
+        accumulator.alpha_tau_powers_g1,
+        accumulator.beta_tau_powers_g1,
+        accumulator.beta_g2,
+    )
+    .expect("could not create Groth16 Lagrange coefficients");
+
+    let mut writer = OpenOptions::new()
+        .read(false)
+        .write(true)
+        .create_new(true)
+        .open(phase2_filename)
+        .expect("unable to create the phase 2 input file in this directory");
+    groth16_params
+        .write(&mut writer, phase2_compressed)
+        .expect("unable to write the phase 2 input file");
+    drop(writer);
+
+    info!("Reading {} back as phase 2 does...", phase2_filename);
+    let mut written = std::fs::read(phase2_filename).expect("unable to read the phase 2 input file");
+    let expected_length = Groth16Params::<T>::serialized_size(phase2_size, phase2_compressed);
+    if written.len() != expected_length {
+        panic!(
+            "The phase 2 input file should be {} bytes long, but it's {} bytes long",
+            expected_length,
+            written.len()
+        );
+    }
+    let read = Groth16Params::<T>::read(
+        &mut written,
+        phase2_compressed,
+        CheckForCorrectness::Full,
+        phase2_size,
+        phase2_size,
+    )
+    .expect("the phase 2 input file can't be read by phase 2");
+    if read != groth16_params {
+        panic!("The phase 2 input file read back differs from the coefficients which were written");
+    }
+
+    info!(
+        "Wrote the phase 2 input for {} constraints to {}",
+        phase2_size, phase2_filename
+    );
+}
@@ -0,0 +1,170 @@
+use crate::{contribute_with_entropy, map_input, verify_manifest::curve_name};
+use phase1::{helpers::CurveKind, Phase1Parameters};
+use setup_utils::{calculate_hash, derive_entropy_from_seed, BatchExpMode, CheckForCorrectness};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{read, remove_file, File, OpenOptions},
+    path::Path,
+};
+use tracing::{error, info};
+
+/// The RNG contributions are made with, seeded with `derive_entropy_from_seed`
+const RECEIPT_RNG: &str = "chacha20-personalized-blake2b";
+
+/// A receipt of a contribution, recording everything but the seed needed to reproduce it,
+/// so that once the seed is revealed anyone can check it produced the response. Hashes are
+/// hex encoded `calculate_hash` outputs, and the commitment to the seed is its hash.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Receipt {
+    pub curve: String,
+    pub power: usize,
+    pub proving_system: String,
+    pub contribution_mode: String,
+    pub chunk_index: usize,
+    pub chunk_size: usize,
+    pub batch_size: usize,
+    /// Whether the response starts with a header
+    pub framed: bool,
+    pub rng: String,
+    pub challenge_hash: String,
+    pub response_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed_commitment: Option<String>,
+}
+
+impl Receipt {
+    fn new<T: Engine>(
+        curve_kind: &CurveKind,
+        framed: bool,
+        challenge_hash: String,
+        response_hash: String,
+        seed_commitment: Option<String>,
+        parameters: &Phase1Parameters<T>,
+    ) -> Self {
+        Self {
+            curve: curve_name(curve_kind).to_string(),
+            power: parameters.total_size_in_log2,
+            proving_system: format!("{:?}", parameters.proving_system).to_lowercase(),
+            contribution_mode: format!("{:?}", parameters.contribution_mode).to_lowercase(),
+            chunk_index: parameters.chunk_index,
+            chunk_size: parameters.chunk_size,
+            batch_size: parameters.batch_size,
+            framed,
+            rng: RECEIPT_RNG.to_string(),
+            challenge_hash,
+            response_hash,
+            seed_commitment,
+        }
+    }
+}
+
+/// Writes the receipt of the contribution of `response_filename` to `challenge_filename` to
+/// `receipt_filename`, committing to the seed if it is provided. The seed itself is never
+/// written, only its hash.
+pub fn write_receipt<T: Engine>(
+    challenge_filename: &str,
+    response_filename: &str,
+    receipt_filename: &str,
+    seed: Option<&[u8]>,
+    curve_kind: &CurveKind,
+    framed: bool,
+    parameters: &Phase1Parameters<T>,
+) {
+    let receipt = Receipt::new(
+        curve_kind,
+        framed,
+        file_hash(challenge_filename),
+        file_hash(response_filename),
+        seed.map(|seed| hex::encode(calculate_hash(seed))),
+        parameters,
+    );
+    serde_json::to_writer_pretty(
+        File::create(receipt_filename).expect("unable to create the receipt file"),
+        &receipt,
+    )
+    .expect("unable to write the receipt file");
+
+    info!("Wrote the receipt of {} to {}", response_filename, receipt_filename);
+}
+
+/// Reproduces the contribution of a receipt from the revealed seed, by contributing to
+/// `challenge_filename` again, and checks that the response has the hash of the receipt.
+/// The receipt must have been written for `parameters`, the challenge must be the one it
+/// was written for, and the seed must match its commitment if it has one. Every mismatch
+/// is reported before failing.
+pub fn verify_receipt<T: Engine + Sync>(
+    receipt_filename: &str,
+    challenge_filename: &str,
+    seed: &[u8],
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!("Reproducing the contribution of {}", receipt_filename);
+
+    let receipt: Receipt = serde_json::from_reader(File::open(receipt_filename).expect("unable to open the receipt"))
+        .expect("unable to parse the receipt");
+
+    let expected = Receipt::new(
+        curve_kind,
+        receipt.framed,
+        file_hash(challenge_filename),
+        receipt.response_hash.clone(),
+        receipt
+            .seed_commitment
+            .as_ref()
+            .map(|_| hex::encode(calculate_hash(seed))),
+        parameters,
+    );
+    if receipt != expected {
+        error!("The receipt is {:?}", receipt);
+        error!("but the current parameters, challenge and seed give {:?}", expected);
+        panic!("The receipt was not written for these parameters, challenge and seed");
+    }
+
+    // The response is reproduced next to the receipt, and removed once hashed
+    let reproduced_filename = format!("{}.response", receipt_filename);
+    let reproduced_challenge_hash_filename = format!("{}.challenge.hash", receipt_filename);
+    let reproduced_hash_filename = format!("{}.response.hash", receipt_filename);
+    contribute_with_entropy(
+        challenge_filename,
+        &reproduced_challenge_hash_filename,
+        &reproduced_filename,
+        &reproduced_hash_filename,
+        CheckForCorrectness::No,
+        BatchExpMode::Auto,
+        if receipt.framed { Some(curve_kind) } else { None },
+        parameters,
+        || derive_entropy_from_seed(seed),
+    );
+    let reproduced_hash = hex::encode(read(&reproduced_hash_filename).expect("unable to read the reproduced hash"));
+    for filename in &[
+        reproduced_filename,
+        reproduced_challenge_hash_filename,
+        reproduced_hash_filename,
+    ] {
+        if Path::new(filename).exists() {
+            remove_file(filename).expect("unable to remove the reproduced files");
+        }
+    }
+
+    if reproduced_hash != receipt.response_hash.to_lowercase() {
+        panic!(
+            "The reproduced response has hash {}, but the receipt lists {}",
+            reproduced_hash, receipt.response_hash
+        );
+    }
+
+    info!("The contribution of the receipt was reproduced from the seed");
+}
+
+fn file_hash(filename: &str) -> String {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)
+        .unwrap_or_else(|_| panic!("unable to open {}", filename));
+    let map = map_input(&reader).expect("unable to create a memory map for input");
+    hex::encode(calculate_hash(&map))
+}
@@ -0,0 +1,125 @@
+use crate::{chunk_filenames, map_input, verify_manifest::curve_name, Manifest, ManifestEntry};
+use phase1::{helpers::CurveKind, ContributionMode, Phase1, Phase1Parameters, ProvingSystem};
+use setup_utils::{calculate_hash, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+};
+use tracing::info;
+
+const COMBINED_IS_COMPRESSED: UseCompression = UseCompression::No;
+const COMPRESS_NEW_CHUNKS: UseCompression = UseCompression::No;
+
+/// Splits a combined accumulator into challenges of `chunk_size` elements, written to the
+/// filenames generated from `name_template` and the prefix, `{prefix}_{chunk_index}` by
+/// default, so that the next round of a ceremony can use a different chunk size than the
+/// one which was combined. The challenges are chained to the combined file through its
+/// hash, and a `{prefix}_manifest.json` listing them is written alongside.
+pub fn rechunk<T: Engine + Sync>(
+    combined_filename: &str,
+    chunk_filename_prefix: &str,
+    name_template: &str,
+    chunk_size: usize,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+    let num_chunks = (upper_bound + chunk_size - 1) / chunk_size;
+    let chunk_filenames = chunk_filenames(name_template, chunk_filename_prefix, num_chunks);
+    info!(
+        "Will split {} into {} chunks of {} elements",
+        combined_filename, num_chunks, chunk_size
+    );
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(combined_filename)
+        .expect("unable open combined file in this directory");
+    let combined = map_input(&reader).expect("unable to create a memory map for input");
+    let expected_length = parameters
+        .into_chunk_parameters(ContributionMode::Chunked, 0, parameters.powers_g1_length)
+        .accumulator_size;
+    if combined.len() != expected_length {
+        panic!(
+            "The size of the combined file should be {}, but it's {}, so something isn't right.",
+            expected_length,
+            combined.len()
+        );
+    }
+    let combined_hash = calculate_hash(&combined);
+
+    let parameters = parameters.into_chunk_parameters(ContributionMode::Chunked, 0, chunk_size);
+    let mut chunks = (0..num_chunks)
+        .map(|chunk_index| {
+            let chunk_parameters = parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, chunk_size);
+            let mut chunk = vec![0; chunk_parameters.accumulator_size];
+            chunk[..parameters.hash_size].copy_from_slice(combined_hash.as_slice());
+            chunk
+        })
+        .collect::<Vec<_>>();
+
+    let res = Phase1::split(
+        (&combined, COMBINED_IS_COMPRESSED),
+        chunks
+            .iter_mut()
+            .map(|chunk| (chunk.as_mut_slice(), COMPRESS_NEW_CHUNKS))
+            .collect::<Vec<_>>(),
+        &parameters,
+    );
+    if let Err(e) = res {
+        info!("Splitting failed: {}", e);
+        panic!("INVALID COMBINED FILE!!!");
+    }
+
+    let entries = chunks
+        .iter()
+        .enumerate()
+        .map(|(chunk_index, chunk)| {
+            let filename = &chunk_filenames[chunk_index];
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(filename)
+                .and_then(|mut file| file.write_all(chunk))
+                .expect("unable to write the chunk file");
+
+            // The manifest lists the chunk files relative to its own directory
+            let relative_filename = Path::new(filename)
+                .file_name()
+                .expect("the chunk file prefix must end with a file name")
+                .to_string_lossy()
+                .to_string();
+            ManifestEntry {
+                chunk_index,
+                filename: relative_filename,
+                hash: hex::encode(calculate_hash(chunk)),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let manifest_filename = format!("{}_manifest.json", chunk_filename_prefix);
+    let manifest = Manifest {
+        num_chunks,
+        curve: Some(curve_name(curve_kind).to_string()),
+        power: Some(parameters.total_size_in_log2),
+        chunks: entries,
+        combined_hash: None,
+    };
+    serde_json::to_writer_pretty(
+        File::create(&manifest_filename).expect("unable to create the manifest file"),
+        &manifest,
+    )
+    .expect("unable to write the manifest file");
+
+    info!(
+        "Wrote {} chunks of {} elements, listed in {}",
+        num_chunks, chunk_size, manifest_filename
+    );
+}
@@ -0,0 +1,286 @@
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    Aes256Gcm,
+};
+use argon2::{Config, Variant};
+use setup_utils::{calculate_hash, print_hash};
+
+use rand::{thread_rng, Rng};
+use std::{
+    env,
+    fs::{read, read_to_string, write},
+    path::Path,
+};
+use tracing::{error, info, warn};
+use zeroize::Zeroize;
+
+/// The environment variable which may hold the hex encoded seed
+pub const SEED_ENV_VAR: &str = "SNARK_SEED";
+
+/// The minimum length of a seed in bytes, below which it is rejected as weak
+pub const DEFAULT_MIN_SEED_LENGTH: usize = 32;
+
+/// The size of the random salt of the passphrase at the start of an encrypted seed file
+const SALT_SIZE: usize = 16;
+
+/// The size of the AES-GCM nonce following the salt in an encrypted seed file
+const NONCE_SIZE: usize = 12;
+
+/// The number of random bytes read from a PKCS#11 token to mix into the seed
+#[cfg(feature = "hsm")]
+const TOKEN_ENTROPY_SIZE: u64 = 32;
+
+/// Loads the hex encoded seed and decodes it. The sources are tried in order of
+/// precedence: the `encrypted_seed_file`, whose passphrase is prompted for, the `seed_file`,
+/// the `SNARK_SEED` environment variable and finally `seed`.
+///
+/// For backwards compatibility, `seed` may be a path to a seed file. Otherwise it is
+/// treated as the hex encoded seed itself, which leaks it into the shell history
+/// and the process listing.
+///
+/// The caller should zeroize the returned seed once the RNG has been derived from it.
+pub fn load_seed(encrypted_seed_file: &str, seed_file: &str, seed: &str) -> Vec<u8> {
+    if !encrypted_seed_file.is_empty() {
+        let encrypted_seed = read(encrypted_seed_file).expect("should have read encrypted seed file");
+        let mut passphrase = read_passphrase("Passphrase of the encrypted seed: ");
+        let seed = decrypt_seed(&encrypted_seed, &passphrase);
+        passphrase.zeroize();
+        return seed;
+    }
+
+    let mut hex_seed = if !seed_file.is_empty() {
+        read_to_string(seed_file).expect("should have read seed file")
+    } else if let Ok(hex_seed) = env::var(SEED_ENV_VAR) {
+        hex_seed
+    } else if Path::new(seed).is_file() {
+        read_to_string(seed).expect("should have read seed")
+    } else if !seed.is_empty() {
+        warn!("!!! The seed was passed as plaintext on the command line !!!");
+        warn!(
+            "It may leak through the shell history or the process list, use --seed-file or {} instead",
+            SEED_ENV_VAR
+        );
+        seed.to_string()
+    } else {
+        panic!("no seed was provided, use --seed-file or {}", SEED_ENV_VAR);
+    };
+
+    let seed = hex::decode(hex_seed.trim()).expect("seed should be a hex string");
+    hex_seed.zeroize();
+
+    seed
+}
+
+/// An estimate of the entropy of a seed, from the frequencies of its bytes
+#[derive(Debug, Clone, Copy)]
+pub struct SeedEntropy {
+    /// The length of the seed in bytes
+    pub length: usize,
+    /// The Shannon entropy of the distribution of the bytes of the seed, in bits per byte
+    pub bits_per_byte: f64,
+    /// The shortest period with which the seed repeats itself, if it does
+    pub period: Option<usize>,
+}
+
+impl SeedEntropy {
+    /// Estimates the entropy of the decoded seed bytes
+    pub fn estimate(seed: &[u8]) -> Self {
+        let mut counts = [0usize; 256];
+        for byte in seed {
+            counts[*byte as usize] += 1;
+        }
+        let bits_per_byte = counts
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / seed.len() as f64;
+                -p * p.log2()
+            })
+            .sum();
+        let period = (1..=seed.len() / 2).find(|period| seed[*period..] == seed[..seed.len() - period]);
+
+        Self {
+            length: seed.len(),
+            bits_per_byte,
+            period,
+        }
+    }
+
+    /// The estimated entropy of the whole seed, in bits
+    pub fn total_bits(&self) -> f64 {
+        match self.period {
+            Some(period) => self.bits_per_byte * period as f64,
+            None => self.bits_per_byte * self.length as f64,
+        }
+    }
+
+    /// Whether the bytes of the seed are much less evenly distributed than random bytes would
+    /// be. A seed of n random bytes has close to log2(min(n, 256)) bits per byte, which a low
+    /// entropy seed, such as one of only zeros or of a few distinct bytes, falls far short of.
+    pub fn is_low(&self) -> bool {
+        let max_bits_per_byte = (self.length.min(256) as f64).log2();
+        self.period.is_some() || self.bits_per_byte < max_bits_per_byte / 2.0
+    }
+}
+
+/// Checks that the seed is at least `min_length` bytes long, and that its entropy is not
+/// obviously low, before it is contributed with, since a short or repetitive seed silently
+/// weakens the contribution. A weak seed is rejected unless `allow_weak_seed` is set, in
+/// which case it is only warned about.
+pub fn check_seed(seed: &[u8], min_length: usize, allow_weak_seed: bool) -> SeedEntropy {
+    let entropy = SeedEntropy::estimate(seed);
+    info!(
+        "The seed is {} bytes long, with an estimated entropy of {:.2} bits per byte, {:.0} bits in total",
+        entropy.length,
+        entropy.bits_per_byte,
+        entropy.total_bits()
+    );
+
+    let mut weaknesses = vec![];
+    if entropy.length < min_length {
+        weaknesses.push(format!(
+            "the seed is {} bytes long, but it should be at least {} bytes long",
+            entropy.length, min_length
+        ));
+    }
+    if let Some(period) = entropy.period {
+        weaknesses.push(format!("the seed repeats itself every {} bytes", period));
+    } else if entropy.is_low() {
+        weaknesses.push("the bytes of the seed are not random enough".to_string());
+    }
+
+    if weaknesses.is_empty() {
+        return entropy;
+    }
+    if allow_weak_seed {
+        for weakness in &weaknesses {
+            warn!("!!! {} !!!", weakness);
+        }
+        warn!("Using the weak seed anyway, as --allow-weak-seed was given");
+    } else {
+        for weakness in &weaknesses {
+            error!("{}", weakness);
+        }
+        panic!("the seed is weak, use a longer random seed, or --allow-weak-seed to use it anyway");
+    }
+
+    entropy
+}
+
+/// Encrypts the hex encoded seed in `seed_file` under a passphrase prompted for at runtime,
+/// and writes it to `encrypted_seed_file` for use with `--encrypted-seed-file`. The plaintext
+/// seed file is left in place, and should be removed securely once the encrypted one is tested.
+pub fn encrypt_seed_file(seed_file: &str, encrypted_seed_file: &str) {
+    let mut hex_seed = read_to_string(seed_file).expect("should have read seed file");
+    let mut seed = hex::decode(hex_seed.trim()).expect("seed should be a hex string");
+    hex_seed.zeroize();
+
+    let mut passphrase = read_passphrase("Passphrase to encrypt the seed with: ");
+    let mut confirmation = read_passphrase("Passphrase again: ");
+    if passphrase != confirmation {
+        panic!("the passphrases do not match");
+    }
+    confirmation.zeroize();
+
+    let encrypted_seed = encrypt_seed(&seed, &passphrase);
+    seed.zeroize();
+    passphrase.zeroize();
+
+    write(encrypted_seed_file, &encrypted_seed).expect("unable to write the encrypted seed file");
+    info!("Wrote the encrypted seed to {}", encrypted_seed_file);
+}
+
+/// Encrypts the seed with AES-256-GCM under a key derived from the passphrase with Argon2id.
+/// The result is laid out as `salt || nonce || ciphertext`, the ciphertext including the tag.
+pub fn encrypt_seed(seed: &[u8], passphrase: &str) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let salt: [u8; SALT_SIZE] = rng.gen();
+    let nonce: [u8; NONCE_SIZE] = rng.gen();
+
+    let mut key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key));
+    key.zeroize();
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&nonce), seed)
+        .expect("unable to encrypt the seed");
+
+    [&salt[..], &nonce[..], &ciphertext[..]].concat()
+}
+
+/// Decrypts a seed encrypted with `encrypt_seed`
+pub fn decrypt_seed(encrypted_seed: &[u8], passphrase: &str) -> Vec<u8> {
+    if encrypted_seed.len() < SALT_SIZE + NONCE_SIZE {
+        panic!("the encrypted seed file is too short");
+    }
+    let (salt, rest) = encrypted_seed.split_at(SALT_SIZE);
+    let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+
+    let mut key = derive_key(passphrase, salt);
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key));
+    key.zeroize();
+    cipher
+        .decrypt(GenericArray::from_slice(nonce), ciphertext)
+        .expect("unable to decrypt the seed, the passphrase may be wrong")
+}
+
+/// Derives the 256-bit AES key from the passphrase, slowly, to hinder brute forcing it
+fn derive_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
+    let config = Config {
+        variant: Variant::Argon2id,
+        ..Config::default()
+    };
+    argon2::hash_raw(passphrase.as_bytes(), salt, &config).expect("unable to derive the key from the passphrase")
+}
+
+/// Prompts for a passphrase on the terminal, so that it never appears on the command line
+fn read_passphrase(prompt: &str) -> String {
+    rpassword::read_password_from_tty(Some(prompt)).expect("unable to read the passphrase")
+}
+
+/// Mixes random bytes generated by the PKCS#11 token of `pkcs11_module`, a path to the
+/// token's PKCS#11 library, into the seed, by hashing them together. The digest of the mixed
+/// seed is printed for auditability, while the output of the token is never revealed.
+///
+/// The token's output is fresh randomness, so the resulting seed cannot be reproduced.
+pub fn mix_token_entropy(seed: &mut Vec<u8>, pkcs11_module: &str) {
+    let mut token_entropy = read_token_entropy(pkcs11_module);
+    let mixed_seed = calculate_hash(&[&seed[..], &token_entropy[..]].concat());
+    token_entropy.zeroize();
+    seed.zeroize();
+    *seed = mixed_seed.to_vec();
+
+    info!("Mixed the entropy of the PKCS#11 token into the seed, the digest of the mixed seed is:");
+    print_hash(&calculate_hash(&seed));
+}
+
+/// Reads random bytes from the first slot of the module with a token present. Generating
+/// random bytes does not require logging in to the token.
+#[cfg(feature = "hsm")]
+fn read_token_entropy(pkcs11_module: &str) -> Vec<u8> {
+    use pkcs11::{
+        types::{CKF_SERIAL_SESSION, CK_TRUE},
+        Ctx,
+    };
+
+    let ctx = Ctx::new_and_initialize(pkcs11_module).expect("unable to load the PKCS#11 module");
+    let slot = *ctx
+        .get_slot_list(CK_TRUE)
+        .expect("unable to list the slots of the PKCS#11 module")
+        .first()
+        .expect("no PKCS#11 token is present");
+    let session = ctx
+        .open_session(slot, CKF_SERIAL_SESSION, None, None)
+        .expect("unable to open a session with the PKCS#11 token");
+    let entropy = ctx
+        .generate_random(session, TOKEN_ENTROPY_SIZE)
+        .expect("unable to generate random bytes with the PKCS#11 token");
+    ctx.close_session(session)
+        .expect("unable to close the session with the PKCS#11 token");
+
+    entropy
+}
+
+#[cfg(not(feature = "hsm"))]
+fn read_token_entropy(_pkcs11_module: &str) -> Vec<u8> {
+    panic!("this binary was built without PKCS#11 support, rebuild it with the hsm feature");
+}
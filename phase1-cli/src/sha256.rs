@@ -0,0 +1,27 @@
+use crate::map_input;
+
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+};
+use tracing::info;
+
+/// Computes the SHA-256 digest of a file, over the same (decoded) bytes as its BLAKE2b hash,
+/// prints it and writes it to a `{filename}.sha256` sidecar in the format of `sha256sum`.
+/// This is only an auxiliary digest for external tools which expect SHA-256, e.g. contracts,
+/// the ceremony itself is still chained through the BLAKE2b hashes.
+pub fn write_sha256(filename: &str) {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)
+        .expect("unable to open the file to compute its SHA-256 digest");
+    let map = map_input(&reader).expect("unable to create a memory map for input");
+    let digest = hex::encode(Sha256::digest(&map));
+    info!("SHA-256 digest of {}: {}", filename, digest);
+
+    let sidecar_filename = format!("{}.sha256", filename);
+    File::create(&sidecar_filename)
+        .and_then(|mut file| writeln!(file, "{}  {}", digest, filename))
+        .expect("unable to write the SHA-256 sidecar file");
+}
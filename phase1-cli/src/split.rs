@@ -1,3 +1,4 @@
+use crate::chunk_filenames;
 use phase1::{Phase1, Phase1Parameters, ProvingSystem};
 use setup_utils::UseCompression;
 
@@ -10,7 +11,17 @@ use tracing::info;
 const CONTRIBUTION_IS_COMPRESSED: UseCompression = UseCompression::Yes;
 const COMPRESS_NEW_SPLIT: UseCompression = UseCompression::No;
 
-pub fn split<T: Engine + Sync>(chunk_filename_prefix: &str, combined_filename: &str, parameters: &Phase1Parameters<T>) {
+/// Splits a full response into the challenges of its chunks, written to the filenames
+/// generated from `name_template` and the prefix, `{prefix}_{chunk_index}` by default.
+/// If `only_chunk` is provided, only the challenge of that chunk is written, so that the
+/// chunks can be split out in parallel on several machines.
+pub fn split<T: Engine + Sync>(
+    chunk_filename_prefix: &str,
+    name_template: &str,
+    combined_filename: &str,
+    only_chunk: Option<usize>,
+    parameters: &Phase1Parameters<T>,
+) {
     info!("Will split contributions");
 
     let mut writers = vec![];
@@ -22,15 +33,26 @@ pub fn split<T: Engine + Sync>(chunk_filename_prefix: &str, combined_filename: &
         ProvingSystem::Marlin => powers_length,
     };
     let num_chunks = (powers_length_for_proving_system + parameters.chunk_size - 1) / parameters.chunk_size;
+    let chunk_filenames = chunk_filenames(name_template, chunk_filename_prefix, num_chunks);
+    let chunk_indices = match only_chunk {
+        Some(chunk_index) if chunk_index >= num_chunks => {
+            panic!("The chunk {} is out of range for {} chunks", chunk_index, num_chunks)
+        }
+        Some(chunk_index) => {
+            info!("Will only split chunk {}", chunk_index);
+            chunk_index..chunk_index + 1
+        }
+        None => 0..num_chunks,
+    };
 
-    for chunk_index in 0..num_chunks {
+    for chunk_index in chunk_indices.clone() {
         let parameters =
             parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
         let response_writer = OpenOptions::new()
             .read(true)
             .write(true)
             .create_new(true)
-            .open(format!("{}_{}", chunk_filename_prefix, chunk_index))
+            .open(&chunk_filenames[chunk_index])
             .expect("unable open response file in this directory");
 
         response_writer
@@ -70,10 +92,7 @@ pub fn split<T: Engine + Sync>(chunk_filename_prefix: &str, combined_filename: &
         let metadata = reader
             .metadata()
             .expect("unable to get filesystem metadata for response file");
-        let expected_response_length = match CONTRIBUTION_IS_COMPRESSED {
-            UseCompression::Yes => parameters_for_input.contribution_size,
-            UseCompression::No => parameters_for_input.accumulator_size,
-        };
+        let expected_response_length = parameters_for_input.get_response_length(CONTRIBUTION_IS_COMPRESSED);
         if metadata.len() != (expected_response_length as u64) {
             panic!(
                 "The size of response file should be {}, but it's {}, so something isn't right.",
@@ -92,7 +111,8 @@ pub fn split<T: Engine + Sync>(chunk_filename_prefix: &str, combined_filename: &
         parameters.total_size_in_log2,
         parameters.batch_size,
     );
-    let res = Phase1::split(
+    let res = Phase1::split_from(
+        chunk_indices.start,
         (&mut readable_map, CONTRIBUTION_IS_COMPRESSED),
         writers
             .iter_mut()
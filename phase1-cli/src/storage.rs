@@ -0,0 +1,93 @@
+use crate::{map_input, InputMap};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+/// A place where accumulators are read from and written to, e.g. the local disk or
+/// an object store, so that a coordinator does not need a local staging copy
+pub trait Storage {
+    /// Returns the number of bytes stored
+    fn len(&self) -> io::Result<u64>;
+
+    /// Returns whether nothing is stored
+    fn is_empty(&self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Reads `len` bytes starting at `offset`
+    fn read_range(&self, offset: u64, len: usize) -> io::Result<Vec<u8>>;
+
+    /// Writes the bytes starting at `offset`, growing the storage if needed
+    fn write_range(&mut self, offset: u64, bytes: &[u8]) -> io::Result<()>;
+
+    /// Reads everything which is stored. Backends which can map their contents
+    /// in memory should do so instead of copying them.
+    fn read_all(&self) -> io::Result<InputMap> {
+        Ok(InputMap::Buffered(self.read_range(0, self.len()? as usize)?))
+    }
+}
+
+/// Storage backed by a file on the local disk
+pub struct LocalFile {
+    file: File,
+}
+
+impl LocalFile {
+    /// Opens an existing file for reading
+    pub fn open(filename: &str) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).open(filename)?;
+        Ok(Self { file })
+    }
+
+    /// Opens an existing file for reading and writing
+    pub fn open_writable(filename: &str) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(filename)?;
+        Ok(Self { file })
+    }
+
+    /// Creates a file for reading and writing, truncating it if it already exists
+    pub fn create(filename: &str) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(filename)?;
+        Ok(Self { file })
+    }
+
+    /// Creates a new file for reading and writing, failing if it already exists
+    pub fn create_new(filename: &str) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(filename)?;
+        Ok(Self { file })
+    }
+}
+
+impl Storage for LocalFile {
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.file.metadata()?.len())
+    }
+
+    fn read_range(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buffer = vec![0; len];
+        file.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn write_range(&mut self, offset: u64, bytes: &[u8]) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(bytes)?;
+        self.file.flush()
+    }
+
+    fn read_all(&self) -> io::Result<InputMap> {
+        map_input(&self.file)
+    }
+}
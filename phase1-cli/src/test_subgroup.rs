@@ -0,0 +1,105 @@
+use phase1::helpers::check_elements_are_nonzero_and_in_prime_order_subgroup;
+use setup_utils::{buffer_size, derive_rng_from_seed, BatchSerializer, SubgroupCheckMode, UseCompression};
+
+use zexe_algebra::{
+    AffineCurve, ConstantSerializedSize, FpParameters, PairingEngine as Engine, PrimeField, ProjectiveCurve,
+    UniformRand, Zero,
+};
+
+use rand::{thread_rng, Rng};
+use tracing::{error, info};
+
+/// Checks that the subgroup check of every mode rejects points of the curve which are not in
+/// the prime order subgroup, and accepts points which are, in both G1 and G2. The points are
+/// generated deterministically, `samples` of each, and a set of valid points with a single
+/// invalid one is checked too, since the batched check only rejects it with high probability.
+/// Every failed case is reported before failing.
+pub fn test_subgroup<T: Engine>(samples: usize) {
+    let mut rng = derive_rng_from_seed(b"test-subgroup");
+    let failures =
+        check_group::<T::G1Affine>("G1", samples, &mut rng) + check_group::<T::G2Affine>("G2", samples, &mut rng);
+
+    if failures > 0 {
+        panic!("The subgroup check is WRONG in {} cases", failures);
+    }
+    info!("The subgroup check rejects every point outside the prime order subgroup, and accepts the others");
+}
+
+/// Returns the number of failed cases for a group
+fn check_group<C: AffineCurve>(group: &str, samples: usize, rng: &mut impl Rng) -> usize {
+    let valid = (0..samples)
+        .map(|_| {
+            C::prime_subgroup_generator()
+                .mul(C::ScalarField::rand(rng))
+                .into_affine()
+        })
+        .collect::<Vec<_>>();
+    let invalid = (0..samples)
+        .map(|_| point_outside_subgroup::<C>(rng))
+        .collect::<Vec<_>>();
+    let mut mixed = valid.clone();
+    mixed[samples / 2] = invalid[0];
+
+    let mut failures = 0;
+    for subgroup_check_mode in &[
+        SubgroupCheckMode::Auto,
+        SubgroupCheckMode::Direct,
+        SubgroupCheckMode::Batched,
+    ] {
+        let cases = [
+            ("valid points", &valid, true),
+            ("points outside the subgroup", &invalid, false),
+            ("valid points with one outside the subgroup", &mixed, false),
+        ];
+        for (name, points, should_pass) in cases.iter() {
+            let passed = check_points(points, *subgroup_check_mode);
+            if passed != *should_pass {
+                error!(
+                    "{}: the {:?} subgroup check {} {}",
+                    group,
+                    subgroup_check_mode,
+                    if passed { "accepted" } else { "rejected" },
+                    name
+                );
+                failures += 1;
+            }
+        }
+    }
+
+    info!("{}: checked {} valid and {} invalid points", group, samples, samples);
+    failures
+}
+
+/// Runs the subgroup check of the verification on the serialized points
+fn check_points<C: AffineCurve>(points: &[C], subgroup_check_mode: SubgroupCheckMode) -> bool {
+    let compression = UseCompression::No;
+    let mut buffer = vec![0; points.len() * buffer_size::<C>(compression)];
+    buffer
+        .write_batch(points, compression)
+        .expect("unable to serialize the points");
+    let mut elements = vec![C::zero(); points.len()];
+    check_elements_are_nonzero_and_in_prime_order_subgroup(
+        (&buffer, compression),
+        (0, points.len()),
+        &mut elements,
+        subgroup_check_mode,
+        &mut thread_rng(),
+    )
+    .is_ok()
+}
+
+/// Samples a random point of the curve until one is outside the prime order subgroup, which
+/// almost every point of the curve is, since the cofactors are large
+fn point_outside_subgroup<C: AffineCurve>(rng: &mut impl Rng) -> C {
+    loop {
+        let bytes = (0..C::SERIALIZED_SIZE).map(|_| rng.gen()).collect::<Vec<u8>>();
+        if let Some(point) = C::from_random_bytes(&bytes) {
+            if !point
+                .mul(<<C::ScalarField as PrimeField>::Params as FpParameters>::MODULUS)
+                .is_zero()
+            {
+                return point;
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use phase1::BatchTiming;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    sync::mpsc::{channel, Sender},
+    thread::{self, JoinHandle},
+};
+
+const TIMINGS_CSV_HEADER: &str = "chunk_index,start,end,duration_ms,elements";
+
+/// Spawns a thread which writes one CSV row per verified batch to `filename`, e.g. for
+/// plotting the throughput of a verification. Every row is flushed as soon as it is
+/// written, so that partial timings survive a crash. The thread exits once the
+/// returned sender is dropped.
+pub fn spawn_timings_writer(
+    filename: &str,
+    chunk_index: usize,
+) -> io::Result<(Sender<BatchTiming>, JoinHandle<io::Result<()>>)> {
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "{}", TIMINGS_CSV_HEADER)?;
+    writer.flush()?;
+
+    let (sender, receiver) = channel::<BatchTiming>();
+    let handle = thread::spawn(move || {
+        for timing in receiver {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                chunk_index,
+                timing.start,
+                timing.end,
+                timing.duration.as_millis(),
+                timing.end - timing.start
+            )?;
+            writer.flush()?;
+        }
+        Ok(())
+    });
+
+    Ok((sender, handle))
+}
@@ -0,0 +1,343 @@
+use crate::map_input;
+use phase1::{helpers::CurveKind, Phase1Parameters, PublicKey, ResponseHeader};
+use setup_utils::{calculate_hash, print_hash, UseCompression};
+
+use zexe_algebra::{CanonicalSerialize, PairingEngine as Engine};
+
+use ed25519_dalek::{Keypair, PublicKey as SigningPublicKey, SecretKey, Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    convert::TryFrom,
+    fs::{read_to_string, File, OpenOptions},
+    path::Path,
+};
+use tracing::{error, info, warn};
+use zeroize::Zeroize;
+
+/// A transcript of the contributions of a ceremony, in the order they were made. Each entry
+/// commits to every entry before it through its chain hash, so that the final chain hash
+/// commits to the whole transcript.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Transcript {
+    /// The hash function of the response and chain hashes. Transcripts written before it was
+    /// recorded are BLAKE2b ones.
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+    /// The hex encoded Ed25519 public key which signs the chain hash of every entry, if the
+    /// transcript is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// A contribution of the transcript, with hex encoded fields
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TranscriptEntry {
+    /// The hash of the response
+    pub response_hash: String,
+    /// The public key of the contributor, encoded as `extract-pubkey` writes it
+    pub public_key: String,
+    /// `H(previous chain hash || response hash || public key)`, the previous chain hash of
+    /// the first entry being the hash of the empty string
+    pub chain_hash: String,
+    /// The signature of the chain hash by the signer of the transcript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// The hash function of a transcript
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    /// BLAKE2b-512, as `calculate_hash` computes it
+    Blake2b,
+    Sha256,
+}
+
+impl Default for HashAlgorithm {
+    fn default() -> Self {
+        HashAlgorithm::Blake2b
+    }
+}
+
+impl HashAlgorithm {
+    pub fn hash(&self, input: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Blake2b => calculate_hash(input).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(input).to_vec(),
+        }
+    }
+}
+
+pub fn hash_algorithm_from_str(src: &str) -> Result<HashAlgorithm, String> {
+    let hash_algorithm = match src.to_lowercase().as_str() {
+        "blake2b" => HashAlgorithm::Blake2b,
+        "sha256" => HashAlgorithm::Sha256,
+        _ => return Err("unsupported hash algorithm".to_string()),
+    };
+    Ok(hash_algorithm)
+}
+
+/// Computes the chain hash of an entry from the chain hash of the entry before it
+pub fn chain_hash(
+    hash_algorithm: HashAlgorithm,
+    previous_chain_hash: &[u8],
+    response_hash: &[u8],
+    public_key: &[u8],
+) -> Vec<u8> {
+    hash_algorithm.hash(&[previous_chain_hash, response_hash, public_key].concat())
+}
+
+/// Appends the contribution of `response_filename` to the transcript, which is created if
+/// it does not exist yet, and prints the new chain hash of the transcript
+pub fn append_to_transcript<T: Engine + Sync>(
+    transcript_filename: &str,
+    response_filename: &str,
+    compressed: UseCompression,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    let mut transcript = if Path::new(transcript_filename).exists() {
+        read_transcript(transcript_filename)
+    } else {
+        Transcript::default()
+    };
+    let previous_chain_hash = match transcript.entries.last() {
+        Some(entry) => hex::decode(&entry.chain_hash).expect("the last chain hash of the transcript is not valid hex"),
+        None => transcript.hash_algorithm.hash(&[]),
+    };
+
+    let response_reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+    let response_readable_map = map_input(&response_reader).expect("unable to create a memory map for the response");
+    let response_hash = transcript.hash_algorithm.hash(&response_readable_map);
+
+    let expected_header = ResponseHeader::new(curve_kind, compressed, parameters);
+    let response = ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+    let public_key = PublicKey::<T>::read(response, compressed, parameters)
+        .expect("wasn't able to deserialize the response file's public key");
+    public_key
+        .validate()
+        .expect("the public key of the response has invalid points");
+    let mut public_key_bytes = vec![];
+    public_key
+        .serialize(&mut public_key_bytes)
+        .expect("unable to serialize the public key");
+
+    let chain_hash = chain_hash(
+        transcript.hash_algorithm,
+        &previous_chain_hash,
+        &response_hash,
+        &public_key_bytes,
+    );
+    transcript.entries.push(TranscriptEntry {
+        response_hash: hex::encode(&response_hash),
+        public_key: hex::encode(&public_key_bytes),
+        chain_hash: hex::encode(&chain_hash),
+        signature: None,
+    });
+    serde_json::to_writer_pretty(
+        File::create(transcript_filename).expect("unable to create the transcript file"),
+        &transcript,
+    )
+    .expect("unable to write the transcript file");
+
+    info!(
+        "Appended {} as entry {} of {}, the chain hash of the transcript is now:",
+        response_filename,
+        transcript.entries.len() - 1,
+        transcript_filename
+    );
+    print_hash(&chain_hash);
+    if transcript.signer.is_some() {
+        warn!("The transcript is signed but the new entry is not, re-sign the transcript to sign it");
+    }
+}
+
+/// Recomputes the chain hash of every entry of the transcript from the first one, and checks
+/// that each matches the one recorded and that the final one is the published chain hash, so
+/// that no entry was inserted, removed, reordered or altered. The signatures of a signed
+/// transcript are checked as well. Every mismatch is reported before failing.
+pub fn verify_transcript_chain(transcript_filename: &str, published_chain_hash: &str) {
+    info!("Verifying the hash chain of {}", transcript_filename);
+
+    let transcript = read_transcript(transcript_filename);
+    if transcript.entries.is_empty() {
+        panic!("The transcript {} is empty", transcript_filename);
+    }
+
+    let mut mismatches = chain_mismatches(&transcript);
+    let chain = transcript.entries.last().unwrap().chain_hash.to_lowercase();
+    if chain != published_chain_hash.trim().to_lowercase() {
+        error!(
+            "The final chain hash is {}, but the published chain hash is {}",
+            chain, published_chain_hash
+        );
+        mismatches += 1;
+    }
+
+    if let Some(signer) = &transcript.signer {
+        let signer = hex::decode(signer)
+            .ok()
+            .and_then(|signer| SigningPublicKey::from_bytes(&signer).ok())
+            .expect("the signer of the transcript is not a valid Ed25519 public key");
+        for (index, entry) in transcript.entries.iter().enumerate() {
+            let chain_hash = hex::decode(&entry.chain_hash).expect("the chain hash is not valid hex");
+            let signature = entry
+                .signature
+                .as_ref()
+                .and_then(|signature| hex::decode(signature).ok())
+                .and_then(|signature| Signature::try_from(&signature[..]).ok());
+            match signature {
+                Some(signature) if signer.verify(&chain_hash, &signature).is_ok() => {}
+                Some(_) => {
+                    error!("Entry {}: the signature of the chain hash is invalid", index);
+                    mismatches += 1;
+                }
+                None => {
+                    error!("Entry {}: the chain hash is not signed", index);
+                    mismatches += 1;
+                }
+            }
+        }
+    }
+
+    if mismatches > 0 {
+        panic!("The hash chain of the transcript is broken in {} places", mismatches);
+    }
+
+    info!(
+        "The hash chain of the {} entries of the transcript matches the published chain hash",
+        transcript.entries.len()
+    );
+}
+
+/// Recomputes the transcript of `transcript_filename` under `hash_algorithm`, signing each
+/// chain hash with the hex encoded Ed25519 secret key of `signing_key_filename` if one is
+/// provided, and writes it to `new_transcript_filename`. The hash chain of the transcript is
+/// verified first, and the responses, listed in the order of the transcript one per line of
+/// `response_list_filename`, must match the response hashes it records, so that the new
+/// transcript commits to the same contributions.
+pub fn resign_transcript(
+    transcript_filename: &str,
+    response_list_filename: &str,
+    new_transcript_filename: &str,
+    hash_algorithm: HashAlgorithm,
+    signing_key_filename: Option<&str>,
+) {
+    info!("Re-signing {}", transcript_filename);
+
+    let transcript = read_transcript(transcript_filename);
+    if transcript.entries.is_empty() {
+        panic!("The transcript {} is empty", transcript_filename);
+    }
+    let mismatches = chain_mismatches(&transcript);
+    if mismatches > 0 {
+        panic!(
+            "The hash chain of {} is broken in {} places, so it can not be re-signed",
+            transcript_filename, mismatches
+        );
+    }
+
+    let response_filenames = read_to_string(response_list_filename).expect("unable to read the response list");
+    let response_filenames = response_filenames
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    if response_filenames.len() != transcript.entries.len() {
+        panic!(
+            "The transcript has {} entries, but {} responses were listed",
+            transcript.entries.len(),
+            response_filenames.len()
+        );
+    }
+
+    let keypair = signing_key_filename.map(|signing_key_filename| {
+        let mut secret_key = hex::decode(
+            read_to_string(signing_key_filename)
+                .expect("unable to read the signing key")
+                .trim(),
+        )
+        .expect("the signing key is not valid hex");
+        let secret = SecretKey::from_bytes(&secret_key).expect("the signing key is not a valid Ed25519 secret key");
+        secret_key.zeroize();
+        let public = SigningPublicKey::from(&secret);
+        Keypair { secret, public }
+    });
+
+    let mut new_transcript = Transcript {
+        hash_algorithm,
+        signer: keypair.as_ref().map(|keypair| hex::encode(keypair.public.as_bytes())),
+        entries: vec![],
+    };
+    let mut chain = hash_algorithm.hash(&[]);
+    for (index, (entry, response_filename)) in transcript.entries.iter().zip(response_filenames).enumerate() {
+        let response_reader = OpenOptions::new()
+            .read(true)
+            .open(response_filename)
+            .expect("unable open response file in this directory");
+        let response_readable_map =
+            map_input(&response_reader).expect("unable to create a memory map for the response");
+        if hex::encode(transcript.hash_algorithm.hash(&response_readable_map)) != entry.response_hash.to_lowercase() {
+            panic!(
+                "Entry {}: {} is not the response recorded by the transcript",
+                index, response_filename
+            );
+        }
+
+        let response_hash = hash_algorithm.hash(&response_readable_map);
+        let public_key = hex::decode(&entry.public_key).expect("the public key is not valid hex");
+        chain = chain_hash(hash_algorithm, &chain, &response_hash, &public_key);
+        new_transcript.entries.push(TranscriptEntry {
+            response_hash: hex::encode(&response_hash),
+            public_key: entry.public_key.clone(),
+            chain_hash: hex::encode(&chain),
+            signature: keypair
+                .as_ref()
+                .map(|keypair| hex::encode(&keypair.sign(&chain).to_bytes()[..])),
+        });
+    }
+
+    serde_json::to_writer_pretty(
+        File::create(new_transcript_filename).expect("unable to create the new transcript file"),
+        &new_transcript,
+    )
+    .expect("unable to write the new transcript file");
+
+    info!(
+        "Wrote the re-signed transcript to {}, its chain hash is:",
+        new_transcript_filename
+    );
+    print_hash(&chain);
+}
+
+/// Recomputes the chain hash of every entry of the transcript, and returns how many do not
+/// match the recorded one
+fn chain_mismatches(transcript: &Transcript) -> usize {
+    let mut mismatches = 0;
+    let mut chain = transcript.hash_algorithm.hash(&[]);
+    for (index, entry) in transcript.entries.iter().enumerate() {
+        let response_hash = hex::decode(&entry.response_hash).expect("the response hash is not valid hex");
+        let public_key = hex::decode(&entry.public_key).expect("the public key is not valid hex");
+        chain = chain_hash(transcript.hash_algorithm, &chain, &response_hash, &public_key);
+        if hex::encode(&chain) != entry.chain_hash.to_lowercase() {
+            error!(
+                "Entry {}: the chain hash is {}, but the transcript records {}",
+                index,
+                hex::encode(&chain),
+                entry.chain_hash
+            );
+            mismatches += 1;
+        }
+    }
+    mismatches
+}
+
+fn read_transcript(transcript_filename: &str) -> Transcript {
+    let transcript_file = File::open(transcript_filename).expect("unable to open the transcript file");
+    serde_json::from_reader(transcript_file).expect("unable to parse the transcript file")
+}
@@ -1,18 +1,24 @@
-use phase1::{Phase1, Phase1Parameters, PublicKey};
-use setup_utils::{calculate_hash, print_hash, CheckForCorrectness, SubgroupCheckMode, UseCompression};
-use zexe_algebra::PairingEngine as Engine;
+use crate::{map_input, spawn_timings_writer};
+use phase1::{
+    helpers::{validation_cache::ValidationCache, CurveKind},
+    Phase1, Phase1Parameters, PublicKey, ResponseHeader, HEADER_SIZE,
+};
+use setup_utils::{
+    calculate_hash, print_hash, CheckForCorrectness, GenericArray, HashWriter, SubgroupCheckMode, UseCompression, U64,
+};
+use zexe_algebra::{CanonicalDeserialize, PairingEngine as Engine};
 
 use memmap::*;
 use std::{
-    fs::OpenOptions,
-    io::{Read, Write},
+    fs::{File, OpenOptions},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    thread,
 };
-use tracing::info;
+use tracing::{info, warn};
 
-const PREVIOUS_CHALLENGE_IS_COMPRESSED: UseCompression = UseCompression::No;
-const CONTRIBUTION_IS_COMPRESSED: UseCompression = UseCompression::Yes;
 const COMPRESS_NEW_CHALLENGE: UseCompression = UseCompression::No;
 
+#[allow(clippy::too_many_arguments)]
 pub fn transform_pok_and_correctness<T: Engine + Sync>(
     challenge_filename: &str,
     challenge_hash_filename: &str,
@@ -23,71 +29,101 @@ pub fn transform_pok_and_correctness<T: Engine + Sync>(
     new_challenge_filename: &str,
     new_challenge_hash_filename: &str,
     subgroup_check_mode: SubgroupCheckMode,
+    continue_on_subgroup_warning: bool,
+    timings_csv: Option<&str>,
+    validation_cache_filename: Option<&str>,
+    previous_challenge_is_compressed: UseCompression,
+    contribution_is_compressed: UseCompression,
+    expected_response_hash: Option<&str>,
+    parallel_hash: bool,
+    reject_identity: bool,
+    low_memory: bool,
+    curve_kind: &CurveKind,
     parameters: &Phase1Parameters<T>,
 ) {
     info!(
         "Will verify and decompress a contribution to accumulator for 2^{} powers of tau",
         parameters.total_size_in_log2
     );
+    if continue_on_subgroup_warning {
+        print_legacy_banner();
+        if timings_csv.is_some() {
+            warn!("Timings are not recorded when auditing legacy files");
+        }
+    }
+
+    if low_memory {
+        transform_pok_and_correctness_low_memory(
+            (challenge_filename, challenge_hash_filename, check_input_correctness),
+            (response_filename, response_hash_filename, check_output_correctness),
+            (new_challenge_filename, new_challenge_hash_filename),
+            subgroup_check_mode,
+            (previous_challenge_is_compressed, contribution_is_compressed),
+            expected_response_hash,
+            reject_identity,
+            curve_kind,
+            parameters,
+        );
+        return;
+    }
 
     // Try to load challenge file from disk.
     let challenge_reader = OpenOptions::new()
         .read(true)
         .open(challenge_filename)
         .expect("unable open challenge file in this directory");
+    let challenge_readable_map = map_input(&challenge_reader).expect("unable to create a memory map for input");
 
     {
-        let metadata = challenge_reader
-            .metadata()
-            .expect("unable to get filesystem metadata for challenge file");
-        let expected_challenge_length = match PREVIOUS_CHALLENGE_IS_COMPRESSED {
-            UseCompression::Yes => parameters.contribution_size - parameters.public_key_size,
-            UseCompression::No => parameters.accumulator_size,
-        };
-        if metadata.len() != (expected_challenge_length as u64) {
+        let expected_challenge_length = parameters.get_length(previous_challenge_is_compressed);
+        if challenge_readable_map.len() != expected_challenge_length {
             panic!(
                 "The size of challenge file should be {}, but it's {}, so something isn't right.",
                 expected_challenge_length,
-                metadata.len()
+                challenge_readable_map.len()
             );
         }
     }
 
-    let challenge_readable_map = unsafe {
-        MmapOptions::new()
-            .map(&challenge_reader)
-            .expect("unable to create a memory map for input")
-    };
-
     // Try to load response file from disk.
     let response_reader = OpenOptions::new()
         .read(true)
         .open(response_filename)
         .expect("unable open response file in this directory");
 
+    let response_readable_map = unsafe {
+        MmapOptions::new()
+            .map(&response_reader)
+            .expect("unable to create a memory map for input")
+    };
+
+    // Catch a wrong response file before spending any time on verifying it
+    if let Some(expected_response_hash) = expected_response_hash {
+        let response_hash = hex::encode(calculate_hash(&response_readable_map));
+        if response_hash != expected_response_hash.trim().to_lowercase() {
+            panic!(
+                "The hash of the response file is {}, but the expected hash is {}. This is not the right response.",
+                response_hash, expected_response_hash
+            );
+        }
+        info!("The response file has the expected hash");
+    }
+
+    // Skip the header of framed responses, after checking it matches our parameters
+    let expected_header = ResponseHeader::new(curve_kind, contribution_is_compressed, parameters);
+    let response = ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+
     {
-        let metadata = response_reader
-            .metadata()
-            .expect("unable to get filesystem metadata for response file");
-        let expected_response_length = match CONTRIBUTION_IS_COMPRESSED {
-            UseCompression::Yes => parameters.contribution_size,
-            UseCompression::No => parameters.accumulator_size + parameters.public_key_size,
-        };
-        if metadata.len() != (expected_response_length as u64) {
+        let expected_response_length = parameters.get_response_length(contribution_is_compressed);
+        if response.len() != expected_response_length {
             panic!(
                 "The size of response file should be {}, but it's {}, so something isn't right.",
                 expected_response_length,
-                metadata.len()
+                response.len()
             );
         }
     }
 
-    let response_readable_map = unsafe {
-        MmapOptions::new()
-            .map(&response_reader)
-            .expect("unable to create a memory map for input")
-    };
-
     info!("Calculating previous challenge hash...");
 
     // Check that contribution is correct
@@ -104,9 +140,7 @@ pub fn transform_pok_and_correctness<T: Engine + Sync>(
     // Check the hash chain - a new response must be based on the previous challenge!
     {
         let mut response_challenge_hash = [0; 64];
-        let mut memory_slice = response_readable_map
-            .get(0..64)
-            .expect("must read point data from file");
+        let mut memory_slice = response.get(0..64).expect("must read point data from file");
         memory_slice
             .read_exact(&mut response_challenge_hash)
             .expect("couldn't read hash of challenge file from response file");
@@ -119,19 +153,34 @@ pub fn transform_pok_and_correctness<T: Engine + Sync>(
         }
     }
 
-    let response_hash = calculate_hash(&response_readable_map);
-    std::fs::File::create(response_hash_filename)
-        .expect("unable to open response hash file")
-        .write_all(response_hash.as_slice())
-        .expect("unable to write response hash");
-
-    info!("Hash of the response file for verification:");
-    print_hash(&response_hash);
+    // The response hash is only needed to start the new challenge, so it can be computed on a
+    // background thread while the response is verified. The thread maps the whole response
+    // file again, so that it hashes exactly the bytes hashed sequentially.
+    let response_hasher = if parallel_hash {
+        info!("Hashing the response file in the background...");
+        let response_filename = response_filename.to_string();
+        Some(thread::spawn(move || {
+            let response_reader = OpenOptions::new()
+                .read(true)
+                .open(&response_filename)
+                .expect("unable open response file in this directory");
+            let response_readable_map = unsafe {
+                MmapOptions::new()
+                    .map(&response_reader)
+                    .expect("unable to create a memory map for input")
+            };
+            calculate_hash(&response_readable_map)
+        }))
+    } else {
+        None
+    };
 
     // get the contributor's public key
-    let public_key = PublicKey::read(&response_readable_map, CONTRIBUTION_IS_COMPRESSED, &parameters)
+    let public_key = PublicKey::read(response, contribution_is_compressed, &parameters)
         .expect("wasn't able to deserialize the response file's public key");
 
+    check_identity_secrets(&public_key, reject_identity);
+
     // check that it follows the protocol
 
     info!("Verifying a contribution to contain proper powers and correspond to the public key...");
@@ -155,33 +204,115 @@ pub fn transform_pok_and_correctness<T: Engine + Sync>(
             .expect("unable to create a memory map for output")
     };
 
-    {
-        (&mut writable_map[0..])
-            .write_all(response_hash.as_slice())
-            .expect("unable to write a default hash to mmap");
-
-        writable_map
-            .flush()
-            .expect("unable to write hash to new challenge file");
+    if response_hasher.is_none() {
+        let response_hash = calculate_hash(&response_readable_map);
+        write_response_hash(&response_hash, response_hash_filename, &mut writable_map);
     }
 
-    let res = Phase1::verification(
-        &challenge_readable_map,
-        &response_readable_map,
-        &mut writable_map,
-        &public_key,
-        current_accumulator_hash.as_slice(),
-        PREVIOUS_CHALLENGE_IS_COMPRESSED,
-        CONTRIBUTION_IS_COMPRESSED,
-        COMPRESS_NEW_CHALLENGE,
-        check_input_correctness,
-        check_output_correctness,
-        subgroup_check_mode,
-        &parameters,
-    );
+    let res = if continue_on_subgroup_warning {
+        Phase1::verification_tolerating_subgroup_failures(
+            &challenge_readable_map,
+            response,
+            &mut writable_map,
+            &public_key,
+            current_accumulator_hash.as_slice(),
+            previous_challenge_is_compressed,
+            contribution_is_compressed,
+            COMPRESS_NEW_CHALLENGE,
+            check_input_correctness,
+            check_output_correctness,
+            subgroup_check_mode,
+            &parameters,
+        )
+        .map(|warnings| {
+            for warning in &warnings {
+                warn!(
+                    "{} elements in range [{}, {}) are not in the prime order subgroup",
+                    warning.element_type, warning.start, warning.end
+                );
+            }
+            warn!("{} batches failed the subgroup check", warnings.len());
+            print_legacy_banner();
+            None
+        })
+    } else if let Some(validation_cache_filename) = validation_cache_filename {
+        if timings_csv.is_some() {
+            warn!("Timings are not recorded when verifying with a validation cache");
+        }
+        let validation_cache = ValidationCache::open(validation_cache_filename, curve_kind, parameters)
+            .expect("unable to read the validation cache");
+        info!(
+            "Ranges in the validation cache skip the subgroup check ({} cached)",
+            validation_cache.len()
+        );
+        let res = Phase1::verification_with_validation_cache(
+            &challenge_readable_map,
+            response,
+            &mut writable_map,
+            &public_key,
+            current_accumulator_hash.as_slice(),
+            previous_challenge_is_compressed,
+            contribution_is_compressed,
+            COMPRESS_NEW_CHALLENGE,
+            check_input_correctness,
+            check_output_correctness,
+            subgroup_check_mode,
+            &validation_cache,
+            &parameters,
+        );
+        if res.is_ok() {
+            validation_cache.save().expect("unable to write the validation cache");
+        }
+        res.map(Some)
+    } else if let Some(timings_csv) = timings_csv {
+        let (timings, timings_writer) =
+            spawn_timings_writer(timings_csv, parameters.chunk_index).expect("unable to create the timings file");
+        let res = Phase1::verification_with_timings(
+            &challenge_readable_map,
+            response,
+            &mut writable_map,
+            &public_key,
+            current_accumulator_hash.as_slice(),
+            previous_challenge_is_compressed,
+            contribution_is_compressed,
+            COMPRESS_NEW_CHALLENGE,
+            check_input_correctness,
+            check_output_correctness,
+            subgroup_check_mode,
+            &timings,
+            &parameters,
+        );
+        drop(timings);
+        timings_writer
+            .join()
+            .expect("the timings writer panicked")
+            .expect("unable to write the timings file");
+        res.map(Some)
+    } else {
+        Phase1::verification(
+            &challenge_readable_map,
+            response,
+            &mut writable_map,
+            &public_key,
+            current_accumulator_hash.as_slice(),
+            previous_challenge_is_compressed,
+            contribution_is_compressed,
+            COMPRESS_NEW_CHALLENGE,
+            check_input_correctness,
+            check_output_correctness,
+            subgroup_check_mode,
+            &parameters,
+        )
+        .map(Some)
+    };
 
     info!("Verification succeeded!");
 
+    if let Some(response_hasher) = response_hasher {
+        let response_hash = response_hasher.join().expect("the response hasher panicked");
+        write_response_hash(&response_hash, response_hash_filename, &mut writable_map);
+    }
+
     writable_map.flush().expect("must flush the memory map");
 
     let new_challenge_readable_map = writable_map.make_read_only().expect("must make a map readonly");
@@ -198,10 +329,250 @@ pub fn transform_pok_and_correctness<T: Engine + Sync>(
     info!("Done! new challenge file contains the new challenge file. The other files");
     info!("were left alone.");
 
-    if let Err(e) = res {
-        info!("Verification failed: {}", e);
-        panic!("INVALID CONTRIBUTION!!!");
-    } else {
-        info!("Verification succeeded!");
+    match res {
+        Ok(Some(report)) => info!("Verification succeeded, {}", report),
+        Ok(None) => info!("Verification succeeded!"),
+        Err(e) => {
+            info!("Verification failed: {}", e);
+            panic!("INVALID CONTRIBUTION!!!");
+        }
+    }
+}
+
+/// Same as `transform_pok_and_correctness`, except that none of the files is mapped or read
+/// as a whole: the files are hashed while streaming them, and the contribution is verified
+/// one batch at a time with `Phase1::verify_streaming`, so that the memory used is bounded
+/// regardless of the power or of the chunk size. Only available for Groth16.
+#[allow(clippy::too_many_arguments)]
+fn transform_pok_and_correctness_low_memory<T: Engine + Sync>(
+    (challenge_filename, challenge_hash_filename, check_input_correctness): (&str, &str, CheckForCorrectness),
+    (response_filename, response_hash_filename, check_output_correctness): (&str, &str, CheckForCorrectness),
+    (new_challenge_filename, new_challenge_hash_filename): (&str, &str),
+    subgroup_check_mode: SubgroupCheckMode,
+    (previous_challenge_is_compressed, contribution_is_compressed): (UseCompression, UseCompression),
+    expected_response_hash: Option<&str>,
+    reject_identity: bool,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    let challenge_reader = OpenOptions::new()
+        .read(true)
+        .open(challenge_filename)
+        .expect("unable open challenge file in this directory");
+    let response_reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+
+    {
+        let expected_challenge_length = parameters.get_length(previous_challenge_is_compressed) as u64;
+        let challenge_length = challenge_reader
+            .metadata()
+            .expect("unable to get filesystem metadata for challenge file")
+            .len();
+        if challenge_length != expected_challenge_length {
+            panic!(
+                "The size of challenge file should be {}, but it's {}, so something isn't right.",
+                expected_challenge_length, challenge_length
+            );
+        }
+    }
+
+    // Skip the header of framed responses, after checking it matches our parameters
+    let header_size = {
+        let expected_header = ResponseHeader::new(curve_kind, contribution_is_compressed, parameters);
+        let mut header = vec![];
+        (&response_reader)
+            .take(HEADER_SIZE as u64)
+            .read_to_end(&mut header)
+            .expect("unable to read the response file");
+        match ResponseHeader::read(&header).expect("invalid response header") {
+            Some(header) => {
+                header.validate(&expected_header).expect("invalid response header");
+                HEADER_SIZE
+            }
+            None => 0,
+        }
+    };
+
+    {
+        let expected_response_length =
+            (header_size + parameters.get_response_length(contribution_is_compressed)) as u64;
+        let response_length = response_reader
+            .metadata()
+            .expect("unable to get filesystem metadata for response file")
+            .len();
+        if response_length != expected_response_length {
+            panic!(
+                "The size of response file should be {}, but it's {}, so something isn't right.",
+                expected_response_length, response_length
+            );
+        }
     }
+
+    let response_hash = stream_hash(&response_reader);
+    if let Some(expected_response_hash) = expected_response_hash {
+        if hex::encode(&response_hash) != expected_response_hash.trim().to_lowercase() {
+            panic!(
+                "The hash of the response file is {}, but the expected hash is {}. This is not the right response.",
+                hex::encode(&response_hash),
+                expected_response_hash
+            );
+        }
+        info!("The response file has the expected hash");
+    }
+
+    info!("Calculating previous challenge hash...");
+
+    let current_accumulator_hash = stream_hash(&challenge_reader);
+    std::fs::File::create(challenge_hash_filename)
+        .expect("unable to open current accumulator hash file")
+        .write_all(current_accumulator_hash.as_slice())
+        .expect("unable to write current accumulator hash");
+
+    info!("Hash of the `challenge` file for verification:");
+    print_hash(&current_accumulator_hash);
+
+    let read_window = |reader: &File, offset: usize, range: std::ops::Range<usize>| unsafe {
+        MmapOptions::new()
+            .offset((offset + range.start) as u64)
+            .len(range.end - range.start)
+            .map(reader)
+    };
+
+    // Check the hash chain - a new response must be based on the previous challenge!
+    {
+        let response_challenge_hash = read_window(&response_reader, header_size, 0..64)
+            .expect("couldn't read hash of challenge file from response file");
+
+        info!("`response` was based on the hash:");
+        print_hash(&response_challenge_hash);
+
+        if &response_challenge_hash[..] != current_accumulator_hash.as_slice() {
+            panic!("Hash chain failure. This is not the right response.");
+        }
+    }
+
+    // get the contributor's public key, which ends the response
+    let public_key = {
+        let response_length = parameters.get_response_length(contribution_is_compressed);
+        let public_key_bytes = read_window(
+            &response_reader,
+            header_size,
+            response_length - parameters.public_key_size..response_length,
+        )
+        .expect("unable to read the response file's public key");
+        PublicKey::<T>::deserialize(&mut &public_key_bytes[..])
+            .expect("wasn't able to deserialize the response file's public key")
+    };
+    check_identity_secrets(&public_key, reject_identity);
+
+    info!("Verifying a contribution to contain proper powers and correspond to the public key, one batch at a time...");
+
+    // Create new challenge file in this directory
+    let mut writer = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(new_challenge_filename)
+        .expect("unable to create new challenge file in this directory");
+    writer
+        .set_len(parameters.accumulator_size as u64)
+        .expect("must make output file large enough");
+
+    std::fs::File::create(response_hash_filename)
+        .expect("unable to open response hash file")
+        .write_all(response_hash.as_slice())
+        .expect("unable to write response hash");
+    info!("Hash of the response file for verification:");
+    print_hash(&response_hash);
+    writer
+        .write_all(response_hash.as_slice())
+        .expect("unable to write the response hash to the new challenge");
+
+    let res = Phase1::verify_streaming(
+        |range| Ok(read_window(&challenge_reader, 0, range)?),
+        |range| Ok(read_window(&response_reader, header_size, range)?),
+        |offset, bytes| {
+            writer.seek(SeekFrom::Start(offset as u64))?;
+            writer.write_all(bytes)?;
+            Ok(())
+        },
+        &public_key,
+        current_accumulator_hash.as_slice(),
+        (previous_challenge_is_compressed, check_input_correctness),
+        (contribution_is_compressed, check_output_correctness),
+        COMPRESS_NEW_CHALLENGE,
+        subgroup_check_mode,
+        parameters,
+    );
+
+    writer.sync_all().expect("unable to write the new challenge file");
+    let recompressed_hash = stream_hash(&writer);
+
+    std::fs::File::create(new_challenge_hash_filename)
+        .expect("unable to open new challenge hash file")
+        .write_all(recompressed_hash.as_slice())
+        .expect("unable to write new challenge hash");
+
+    info!("Here's the BLAKE2b hash of the decompressed participant's response as new_challenge file:");
+    print_hash(&recompressed_hash);
+
+    match res {
+        Ok(report) => info!("Verification succeeded, {}", report),
+        Err(e) => {
+            info!("Verification failed: {}", e);
+            panic!("INVALID CONTRIBUTION!!!");
+        }
+    }
+}
+
+/// Hashes the whole file while streaming it, instead of mapping it as a whole
+fn stream_hash(mut file: &File) -> GenericArray<u8, U64> {
+    file.seek(SeekFrom::Start(0)).expect("unable to rewind the file");
+    let mut hasher = HashWriter::new(io::sink());
+    io::copy(&mut BufReader::new(file), &mut hasher).expect("unable to hash the file");
+    hasher.into_hash()
+}
+
+/// A secret of 1 passes verification, but leaves the elements it multiplies unchanged
+fn check_identity_secrets<T: Engine>(public_key: &PublicKey<T>, reject_identity: bool) {
+    let identity_secrets = public_key.identity_secrets();
+    if !identity_secrets.is_empty() {
+        warn!("==================================================================");
+        warn!(
+            "The {} secrets of the contribution are 1, so it adds no randomness to them!",
+            identity_secrets.join(", ")
+        );
+        warn!("==================================================================");
+        if reject_identity {
+            panic!(
+                "The contribution is a no-op for its {} secrets, which are 1",
+                identity_secrets.join(", ")
+            );
+        }
+    }
+}
+
+/// Writes the hash of the response to its file, and at the start of the new challenge
+fn write_response_hash(response_hash: &[u8], response_hash_filename: &str, new_challenge: &mut MmapMut) {
+    std::fs::File::create(response_hash_filename)
+        .expect("unable to open response hash file")
+        .write_all(response_hash)
+        .expect("unable to write response hash");
+
+    info!("Hash of the response file for verification:");
+    print_hash(response_hash);
+
+    (&mut new_challenge[0..])
+        .write_all(response_hash)
+        .expect("unable to write a default hash to mmap");
+    new_challenge
+        .flush()
+        .expect("unable to write hash to new challenge file");
+}
+
+fn print_legacy_banner() {
+    warn!("!!! Subgroup check failures are only logged and do NOT abort the verification !!!");
+    warn!("!!! This is NOT a full-security verification, only use it to audit legacy files !!!");
 }
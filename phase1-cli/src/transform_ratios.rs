@@ -1,15 +1,33 @@
+use crate::open_input;
 use phase1::{Phase1, Phase1Parameters};
-use setup_utils::{calculate_hash, print_hash, CheckForCorrectness, UseCompression};
+use setup_utils::{calculate_hash, print_hash, CheckForCorrectness, SectionMask, UseCompression};
 
 use zexe_algebra::PairingEngine as Engine;
 
 use memmap::*;
-use std::fs::OpenOptions;
-use tracing::info;
+use std::{
+    fs::File,
+    io::{self, BufReader, Read},
+};
+use tracing::{info, warn};
 
+/// Verifies the ratios of a combined response. A response larger than expected is rejected,
+/// unless `truncate_extra` is set, in which case only its expected prefix is verified, e.g. to
+/// ignore a metadata footer appended by the writer. A gzip or zstd encoded response is
+/// decoded to a temporary file next to it, which is mapped instead and removed afterwards.
+///
+/// With `decompress_verify`, the response holds compressed elements, and each batch is
+/// decompressed in memory right before its ratios are checked, so that verification runs on
+/// uncompressed points without writing a decompressed copy of the response to disk. The
+/// public key which follows a compressed response is ignored.
+#[allow(clippy::too_many_arguments)]
 pub fn transform_ratios<T: Engine + Sync>(
     response_filename: &str,
     check_input_correctness: CheckForCorrectness,
+    mmap_window: bool,
+    sections: SectionMask,
+    truncate_extra: bool,
+    decompress_verify: bool,
     parameters: &Phase1Parameters<T>,
 ) {
     info!(
@@ -17,13 +35,10 @@ pub fn transform_ratios<T: Engine + Sync>(
         parameters.total_size_in_log2
     );
 
-    // Try to load response file from disk.
-    let response_reader = OpenOptions::new()
-        .read(true)
-        .open(response_filename)
-        .expect("unable open response file in this directory");
+    // Try to load response file from disk, decoding it to a temporary file if it is encoded
+    let response_reader = open_input(response_filename).expect("unable open response file in this directory");
 
-    {
+    let expected_response_length = {
         let parameters = Phase1Parameters::<T>::new_chunk(
             parameters.contribution_mode,
             0,
@@ -35,18 +50,65 @@ pub fn transform_ratios<T: Engine + Sync>(
         let metadata = response_reader
             .metadata()
             .expect("unable to get filesystem metadata for response file");
-        let expected_response_length = parameters.accumulator_size;
-        if metadata.len() != (expected_response_length as u64) {
+        let (expected_response_length, public_key_size) = if decompress_verify {
+            (
+                (parameters.contribution_size - parameters.public_key_size) as u64,
+                parameters.public_key_size as u64,
+            )
+        } else {
+            (parameters.accumulator_size as u64, 0)
+        };
+        if metadata.len() < expected_response_length {
             panic!(
-                "The size of response file should be {}, but it's {}, so something isn't right.",
+                "The size of response file should be {}, but it's {}, which is {} bytes too small.",
                 expected_response_length,
-                metadata.len()
+                metadata.len(),
+                expected_response_length - metadata.len()
+            );
+        } else if public_key_size > 0 && metadata.len() == expected_response_length + public_key_size {
+            info!("The response is followed by its public key, which is not needed to verify the ratios");
+        } else if metadata.len() > expected_response_length {
+            if !truncate_extra {
+                panic!(
+                    "The size of response file should be {}, but it's {}, which is {} bytes too large. \
+                     Use --truncate-extra if the response is followed by other data.",
+                    expected_response_length,
+                    metadata.len(),
+                    metadata.len() - expected_response_length
+                );
+            }
+            warn!(
+                "The size of response file should be {}, but it's {}, so the last {} bytes are ignored.",
+                expected_response_length,
+                metadata.len(),
+                metadata.len() - expected_response_length
             );
         }
+        expected_response_length
+    };
+
+    let compressed_response = if decompress_verify {
+        UseCompression::Yes
+    } else {
+        UseCompression::No
+    };
+
+    if mmap_window {
+        transform_ratios_windowed(
+            &response_reader,
+            expected_response_length,
+            compressed_response,
+            check_input_correctness,
+            sections,
+            parameters,
+        );
+        return;
     }
 
+    // Only the expected prefix of the file is mapped, any extra data is ignored
     let response_readable_map = unsafe {
         MmapOptions::new()
+            .len(expected_response_length as usize)
             .map(&response_reader)
             .expect("unable to create a memory map for input")
     };
@@ -59,11 +121,58 @@ pub fn transform_ratios<T: Engine + Sync>(
     // check that it follows the protocol
     info!("Verifying a contribution to contain proper powers and correspond to the public key...");
 
-    let res = Phase1::aggregate_verification(
-        (&response_readable_map, UseCompression::No, check_input_correctness),
+    let res = Phase1::aggregate_verification_of_sections(
+        (&response_readable_map, compressed_response, check_input_correctness),
+        sections,
         &parameters,
     );
 
+    match res {
+        Ok(report) => info!("Verification succeeded, {}", report),
+        Err(e) => {
+            info!("Verification failed: {}", e);
+            panic!("INVALID CONTRIBUTION!!!");
+        }
+    }
+}
+
+/// Verifies the response while mapping only the current batch of each section in memory,
+/// so that the resident memory stays bounded regardless of the size of the response.
+fn transform_ratios_windowed<T: Engine + Sync>(
+    response_reader: &File,
+    response_length: u64,
+    compressed_response: UseCompression,
+    check_input_correctness: CheckForCorrectness,
+    sections: SectionMask,
+    parameters: &Phase1Parameters<T>,
+) {
+    // Hash the response while streaming it, instead of mapping it as a whole
+    let response_hash = {
+        let mut hasher = HashWriter::new(io::sink());
+        io::copy(&mut BufReader::new(response_reader).take(response_length), &mut hasher)
+            .expect("unable to hash the response file");
+        hasher.into_hash()
+    };
+
+    info!("Hash of the response file for verification:");
+    print_hash(&response_hash);
+
+    info!("Verifying a contribution to contain proper powers, one mmap window at a time...");
+
+    let res = Phase1::aggregate_verification_windowed(
+        |range| {
+            Ok(unsafe {
+                MmapOptions::new()
+                    .offset(range.start as u64)
+                    .len(range.end - range.start)
+                    .map(response_reader)?
+            })
+        },
+        (compressed_response, check_input_correctness),
+        sections,
+        parameters,
+    );
+
     if let Err(e) = res {
         info!("Verification failed: {}", e);
         panic!("INVALID CONTRIBUTION!!!");
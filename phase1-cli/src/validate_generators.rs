@@ -0,0 +1,52 @@
+use crate::map_input;
+use phase1::{helpers::check_generators, ContributionMode, Phase1Parameters, ResponseHeader, HEADER_SIZE};
+use setup_utils::{CheckForCorrectness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::fs::OpenOptions;
+use tracing::info;
+
+/// Checks that the first TauG1 and TauG2 elements of a challenge or response are the
+/// generators, reading only those elements. This is a sub-second sanity check of a large
+/// file, and NOT a replacement for a full verification.
+pub fn validate_generators<T: Engine + Sync>(
+    input_filename: &str,
+    compressed: UseCompression,
+    check_input_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    if parameters.contribution_mode == ContributionMode::Chunked && parameters.chunk_index != 0 {
+        panic!(
+            "Only the first chunk contains the generators, but this is chunk {}",
+            parameters.chunk_index
+        );
+    }
+    info!("Will check the generators of {}", input_filename);
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(input_filename)
+        .expect("unable open input file in this directory");
+    let readable_map = map_input(&reader).expect("unable to create a memory map for input");
+    let input = match ResponseHeader::read(&readable_map).expect("invalid response header") {
+        Some(_) => &readable_map[HEADER_SIZE..],
+        None => &readable_map[..],
+    };
+    let expected_length = parameters.get_length(compressed);
+    if input.len() < expected_length {
+        panic!(
+            "The size of {} should be at least {}, but it's {}, so something isn't right.",
+            input_filename,
+            expected_length,
+            input.len()
+        );
+    }
+
+    if let Err(e) = check_generators((input, compressed, check_input_correctness), parameters) {
+        info!("Checking the generators failed: {}", e);
+        panic!("INVALID GENERATORS!!!");
+    }
+
+    info!("The first TauG1 and TauG2 elements are the generators, the rest of the file was NOT checked");
+}
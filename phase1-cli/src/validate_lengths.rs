@@ -0,0 +1,89 @@
+use phase1::{ContributionMode, Phase1Parameters, ProvingSystem, HEADER_SIZE};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use serde::Serialize;
+use std::{fs::File, path::Path};
+use tracing::{error, info};
+
+/// The result of checking the length of the response file of a single chunk
+#[derive(Debug, Serialize)]
+pub struct LengthEntry {
+    pub chunk_index: usize,
+    pub filename: String,
+    /// The expected length of the response, without a header
+    pub expected_length: u64,
+    /// The length of the file, or `None` if it is missing
+    pub actual_length: Option<u64>,
+    pub valid: bool,
+}
+
+/// Checks the length of the `response.{chunk_index}` file of every chunk in `directory`
+/// against the size a compressed response to that chunk should have, with or without a
+/// header. Only the file metadata is read, so this is a cheap preflight which catches
+/// truncated or missing uploads before any of them is verified. Every wrong file is reported,
+/// and written as a JSON report to `json_filename` when it is provided, before failing.
+pub fn validate_lengths<T: Engine + Sync>(
+    directory: &str,
+    json_filename: Option<&str>,
+    parameters: &Phase1Parameters<T>,
+) {
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+    let num_chunks = (upper_bound + parameters.chunk_size - 1) / parameters.chunk_size;
+    info!(
+        "Will validate the lengths of the responses to {} chunks in {}",
+        num_chunks, directory
+    );
+
+    let entries = (0..num_chunks)
+        .map(|chunk_index| {
+            let chunk_parameters =
+                parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, parameters.chunk_size);
+            let expected_length = chunk_parameters.contribution_size as u64;
+            let path = Path::new(directory).join(format!("response.{}", chunk_index));
+            let actual_length = File::open(&path)
+                .and_then(|file| file.metadata())
+                .map(|metadata| metadata.len())
+                .ok();
+            let valid = match actual_length {
+                Some(length) => length == expected_length || length == expected_length + HEADER_SIZE as u64,
+                None => false,
+            };
+
+            match actual_length {
+                Some(length) if !valid => error!(
+                    "Chunk {}: {} is {} bytes long, but it should be {} bytes without a header",
+                    chunk_index,
+                    path.display(),
+                    length,
+                    expected_length
+                ),
+                None => error!("Chunk {}: {} is missing", chunk_index, path.display()),
+                _ => {}
+            }
+
+            LengthEntry {
+                chunk_index,
+                filename: path.display().to_string(),
+                expected_length,
+                actual_length,
+                valid,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(json_filename) = json_filename {
+        let json_file = File::create(json_filename).expect("unable to create the JSON report");
+        serde_json::to_writer_pretty(json_file, &entries).expect("unable to write the JSON report");
+    }
+
+    let num_invalid = entries.iter().filter(|entry| !entry.valid).count();
+    if num_invalid > 0 {
+        panic!("{} out of {} responses have the wrong length", num_invalid, num_chunks);
+    }
+
+    info!("All {} responses have the right length", num_chunks);
+}
@@ -0,0 +1,77 @@
+use crate::map_input;
+use phase1::{
+    helpers::{verify_chunk, CurveKind},
+    Phase1Parameters, PublicKey, ResponseHeader,
+};
+use setup_utils::{calculate_hash, print_hash, CheckForCorrectness, SubgroupCheckMode, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::fs::OpenOptions;
+use tracing::info;
+
+const PREVIOUS_CHALLENGE_IS_COMPRESSED: UseCompression = UseCompression::No;
+const CONTRIBUTION_IS_COMPRESSED: UseCompression = UseCompression::Yes;
+
+/// Verifies a response against the challenge it claims to be based on, without producing
+/// a new challenge. The digest the contributor's key must be bound to is recomputed from
+/// the challenge instead of being trusted, and the hash embedded at the start of the
+/// response must match it, so the response is checked to extend the digest chain.
+pub fn verify_chain<T: Engine + Sync>(
+    challenge_filename: &str,
+    response_filename: &str,
+    check_input_correctness: CheckForCorrectness,
+    check_output_correctness: CheckForCorrectness,
+    subgroup_check_mode: SubgroupCheckMode,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!("Verifying that {} extends {}", response_filename, challenge_filename);
+
+    let challenge_reader = OpenOptions::new()
+        .read(true)
+        .open(challenge_filename)
+        .expect("unable open challenge file in this directory");
+    let challenge_readable_map = map_input(&challenge_reader).expect("unable to create a memory map for the challenge");
+    let challenge = &challenge_readable_map[..];
+
+    let response_reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+    let response_readable_map = map_input(&response_reader).expect("unable to create a memory map for the response");
+    let expected_header = ResponseHeader::new(curve_kind, CONTRIBUTION_IS_COMPRESSED, parameters);
+    let response = ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+
+    // The digest is the hash of the challenge the contributor received
+    let digest = calculate_hash(challenge);
+    info!("Hash of the `challenge` file:");
+    print_hash(&digest);
+
+    // Check the hash chain - the response must be based on the previous challenge!
+    let response_challenge_hash = response.get(0..64).expect("must read the hash from the response file");
+    info!("`response` was based on the hash:");
+    print_hash(response_challenge_hash);
+    if response_challenge_hash != digest.as_slice() {
+        panic!("Hash chain failure. This response is not based on the provided challenge.");
+    }
+
+    let public_key = PublicKey::read(response, CONTRIBUTION_IS_COMPRESSED, parameters)
+        .expect("wasn't able to deserialize the response file's public key");
+
+    let res = verify_chunk(
+        (challenge, PREVIOUS_CHALLENGE_IS_COMPRESSED, check_input_correctness),
+        (response, CONTRIBUTION_IS_COMPRESSED, check_output_correctness),
+        &public_key,
+        digest.as_slice(),
+        subgroup_check_mode,
+        parameters,
+    );
+
+    if let Err(e) = res {
+        info!("Verification failed: {}", e);
+        panic!("INVALID CONTRIBUTION!!!");
+    } else {
+        info!("Verification succeeded, the response extends the digest chain");
+    }
+}
@@ -0,0 +1,70 @@
+use phase1::{Phase1, Phase1Parameters};
+use setup_utils::CheckForCorrectness;
+
+use zexe_algebra::PairingEngine as Engine;
+
+use memmap::*;
+use std::fs::OpenOptions;
+use tracing::info;
+
+fn map_with_length(filename: &str, expected_length: usize) -> Mmap {
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(filename)
+        .expect("unable open response file in this directory");
+    let metadata = reader
+        .metadata()
+        .expect("unable to get filesystem metadata for response file");
+    if metadata.len() != (expected_length as u64) {
+        panic!(
+            "The size of response file should be {}, but it's {}, so something isn't right.",
+            expected_length,
+            metadata.len()
+        );
+    }
+
+    unsafe {
+        MmapOptions::new()
+            .map(&reader)
+            .expect("unable to create a memory map for input")
+    }
+}
+
+/// Checks that a compressed response decompresses to exactly the provided uncompressed response.
+pub fn verify_compression<T: Engine + Sync>(
+    compressed_filename: &str,
+    uncompressed_filename: &str,
+    check_input_correctness: CheckForCorrectness,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!(
+        "Will verify that the compressed and uncompressed responses for 2^{} powers of tau agree",
+        parameters.total_size_in_log2
+    );
+
+    let compressed = map_with_length(compressed_filename, parameters.contribution_size);
+    let uncompressed = map_with_length(
+        uncompressed_filename,
+        parameters.accumulator_size + parameters.public_key_size,
+    );
+
+    // The previous challenge hash is never compressed
+    if compressed[0..parameters.hash_size] != uncompressed[0..parameters.hash_size] {
+        panic!("The responses are based on different challenges!");
+    }
+
+    // The public key is serialized identically in both forms
+    let compressed_key = &compressed[parameters.contribution_size - parameters.public_key_size..];
+    let uncompressed_key = &uncompressed[parameters.accumulator_size..];
+    if compressed_key != uncompressed_key {
+        panic!("The responses contain different public keys!");
+    }
+
+    let res = Phase1::verify_compression(&compressed, &uncompressed, check_input_correctness, &parameters);
+    if let Err(e) = res {
+        info!("Verification failed: {}", e);
+        panic!("INVALID COMPRESSION!!!");
+    } else {
+        info!("Compressed and uncompressed responses agree!");
+    }
+}
@@ -0,0 +1,35 @@
+use crate::map_input;
+use setup_utils::{calculate_hash, print_hash};
+
+use std::{fs::OpenOptions, process};
+use tracing::{error, info};
+
+/// Recomputes the final hash of a published combined file and checks that it matches
+/// the hex encoded hash which was announced when the contributions were combined.
+/// A mismatch exits with code 1, like the `verify-final` of phase 2.
+pub fn verify_final(combined_filename: &str, expected_hash: &str) {
+    info!("Verifying the final hash of {}", combined_filename);
+
+    let expected_hash = hex::decode(expected_hash).expect("could not hex decode the expected final hash");
+
+    let reader = OpenOptions::new()
+        .read(true)
+        .open(combined_filename)
+        .expect("unable open combined file in this directory");
+    let combined_map = map_input(&reader).expect("unable to read the combined file");
+
+    let final_hash = calculate_hash(&combined_map);
+    info!("FINAL HASH of the combined parameters: {}", hex::encode(&final_hash));
+    print_hash(&final_hash);
+
+    if final_hash.as_slice() != expected_hash.as_slice() {
+        error!(
+            "The final hash {} does not match the expected hash {}, this is NOT the authentic combined file!",
+            hex::encode(&final_hash),
+            hex::encode(&expected_hash)
+        );
+        process::exit(1);
+    }
+
+    info!("The combined file matches the expected final hash");
+}
@@ -0,0 +1,162 @@
+use crate::map_input;
+use phase1::{helpers::CurveKind, Phase1Parameters};
+use setup_utils::calculate_hash;
+
+use zexe_algebra::PairingEngine as Engine;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    path::Path,
+};
+use tracing::{error, info};
+
+/// A manifest of the chunk files of a ceremony round, e.g.
+/// `{"num_chunks":2,"chunks":[{"chunk_index":0,"filename":"challenge_0","hash":"..."},...]}`
+/// where the hash is the hex encoded `calculate_hash` of the file. The curve and power of
+/// the ceremony are optional, for manifests written before they were recorded. The manifest
+/// of a combination also records the hash of the combined file the chunks went into.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Manifest {
+    pub num_chunks: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power: Option<usize>,
+    pub chunks: Vec<ManifestEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub combined_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    pub chunk_index: usize,
+    /// The chunk file, relative to the directory of the manifest
+    pub filename: String,
+    pub hash: String,
+}
+
+/// Recomputes the hash of every chunk file listed in the manifest and checks that each
+/// chunk of `0..num_chunks` is listed exactly once, so that a downloaded set of chunks can
+/// be checked in full before contributing. Every problem is reported before failing.
+pub fn verify_manifest(manifest_filename: &str) {
+    info!("Verifying the chunk files listed in {}", manifest_filename);
+
+    let manifest = read_manifest(manifest_filename);
+    verify_chunk_files(manifest_filename, &manifest);
+
+    info!("All {} chunk files match the manifest", manifest.num_chunks);
+}
+
+/// Verifies the chunk files listed in the manifest as `verify_manifest` does, after checking
+/// that the manifest was written for the curve and power of `parameters`, and returns the
+/// chunk files in the order of their chunk index, ready to be combined.
+pub fn manifest_chunk_files<T: Engine>(
+    manifest_filename: &str,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) -> Vec<String> {
+    info!("Reading the chunk files to combine from {}", manifest_filename);
+
+    let manifest = read_manifest(manifest_filename);
+    if let Some(curve) = &manifest.curve {
+        if curve.to_lowercase() != curve_name(curve_kind) {
+            panic!(
+                "The manifest was written for the {} curve, but the chunks are combined for {}",
+                curve,
+                curve_name(curve_kind)
+            );
+        }
+    }
+    if let Some(power) = manifest.power {
+        if power != parameters.total_size_in_log2 {
+            panic!(
+                "The manifest was written for power {}, but the chunks are combined for power {}",
+                power, parameters.total_size_in_log2
+            );
+        }
+    }
+
+    let chunk_files = verify_chunk_files(manifest_filename, &manifest);
+    info!("All {} chunk files match the manifest", manifest.num_chunks);
+    chunk_files
+}
+
+/// The name of the curve, as recorded in a manifest
+pub(crate) fn curve_name(curve_kind: &CurveKind) -> &'static str {
+    match curve_kind {
+        CurveKind::Bls12_377 => "bls12_377",
+        CurveKind::BW6 => "bw6",
+    }
+}
+
+fn read_manifest(manifest_filename: &str) -> Manifest {
+    let manifest_file = File::open(manifest_filename).expect("unable to open the manifest file");
+    serde_json::from_reader(manifest_file).expect("unable to parse the manifest file")
+}
+
+/// Checks every chunk file listed in the manifest, failing after reporting every problem,
+/// and returns their paths in the order of their chunk index
+fn verify_chunk_files(manifest_filename: &str, manifest: &Manifest) -> Vec<String> {
+    let directory = Path::new(manifest_filename).parent().unwrap_or_else(|| Path::new(""));
+
+    let mut problems = 0;
+    let mut chunk_files = vec![];
+    let mut entries = BTreeMap::new();
+    for entry in manifest.chunks.iter() {
+        if entry.chunk_index >= manifest.num_chunks {
+            error!(
+                "Chunk {} ({}) is out of range for {} chunks",
+                entry.chunk_index, entry.filename, manifest.num_chunks
+            );
+            problems += 1;
+        } else if entries.insert(entry.chunk_index, entry).is_some() {
+            error!("Chunk {} is listed more than once", entry.chunk_index);
+            problems += 1;
+        }
+    }
+
+    for chunk_index in 0..manifest.num_chunks {
+        let entry = match entries.get(&chunk_index) {
+            Some(entry) => entry,
+            None => {
+                error!("Chunk {} is missing from the manifest", chunk_index);
+                problems += 1;
+                continue;
+            }
+        };
+
+        let path = directory.join(&entry.filename);
+        let reader = match OpenOptions::new().read(true).open(&path) {
+            Ok(reader) => reader,
+            Err(e) => {
+                error!("Chunk {}: unable to open {}: {}", chunk_index, path.display(), e);
+                problems += 1;
+                continue;
+            }
+        };
+        let chunk_map = map_input(&reader).expect("unable to read the chunk file");
+        let hash = hex::encode(calculate_hash(&chunk_map));
+        if hash != entry.hash.to_lowercase() {
+            error!(
+                "Chunk {}: the hash of {} is {}, but the manifest lists {}",
+                chunk_index,
+                path.display(),
+                hash,
+                entry.hash
+            );
+            problems += 1;
+        }
+        chunk_files.push(path.display().to_string());
+    }
+
+    if problems > 0 {
+        panic!(
+            "Found {} problems with the chunk files listed in the manifest",
+            problems
+        );
+    }
+
+    chunk_files
+}
@@ -0,0 +1,83 @@
+use crate::map_input;
+use phase1::{helpers::CurveKind, Phase1Parameters, PublicKey, ResponseHeader};
+use setup_utils::{calculate_hash, UseCompression};
+
+use zexe_algebra::{CanonicalDeserialize, PairingEngine as Engine};
+
+use std::fs::OpenOptions;
+use tracing::{error, info};
+
+const CONTRIBUTION_IS_COMPRESSED: UseCompression = UseCompression::Yes;
+
+/// Checks that a published transcript entry, i.e. the hex encoded hash of a response and
+/// the hex encoded public key written at its end, matches the response held locally, so
+/// that a contributor can independently confirm their contribution was recorded faithfully.
+/// Every diverging field is reported before failing.
+pub fn verify_my_contribution<T: Engine + Sync>(
+    response_filename: &str,
+    published_hash: &str,
+    published_public_key: &str,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!(
+        "Verifying that the published transcript entry matches {}",
+        response_filename
+    );
+
+    let response_reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+    let response_readable_map = map_input(&response_reader).expect("unable to create a memory map for the response");
+
+    let mut mismatches = vec![];
+
+    let hash = hex::encode(calculate_hash(&response_readable_map));
+    if hash != published_hash.trim().to_lowercase() {
+        error!(
+            "The hash of the response is {}, but the published hash is {}",
+            hash, published_hash
+        );
+        mismatches.push("hash");
+    }
+
+    let expected_header = ResponseHeader::new(curve_kind, CONTRIBUTION_IS_COMPRESSED, parameters);
+    let response = ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+    let public_key = PublicKey::<T>::read(response, CONTRIBUTION_IS_COMPRESSED, parameters)
+        .expect("wasn't able to deserialize the response file's public key");
+    let published_public_key_bytes =
+        hex::decode(published_public_key.trim()).expect("the published public key is not valid hex");
+    let published_public_key = PublicKey::<T>::deserialize(&mut &published_public_key_bytes[..])
+        .expect("wasn't able to deserialize the published public key");
+
+    let fields = [
+        ("public key tau_g1", public_key.tau_g1 == published_public_key.tau_g1),
+        (
+            "public key alpha_g1",
+            public_key.alpha_g1 == published_public_key.alpha_g1,
+        ),
+        ("public key beta_g1", public_key.beta_g1 == published_public_key.beta_g1),
+        ("public key tau_g2", public_key.tau_g2 == published_public_key.tau_g2),
+        (
+            "public key alpha_g2",
+            public_key.alpha_g2 == published_public_key.alpha_g2,
+        ),
+        ("public key beta_g2", public_key.beta_g2 == published_public_key.beta_g2),
+    ];
+    for (field, matches) in fields.iter() {
+        if !matches {
+            error!("The {} of the response differs from the published one", field);
+            mismatches.push(*field);
+        }
+    }
+
+    if !mismatches.is_empty() {
+        panic!(
+            "The published transcript entry does NOT match the response: {} diverged",
+            mismatches.join(", ")
+        );
+    }
+
+    info!("The published hash and public key match your response");
+}
@@ -0,0 +1,56 @@
+use crate::map_input;
+use phase1::{
+    helpers::{verify_pok, CurveKind},
+    Phase1Parameters, PublicKey, ResponseHeader,
+};
+use setup_utils::{print_hash, CheckForCorrectness, UseCompression};
+
+use zexe_algebra::PairingEngine as Engine;
+
+use std::fs::OpenOptions;
+use tracing::info;
+
+const CONTRIBUTION_IS_COMPRESSED: UseCompression = UseCompression::Yes;
+
+/// Checks only the proofs of knowledge of a response's key against the digest of the
+/// challenge it should be based on, and the generators at the start of the response.
+/// This skips the checks of every element of the response, so it is a quick screen of
+/// whether the contributor knew their secrets, and NOT a replacement for a full verification.
+pub fn verify_pok_only<T: Engine + Sync>(
+    response_filename: &str,
+    challenge_hash_filename: &str,
+    check_output_correctness: CheckForCorrectness,
+    curve_kind: &CurveKind,
+    parameters: &Phase1Parameters<T>,
+) {
+    info!("Verifying the proofs of knowledge of {}", response_filename);
+
+    let digest = std::fs::read(challenge_hash_filename).expect("unable to read the challenge hash file");
+    info!("Hash of the `challenge` file:");
+    print_hash(&digest);
+
+    let response_reader = OpenOptions::new()
+        .read(true)
+        .open(response_filename)
+        .expect("unable open response file in this directory");
+    let response_readable_map = map_input(&response_reader).expect("unable to create a memory map for the response");
+    let expected_header = ResponseHeader::new(curve_kind, CONTRIBUTION_IS_COMPRESSED, parameters);
+    let response = ResponseHeader::strip(&response_readable_map, &expected_header).expect("invalid response header");
+
+    let public_key = PublicKey::read(response, CONTRIBUTION_IS_COMPRESSED, parameters)
+        .expect("wasn't able to deserialize the response file's public key");
+
+    let res = verify_pok(
+        (response, CONTRIBUTION_IS_COMPRESSED, check_output_correctness),
+        &public_key,
+        &digest,
+        parameters,
+    );
+
+    if let Err(e) = res {
+        info!("Verification failed: {}", e);
+        panic!("INVALID PROOF OF KNOWLEDGE!!!");
+    } else {
+        info!("The contributor knew the secrets of their key, the rest of the response was NOT verified");
+    }
+}
@@ -1,13 +1,13 @@
 use phase1::{
-    helpers::{curve_from_str, proving_system_from_str, CurveKind},
-    ContributionMode, Phase1, Phase1Parameters, ProvingSystem,
+    helpers::{bind_digest_to_chunk, curve_from_str, proving_system_from_str, verify_chunk, CurveKind},
+    ContributionMode, Phase1, Phase1Parameters, ProvingSystem, PublicKey,
 };
 use setup_utils::{
     calculate_hash, derive_rng_from_seed, get_rng, user_system_randomness, BatchExpMode, CheckForCorrectness,
-    UseCompression,
+    SubgroupCheckMode, UseCompression,
 };
 
-use zexe_algebra::{Bls12_377, PairingEngine, BW6_761};
+use zexe_algebra::{Bls12_377, CanonicalDeserialize, PairingEngine, BW6_761};
 
 use phase1::helpers::batch_exp_mode_from_str;
 use rand::Rng;
@@ -111,6 +111,46 @@ impl Phase1WASM {
         };
         convert_contribution_result_to_wasm(&res)
     }
+
+    /// Verifies a single chunk of a contribution in the browser, e.g. from a transcript viewer.
+    ///
+    /// The challenge and the response are both held in the linear memory of the module,
+    /// which is limited to 4GB on wasm32, so only small powers can be verified in full
+    /// contribution mode. Verify large ceremonies chunk by chunk instead.
+    #[wasm_bindgen]
+    pub fn verify_chunked(
+        curve_kind: &str,
+        proving_system: &str,
+        batch_size: usize,
+        power: usize,
+        chunk_index: usize,
+        chunk_size: usize,
+        challenge: &[u8],
+        response: &[u8],
+        public_key: &[u8],
+        digest: &[u8],
+    ) -> Result<(), JsValue> {
+        let proving_system =
+            proving_system_from_str(proving_system).map_err(|_| JsValue::from_str("invalid proving system"))?;
+        let curve_kind = curve_from_str(curve_kind).map_err(|_| JsValue::from_str("invalid curve_kind"))?;
+        let res = match curve_kind {
+            CurveKind::Bls12_377 => verify_chunk_bytes(
+                challenge,
+                response,
+                public_key,
+                digest,
+                &get_parameters_chunked::<Bls12_377>(proving_system, power, batch_size, chunk_index, chunk_size),
+            ),
+            CurveKind::BW6 => verify_chunk_bytes(
+                challenge,
+                response,
+                public_key,
+                digest,
+                &get_parameters_chunked::<BW6_761>(proving_system, power, batch_size, chunk_index, chunk_size),
+            ),
+        };
+        res.map_err(|e| JsValue::from_str(&e))
+    }
 }
 
 pub fn get_parameters_full<E: PairingEngine>(
@@ -138,16 +178,35 @@ pub fn get_parameters_chunked<E: PairingEngine>(
     )
 }
 
+/// Verifies that the response was correctly computed from the challenge with the
+/// serialized public key, where `digest` is the hash of the challenge the key was
+/// generated for. The checks run sequentially, without spawning any threads.
+pub fn verify_chunk_bytes<E: PairingEngine>(
+    challenge: &[u8],
+    response: &[u8],
+    public_key: &[u8],
+    digest: &[u8],
+    parameters: &Phase1Parameters<E>,
+) -> Result<(), String> {
+    let public_key = PublicKey::<E>::deserialize(&mut &public_key[..]).map_err(|e| e.to_string())?;
+    verify_chunk(
+        (challenge, COMPRESSED_INPUT, CHECK_INPUT_CORRECTNESS),
+        (response, COMPRESSED_OUTPUT, CheckForCorrectness::Full),
+        &public_key,
+        digest,
+        SubgroupCheckMode::Auto,
+        parameters,
+    )
+    .map_err(|e| e.to_string())
+}
+
 pub fn contribute_challenge<E: PairingEngine + Sync>(
     challenge: &[u8],
     batch_exp_mode: BatchExpMode,
     parameters: &Phase1Parameters<E>,
     mut rng: impl Rng,
 ) -> Result<ContributionResponse, String> {
-    let expected_challenge_length = match COMPRESSED_INPUT {
-        UseCompression::Yes => parameters.contribution_size,
-        UseCompression::No => parameters.accumulator_size,
-    };
+    let expected_challenge_length = parameters.get_length(COMPRESSED_INPUT);
 
     if challenge.len() != expected_challenge_length {
         return Err(format!(
@@ -157,10 +216,7 @@ pub fn contribute_challenge<E: PairingEngine + Sync>(
         ));
     }
 
-    let required_output_length = match COMPRESSED_OUTPUT {
-        UseCompression::Yes => parameters.contribution_size,
-        UseCompression::No => parameters.accumulator_size + parameters.public_key_size,
-    };
+    let required_output_length = parameters.get_response_length(COMPRESSED_OUTPUT);
 
     let mut response: Vec<u8> = vec![];
     let current_accumulator_hash = calculate_hash(&challenge);
@@ -171,7 +227,7 @@ pub fn contribute_challenge<E: PairingEngine + Sync>(
 
     // Construct our keypair using the RNG we created above
     let (public_key, private_key): (phase1::PublicKey<E>, phase1::PrivateKey<E>) =
-        match Phase1::key_generation(&mut rng, current_accumulator_hash.as_ref()) {
+        match Phase1::key_generation(&mut rng, &bind_digest_to_chunk(&current_accumulator_hash, parameters)) {
             Ok(pair) => pair,
             Err(_) => return Err("could not generate keypair".to_string()),
         };
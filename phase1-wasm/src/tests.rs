@@ -1,6 +1,6 @@
 use crate::phase1::*;
 use phase1::{ContributionMode, Phase1, Phase1Parameters, ProvingSystem};
-use setup_utils::{batch_exp, blank_hash, generate_powers_of_tau, BatchExpMode, UseCompression};
+use setup_utils::{batch_exp, blank_hash, calculate_hash, generate_powers_of_tau, BatchExpMode, UseCompression};
 
 use zexe_algebra::{batch_inversion, AffineCurve, Bls12_377, Field, PairingEngine, ProjectiveCurve, BW6_761};
 
@@ -209,3 +209,35 @@ fn test_phase1_contribute_bw6_761_chunked() {
         }
     }
 }
+
+fn verify_chunk_bytes_test<E: PairingEngine + Sync>(parameters: &Phase1Parameters<E>) {
+    let (input, _) = generate_input(parameters, COMPRESSED_INPUT);
+    let response = contribute_challenge(&input, BatchExpMode::Auto, parameters, XorShiftRng::seed_from_u64(0))
+        .unwrap()
+        .response;
+    let digest = calculate_hash(&input);
+    let (accumulator, public_key) = response.split_at(parameters.get_length(COMPRESSED_OUTPUT));
+
+    verify_chunk_bytes(&input, accumulator, public_key, &digest, parameters).unwrap();
+    // the key is bound to the challenge it was generated for
+    if parameters.chunk_index == 0 {
+        assert!(verify_chunk_bytes(&input, accumulator, public_key, &blank_hash(), parameters).is_err());
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_phase1_verify_chunk_bytes_bls12_377() {
+    for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+        let powers = 5;
+        let chunk_size = 3 + 3 * powers + 1; // to ensure the Marlin extra elements fit in chunk 0
+        for i in 0..2 {
+            verify_chunk_bytes_test(&get_parameters_chunked::<Bls12_377>(
+                *proving_system,
+                powers,
+                2,
+                i,
+                chunk_size,
+            ));
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use phase1::{
-    helpers::testing::{generate_input, setup_verify},
-    Phase1, Phase1Parameters, ProvingSystem,
+    helpers::{
+        pool::BufferPool,
+        testing::{generate_input, generate_output, setup_verify},
+    },
+    ContributionMode, Phase1, Phase1Parameters, ProvingSystem,
 };
 use setup_utils::*;
 
-use zexe_algebra::Bls12_377;
+use zexe_algebra::{AffineCurve, Bls12_377, PairingEngine};
 
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use rand::thread_rng;
@@ -146,10 +149,98 @@ fn benchmark_verification(c: &mut Criterion) {
     }
 }
 
+// Benchmark combining a ceremony split into many chunks. The chunks are written
+// into disjoint regions of the output concurrently.
+fn benchmark_aggregation(c: &mut Criterion) {
+    let compressed = UseCompression::No;
+    let proving_system = &[ProvingSystem::Groth16, ProvingSystem::Marlin];
+    let power = 12;
+    let batch = 256;
+
+    let mut group = c.benchmark_group(format!("aggregation_{}", power));
+    group.sample_size(10);
+
+    for proof_system in proving_system {
+        let full_parameters = Phase1Parameters::<Bls12_377>::new_full(*proof_system, power, batch);
+        let num_powers = match proof_system {
+            ProvingSystem::Groth16 => full_parameters.powers_g1_length,
+            ProvingSystem::Marlin => full_parameters.powers_length,
+        };
+
+        for chunk_size in &[64, 256, 1024] {
+            let num_chunks = (num_powers + chunk_size - 1) / chunk_size;
+
+            // The responses of each chunk, initialized to the generators
+            let responses = (0..num_chunks)
+                .map(|chunk_index| {
+                    let parameters =
+                        full_parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, *chunk_size);
+                    let (input, _) = generate_input(&parameters, compressed, CheckForCorrectness::No);
+                    let mut response = generate_output(&parameters, compressed);
+                    response[..input.len()].copy_from_slice(&input);
+                    response
+                })
+                .collect::<Vec<_>>();
+            let inputs = responses.iter().map(|r| (r.as_slice(), compressed)).collect::<Vec<_>>();
+            let parameters = full_parameters.into_chunk_parameters(ContributionMode::Chunked, 0, *chunk_size);
+            let mut output = generate_output(&full_parameters, compressed);
+
+            group.throughput(Throughput::Elements(num_chunks as u64));
+            group.bench_with_input(format!("{:?}_{}", proof_system, chunk_size), &num_chunks, |b, _| {
+                b.iter(|| Phase1::aggregation(&inputs, (&mut output, compressed), &parameters).unwrap())
+            });
+        }
+    }
+}
+
+// Benchmark reading a large section batch by batch, with and without recycling the
+// buffers. Every batch allocates a buffer without the pool, while the pool allocates
+// a single one, and both report the allocations next to the timings.
+fn benchmark_buffer_pool(c: &mut Criterion) {
+    type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+
+    let compressed = UseCompression::No;
+    let power = 18;
+    let batch = 256;
+    let element_size = buffer_size::<G1>(compressed);
+
+    let mut section = vec![0; (1 << power) * element_size];
+    let elements = vec![G1::prime_subgroup_generator(); batch];
+    for chunk in section.chunks_mut(batch * element_size) {
+        chunk.write_batch(&elements, compressed).unwrap();
+    }
+
+    let mut group = c.benchmark_group(format!("buffer_pool_{}", power));
+    group.sample_size(10);
+
+    for limit in &[0, 4] {
+        let pool = BufferPool::<G1>::new(*limit);
+        let mut batches_read = 0;
+
+        group.throughput(Throughput::Elements(1 << power));
+        group.bench_with_input(format!("limit_{}", limit), limit, |b, _| {
+            b.iter(|| {
+                for chunk in section.chunks(batch * element_size) {
+                    pool.read_batch(chunk, compressed, CheckForCorrectness::No).unwrap();
+                    batches_read += 1;
+                }
+            })
+        });
+        println!(
+            "buffer pool with limit {}: {} allocations for {} batches read",
+            limit,
+            pool.allocations(),
+            batches_read
+        );
+    }
+}
+
 criterion_group!(
     benches,
     benchmark_initialization,
     benchmark_computation,
-    benchmark_verification
+    benchmark_verification,
+    benchmark_aggregation,
+    benchmark_buffer_pool
 );
 criterion_main!(benches);
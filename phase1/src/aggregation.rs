@@ -1,4 +1,8 @@
 use super::*;
+use crate::helpers::pool::BufferPool;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
     ///
@@ -8,177 +12,263 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
     /// reads them as group elements, and attempts to write them to
     /// the output buffer.
     ///
+    /// Chunk `i` starts at element `i * chunk_size` of each section. When the chunk size
+    /// does not divide the length of a section, the last chunk of that section is shorter,
+    /// and chunks starting past its end hold none of its elements.
+    ///
     pub fn aggregation(
         inputs: &[(&[u8], UseCompression)],
         (output, compressed_output): (&mut [u8], UseCompression),
         parameters: &Phase1Parameters<E>,
+    ) -> Result<()> {
+        Self::aggregation_from(0, inputs, (output, compressed_output), parameters)
+    }
+
+    ///
+    /// Phase 1: Partial Aggregation
+    ///
+    /// Same as `aggregation`, except that `inputs` are the contributions of the
+    /// consecutive chunks starting at `first_chunk_index`. This allows combining
+    /// the chunks in several steps, e.g. to resume an interrupted combination.
+    ///
+    pub fn aggregation_from(
+        first_chunk_index: usize,
+        inputs: &[(&[u8], UseCompression)],
+        (output, compressed_output): (&mut [u8], UseCompression),
+        parameters: &Phase1Parameters<E>,
     ) -> Result<()> {
         let span = info_span!("phase1-aggregation");
         let _enter = span.enter();
 
         info!("starting...");
 
-        for (chunk_index, (input, compressed_input)) in inputs.iter().enumerate() {
-            let chunk_parameters =
-                parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
+        // The inputs are trusted unless strict correctness checks were requested
+        let check_input_correctness =
+            strict_correctness_check_config(CheckForCorrectness::No, parameters.strict_correctness_checks);
 
-            let input = *input;
-            let compressed_input = *compressed_input;
+        // Each chunk writes to its own non-overlapping regions of the output, so the
+        // chunks can be combined concurrently. Only the first chunk gets the beta_g2 slice.
+        let outputs = split_into_chunks_mut(output, parameters, compressed_output, first_chunk_index + inputs.len());
+        let jobs = (first_chunk_index..)
+            .zip(inputs.iter().zip(outputs.into_iter().skip(first_chunk_index)))
+            .collect::<Vec<_>>();
 
-            let (in_tau_g1, in_tau_g2, in_alpha_g1, in_beta_g1, in_beta_g2) =
-                split(input, &chunk_parameters, compressed_input);
-            let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) =
-                split_at_chunk_mut(output, &chunk_parameters, compressed_output);
+        // The chunks are read into recycled buffers
+        let g1_pool = BufferPool::<E::G1Affine>::new(parameters.buffer_pool_size);
+        let g2_pool = BufferPool::<E::G2Affine>::new(parameters.buffer_pool_size);
 
-            let start = chunk_index * chunk_parameters.chunk_size;
-            let end = (chunk_index + 1) * chunk_parameters.chunk_size;
+        cfg_into_iter!(jobs).for_each(
+            |(chunk_index, ((input, compressed_input), (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2)))| {
+                let chunk_parameters =
+                    parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
 
-            debug!("combining chunk from {} to {}", start, end);
+                let input = *input;
+                let compressed_input = *compressed_input;
 
-            let span = info_span!("batch", start, end);
-            let _enter = span.enter();
+                let (in_tau_g1, in_tau_g2, in_alpha_g1, in_beta_g1, in_beta_g2) =
+                    split(input, &chunk_parameters, compressed_input);
 
-            match parameters.proving_system {
-                ProvingSystem::Groth16 => {
-                    rayon::scope(|t| {
-                        let _enter = span.enter();
+                // The last chunk is shorter when the chunk size does not divide the number of elements
+                let start = chunk_index * chunk_parameters.chunk_size;
+                let end = start + chunk_parameters.g1_chunk_size;
 
-                        t.spawn(|_| {
-                            let _enter = span.enter();
+                debug!("combining chunk from {} to {}", start, end);
 
-                            let elements: Vec<E::G1Affine> = in_tau_g1
-                                .read_batch(compressed_input, CheckForCorrectness::No)
-                                .expect("should have read batch");
-                            tau_g1
-                                .write_batch(&elements, compressed_output)
-                                .expect("should have written batch");
+                let span = info_span!("batch", start, end);
+                let _enter = span.enter();
 
-                            trace!("tau_g1 aggregation for chunk {} successful", chunk_index);
-                        });
+                match parameters.proving_system {
+                    ProvingSystem::Groth16 => {
+                        rayon::scope(|t| {
+                            let _enter = span.enter();
 
-                        if start < chunk_parameters.powers_length {
-                            rayon::scope(|t| {
+                            t.spawn(|_| {
                                 let _enter = span.enter();
 
-                                t.spawn(|_| {
-                                    let _enter = span.enter();
-
-                                    let elements: Vec<E::G2Affine> = in_tau_g2
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
-                                        .expect("should have read batch");
-                                    tau_g2
-                                        .write_batch(&elements, compressed_output)
-                                        .expect("should have written batch");
+                                let elements = g1_pool
+                                    .read_batch(in_tau_g1, compressed_input, check_input_correctness)
+                                    .expect("should have read batch");
+                                tau_g1
+                                    .write_batch(&elements[..], compressed_output)
+                                    .expect("should have written batch");
 
-                                    trace!("tau_g2 aggregation for chunk {} successful", chunk_index);
-                                });
+                                trace!("tau_g1 aggregation for chunk {} successful", chunk_index);
+                            });
 
-                                t.spawn(|_| {
+                            if start < chunk_parameters.powers_length {
+                                rayon::scope(|t| {
                                     let _enter = span.enter();
 
-                                    let elements: Vec<E::G1Affine> = in_alpha_g1
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
-                                        .expect("should have read batch");
-                                    alpha_g1
-                                        .write_batch(&elements, compressed_output)
-                                        .expect("should have written batch");
+                                    t.spawn(|_| {
+                                        let _enter = span.enter();
 
-                                    trace!("alpha_g1 aggregation for chunk {} successful", chunk_index);
-                                });
+                                        let elements = g2_pool
+                                            .read_batch(in_tau_g2, compressed_input, check_input_correctness)
+                                            .expect("should have read batch");
+                                        tau_g2
+                                            .write_batch(&elements[..], compressed_output)
+                                            .expect("should have written batch");
 
-                                t.spawn(|_| {
-                                    let _enter = span.enter();
-
-                                    let elements: Vec<E::G1Affine> = in_beta_g1
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
-                                        .expect("should have read batch");
-                                    beta_g1
-                                        .write_batch(&elements, compressed_output)
-                                        .expect("should have written batch");
+                                        trace!("tau_g2 aggregation for chunk {} successful", chunk_index);
+                                    });
 
-                                    trace!("beta_g1 aggregation for chunk {} successful", chunk_index);
-                                });
-                            });
-                        }
+                                    t.spawn(|_| {
+                                        let _enter = span.enter();
 
-                        if chunk_index == 0 {
-                            let element: E::G2Affine = (&*in_beta_g2)
-                                .read_element(compressed_input, CheckForCorrectness::No)
-                                .expect("should have read element");
-                            beta_g2
-                                .write_element(&element, compressed_output)
-                                .expect("should have written element");
-                            trace!("beta_g2 aggregation for chunk {} successful", chunk_index);
-                        }
-                    });
-                }
+                                        let elements = g1_pool
+                                            .read_batch(in_alpha_g1, compressed_input, check_input_correctness)
+                                            .expect("should have read batch");
+                                        alpha_g1
+                                            .write_batch(&elements[..], compressed_output)
+                                            .expect("should have written batch");
 
-                ProvingSystem::Marlin => {
-                    rayon::scope(|t| {
-                        let _enter = span.enter();
+                                        trace!("alpha_g1 aggregation for chunk {} successful", chunk_index);
+                                    });
 
-                        t.spawn(|_| {
-                            let _enter = span.enter();
+                                    t.spawn(|_| {
+                                        let _enter = span.enter();
 
-                            let elements: Vec<E::G1Affine> = in_tau_g1
-                                .read_batch(compressed_input, CheckForCorrectness::No)
-                                .expect("should have read batch");
-                            tau_g1
-                                .write_batch(&elements, compressed_output)
-                                .expect("should have written batch");
+                                        let elements = g1_pool
+                                            .read_batch(in_beta_g1, compressed_input, check_input_correctness)
+                                            .expect("should have read batch");
+                                        beta_g1
+                                            .write_batch(&elements[..], compressed_output)
+                                            .expect("should have written batch");
 
-                            trace!("tau_g1 aggregation for chunk {} successful", chunk_index);
+                                        trace!("beta_g1 aggregation for chunk {} successful", chunk_index);
+                                    });
+                                });
+                            }
+
+                            if chunk_index == 0 {
+                                let element: E::G2Affine = (&*in_beta_g2)
+                                    .read_element(compressed_input, check_input_correctness)
+                                    .expect("should have read element");
+                                beta_g2
+                                    .write_element(&element, compressed_output)
+                                    .expect("should have written element");
+                                trace!("beta_g2 aggregation for chunk {} successful", chunk_index);
+                            }
                         });
+                    }
 
-                        // handle tau G2
-                        if start < 2 + chunk_parameters.total_size_in_log2 {
-                            rayon::scope(|t| {
+                    ProvingSystem::Marlin => {
+                        rayon::scope(|t| {
+                            let _enter = span.enter();
+
+                            t.spawn(|_| {
                                 let _enter = span.enter();
-                                t.spawn(|_| {
-                                    let _enter = span.enter();
 
-                                    let elements: Vec<E::G2Affine> = in_tau_g2
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
-                                        .expect("should have read batch");
-                                    tau_g2
-                                        .write_batch(&elements, compressed_output)
-                                        .expect("should have written batch");
+                                let elements = g1_pool
+                                    .read_batch(in_tau_g1, compressed_input, check_input_correctness)
+                                    .expect("should have read batch");
+                                tau_g1
+                                    .write_batch(&elements[..], compressed_output)
+                                    .expect("should have written batch");
 
-                                    trace!("tau_g2 aggregation for chunk {} successful", chunk_index);
-                                });
+                                trace!("tau_g1 aggregation for chunk {} successful", chunk_index);
                             });
-                        }
-                        // handle alpha tau G1
-                        if start < 3 + 3 * chunk_parameters.total_size_in_log2 {
-                            rayon::scope(|t| {
-                                let _enter = span.enter();
 
-                                t.spawn(|_| {
+                            // handle tau G2
+                            if start < 2 + chunk_parameters.total_size_in_log2 {
+                                rayon::scope(|t| {
+                                    let _enter = span.enter();
+                                    t.spawn(|_| {
+                                        let _enter = span.enter();
+
+                                        let elements = g2_pool
+                                            .read_batch(in_tau_g2, compressed_input, check_input_correctness)
+                                            .expect("should have read batch");
+                                        tau_g2
+                                            .write_batch(&elements[..], compressed_output)
+                                            .expect("should have written batch");
+
+                                        trace!("tau_g2 aggregation for chunk {} successful", chunk_index);
+                                    });
+                                });
+                            }
+                            // handle alpha tau G1
+                            if start < 3 + 3 * chunk_parameters.total_size_in_log2 {
+                                rayon::scope(|t| {
                                     let _enter = span.enter();
 
-                                    let elements: Vec<E::G1Affine> = in_alpha_g1
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
-                                        .expect("should have read batch");
-                                    alpha_g1
-                                        .write_batch(&elements, compressed_output)
-                                        .expect("should have written batch");
+                                    t.spawn(|_| {
+                                        let _enter = span.enter();
 
-                                    trace!("alpha_g1 aggregation for chunk {} successful", chunk_index);
+                                        let elements = g1_pool
+                                            .read_batch(in_alpha_g1, compressed_input, check_input_correctness)
+                                            .expect("should have read batch");
+                                        alpha_g1
+                                            .write_batch(&elements[..], compressed_output)
+                                            .expect("should have written batch");
+
+                                        trace!("alpha_g1 aggregation for chunk {} successful", chunk_index);
+                                    });
                                 });
-                            });
-                        }
-                    });
+                            }
+                        });
+                    }
                 }
-            }
 
-            debug!("chunk {} processing successful", chunk_index);
-        }
+                debug!("chunk {} processing successful", chunk_index);
+            },
+        );
 
         info!("phase1-aggregation complete");
 
         Ok(())
     }
 
+    /// Checks that the chunks starting at `first_chunk_index` were combined into `output`,
+    /// i.e. that the output contains exactly the elements of each chunk's contribution.
+    pub fn verify_aggregation_from(
+        first_chunk_index: usize,
+        inputs: &[(&[u8], UseCompression)],
+        (output, compressed_output): (&[u8], UseCompression),
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<()> {
+        let outputs = split_into_chunks(output, parameters, compressed_output, first_chunk_index + inputs.len());
+
+        for (chunk_index, ((input, compressed_input), output)) in
+            (first_chunk_index..).zip(inputs.iter().zip(outputs.into_iter().skip(first_chunk_index)))
+        {
+            let chunk_parameters =
+                parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
+            let (in_tau_g1, in_tau_g2, in_alpha_g1, in_beta_g1, in_beta_g2) =
+                split(input, &chunk_parameters, *compressed_input);
+            let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = output;
+
+            let compare_g1 = |input: &[u8], output: &[u8], element_type| {
+                compare_aggregated::<E::G1Affine>(
+                    (input, *compressed_input),
+                    (output, compressed_output),
+                    element_type,
+                    chunk_index,
+                )
+            };
+            let compare_g2 = |input: &[u8], output: &[u8], element_type| {
+                compare_aggregated::<E::G2Affine>(
+                    (input, *compressed_input),
+                    (output, compressed_output),
+                    element_type,
+                    chunk_index,
+                )
+            };
+
+            compare_g1(in_tau_g1, tau_g1, ElementType::TauG1)?;
+            compare_g2(in_tau_g2, tau_g2, ElementType::TauG2)?;
+            compare_g1(in_alpha_g1, alpha_g1, ElementType::AlphaG1)?;
+            if parameters.proving_system == ProvingSystem::Groth16 {
+                compare_g1(in_beta_g1, beta_g1, ElementType::BetaG1)?;
+                if chunk_index == 0 {
+                    compare_g2(in_beta_g2, beta_g2, ElementType::BetaG2)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     ///
     /// Phase 1: Split
     ///
@@ -190,13 +280,33 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
         (input, compressed_input): (&[u8], UseCompression),
         outputs: Vec<(&mut [u8], UseCompression)>,
         parameters: &Phase1Parameters<E>,
+    ) -> Result<()> {
+        Self::split_from(0, (input, compressed_input), outputs, parameters)
+    }
+
+    ///
+    /// Phase 1: Partial Split
+    ///
+    /// Same as `split`, except that `outputs` are the consecutive chunks starting
+    /// at `first_chunk_index`. This allows extracting only some of the chunks,
+    /// e.g. to contribute to a range of chunks of a full challenge.
+    ///
+    pub fn split_from(
+        first_chunk_index: usize,
+        (input, compressed_input): (&[u8], UseCompression),
+        outputs: Vec<(&mut [u8], UseCompression)>,
+        parameters: &Phase1Parameters<E>,
     ) -> Result<()> {
         let span = info_span!("phase1-aggregation");
         let _enter = span.enter();
 
         info!("starting...");
 
-        for (chunk_index, (output, compressed_output)) in outputs.into_iter().enumerate() {
+        // The inputs are trusted unless strict correctness checks were requested
+        let check_input_correctness =
+            strict_correctness_check_config(CheckForCorrectness::No, parameters.strict_correctness_checks);
+
+        for (chunk_index, (output, compressed_output)) in (first_chunk_index..).zip(outputs.into_iter()) {
             let chunk_parameters =
                 parameters.into_chunk_parameters(parameters.contribution_mode, chunk_index, parameters.chunk_size);
 
@@ -204,8 +314,9 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                 split_at_chunk(input, &chunk_parameters, compressed_input);
             let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = split_mut(output, &chunk_parameters, compressed_output);
 
+            // The last chunk is shorter when the chunk size does not divide the number of elements
             let start = chunk_index * chunk_parameters.chunk_size;
-            let end = (chunk_index + 1) * chunk_parameters.chunk_size;
+            let end = start + chunk_parameters.g1_chunk_size;
 
             debug!("splitting chunk from {} to {}", start, end);
 
@@ -221,7 +332,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                             let _enter = span.enter();
 
                             let elements: Vec<E::G1Affine> = in_tau_g1
-                                .read_batch(compressed_input, CheckForCorrectness::No)
+                                .read_batch(compressed_input, check_input_correctness)
                                 .expect("should have read batch");
                             tau_g1
                                 .write_batch(&elements, compressed_output)
@@ -238,7 +349,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                     let _enter = span.enter();
 
                                     let elements: Vec<E::G2Affine> = in_tau_g2
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
+                                        .read_batch(compressed_input, check_input_correctness)
                                         .expect("should have read batch");
                                     tau_g2
                                         .write_batch(&elements, compressed_output)
@@ -251,7 +362,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                     let _enter = span.enter();
 
                                     let elements: Vec<E::G1Affine> = in_alpha_g1
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
+                                        .read_batch(compressed_input, check_input_correctness)
                                         .expect("should have read batch");
                                     alpha_g1
                                         .write_batch(&elements, compressed_output)
@@ -264,7 +375,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                     let _enter = span.enter();
 
                                     let elements: Vec<E::G1Affine> = in_beta_g1
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
+                                        .read_batch(compressed_input, check_input_correctness)
                                         .expect("should have read batch");
                                     beta_g1
                                         .write_batch(&elements, compressed_output)
@@ -276,7 +387,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         }
 
                         let element: E::G2Affine = (&*in_beta_g2)
-                            .read_element(compressed_input, CheckForCorrectness::No)
+                            .read_element(compressed_input, check_input_correctness)
                             .expect("should have read element");
                         beta_g2
                             .write_element(&element, compressed_output)
@@ -293,7 +404,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                             let _enter = span.enter();
 
                             let elements: Vec<E::G1Affine> = in_tau_g1
-                                .read_batch(compressed_input, CheckForCorrectness::No)
+                                .read_batch(compressed_input, check_input_correctness)
                                 .expect("should have read batch");
                             tau_g1
                                 .write_batch(&elements, compressed_output)
@@ -310,7 +421,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                     let _enter = span.enter();
 
                                     let elements: Vec<E::G2Affine> = in_tau_g2
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
+                                        .read_batch(compressed_input, check_input_correctness)
                                         .expect("should have read batch");
                                     tau_g2
                                         .write_batch(&elements, compressed_output)
@@ -329,7 +440,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                     let _enter = span.enter();
 
                                     let elements: Vec<E::G1Affine> = in_alpha_g1
-                                        .read_batch(compressed_input, CheckForCorrectness::No)
+                                        .read_batch(compressed_input, check_input_correctness)
                                         .expect("should have read batch");
                                     alpha_g1
                                         .write_batch(&elements, compressed_output)
@@ -352,10 +463,27 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
     }
 }
 
+/// Checks that a section of a chunk's contribution and the same section of the combined output
+/// contain the same elements
+fn compare_aggregated<C: AffineCurve>(
+    (input, compressed_input): (&[u8], UseCompression),
+    (output, compressed_output): (&[u8], UseCompression),
+    element_type: ElementType,
+    chunk_index: usize,
+) -> Result<()> {
+    let input_elements: Vec<C> = input.read_batch(compressed_input, CheckForCorrectness::No)?;
+    let output_elements: Vec<C> = output.read_batch(compressed_output, CheckForCorrectness::No)?;
+    if input_elements != output_elements {
+        return Err(Error::AggregationMismatch(element_type, chunk_index));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::helpers::testing::{generate_input, generate_new_challenge, generate_output};
+    use setup_utils::calculate_hash;
 
     use zexe_algebra::{Bls12_377, BW6_761};
 
@@ -552,6 +680,16 @@ mod tests {
                     batch,
                 );
                 Phase1::aggregation(&full_contribution, (&mut output, compressed_output), &parameters).unwrap();
+                Phase1::verify_aggregation_from(0, &full_contribution, (&output, compressed_output), &parameters)
+                    .unwrap();
+
+                // combining the chunks in two steps gives the same output
+                let mut resumed_output = generate_output(&full_parameters, compressed_output);
+                let (first, rest) = full_contribution.split_at(num_chunks / 2);
+                Phase1::aggregation_from(0, first, (&mut resumed_output, compressed_output), &parameters).unwrap();
+                Phase1::aggregation_from(first.len(), rest, (&mut resumed_output, compressed_output), &parameters)
+                    .unwrap();
+                assert_eq!(resumed_output, output);
 
                 let parameters = Phase1Parameters::<E>::new_full(*proving_system, powers, batch);
                 assert!(
@@ -767,6 +905,111 @@ mod tests {
         }
     }
 
+    // contributing to ranges of chunks of a full challenge with the same key, e.g. on separate
+    // machines, and combining them gives a full contribution
+    fn contribution_in_chunk_ranges_test<E: PairingEngine>(powers: usize, batch: usize) {
+        let compressed_input = UseCompression::No;
+        let compressed_output = UseCompression::Yes;
+
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let full_parameters = Phase1Parameters::<E>::new_full(*proving_system, powers, batch);
+            let (input, _) = generate_input(&full_parameters, compressed_input, CheckForCorrectness::No);
+            let digest = calculate_hash(&input);
+
+            // the key is bound to the full challenge, and is the same on every machine
+            let key_generation = || {
+                let mut rng = derive_rng_from_seed(b"test_contribution_in_chunk_ranges");
+                Phase1::<E>::key_generation(&mut rng, &digest).expect("could not generate keypair")
+            };
+
+            let parameters = Phase1Parameters::<E>::new(
+                ContributionMode::Chunked,
+                0,
+                batch,
+                full_parameters.curve.clone(),
+                *proving_system,
+                powers,
+                batch,
+            );
+            let upper_bound = match *proving_system {
+                ProvingSystem::Groth16 => parameters.powers_g1_length,
+                ProvingSystem::Marlin => parameters.powers_length,
+            };
+            let num_chunks = (upper_bound + batch - 1) / batch;
+
+            let mut chunk_outputs = vec![];
+            for (first, last) in &[(0, num_chunks / 2), (num_chunks / 2, num_chunks)] {
+                let (_, private_key) = key_generation();
+
+                let mut chunk_inputs = (*first..*last)
+                    .map(|chunk_index| {
+                        let chunk_parameters =
+                            parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, batch);
+                        generate_output(&chunk_parameters, compressed_input)
+                    })
+                    .collect::<Vec<_>>();
+                Phase1::split_from(
+                    *first,
+                    (&input, compressed_input),
+                    chunk_inputs
+                        .iter_mut()
+                        .map(|v| (v.as_mut_slice(), compressed_input))
+                        .collect::<Vec<_>>(),
+                    &parameters,
+                )
+                .unwrap();
+
+                for (chunk_index, chunk_input) in (*first..*last).zip(chunk_inputs) {
+                    let chunk_parameters =
+                        parameters.into_chunk_parameters(ContributionMode::Chunked, chunk_index, batch);
+                    let mut chunk_output = generate_output(&chunk_parameters, compressed_output);
+                    Phase1::computation(
+                        &chunk_input,
+                        &mut chunk_output,
+                        compressed_input,
+                        compressed_output,
+                        CheckForCorrectness::No,
+                        BatchExpMode::Auto,
+                        &private_key,
+                        &chunk_parameters,
+                    )
+                    .unwrap();
+                    chunk_outputs.push(chunk_output);
+                }
+            }
+
+            let mut output = generate_output(&full_parameters, compressed_output);
+            let chunk_outputs = chunk_outputs
+                .iter()
+                .map(|v| (v.as_slice(), compressed_output))
+                .collect::<Vec<_>>();
+            Phase1::aggregation(&chunk_outputs, (&mut output, compressed_output), &parameters).unwrap();
+
+            let (public_key, _) = key_generation();
+            let mut new_challenge = generate_new_challenge(&full_parameters, UseCompression::No);
+            let res = Phase1::verification(
+                &input,
+                &output,
+                &mut new_challenge,
+                &public_key,
+                &digest,
+                compressed_input,
+                compressed_output,
+                UseCompression::No,
+                CheckForCorrectness::No,
+                CheckForCorrectness::Full,
+                SubgroupCheckMode::Auto,
+                &full_parameters,
+            );
+            assert!(res.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_contribution_in_chunk_ranges_bls12_377() {
+        contribution_in_chunk_ranges_test::<Bls12_377>(4, 3 + 3 * 4);
+    }
+
     #[test]
     #[should_panic]
     fn test_aggregation_bls12_377_wrong_chunks() {
@@ -781,6 +1024,13 @@ mod tests {
         aggregation_test::<Bls12_377>(4, 3 + 3 * 4, UseCompression::Yes, UseCompression::No, false);
     }
 
+    #[test]
+    fn test_aggregation_ragged_last_chunk_bls12_377() {
+        // With Groth16, the 63 tau_g1 elements are in chunks of 18, 18, 18 and 9, the 32 others in chunks of 18 and 14
+        aggregation_test::<Bls12_377>(5, 3 + 3 * 5, UseCompression::Yes, UseCompression::Yes, false);
+        aggregation_test::<Bls12_377>(5, 3 + 3 * 5, UseCompression::No, UseCompression::Yes, false);
+    }
+
     #[test]
     #[should_panic]
     fn test_aggregation_bw6_wrong_chunks() {
@@ -92,6 +92,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                         &powers,
                                         None,
                                         batch_exp_mode,
+                                        parameters.parallel_grain,
                                     )
                                     .expect("could not apply powers of tau to tau_g1 elements");
 
@@ -135,6 +136,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                                 &powers,
                                                 None,
                                                 batch_exp_mode,
+                                                parameters.parallel_grain,
                                             )
                                             .expect("could not apply powers of tau to tau_g2 elements");
 
@@ -154,6 +156,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                                 &powers,
                                                 Some(&key.alpha),
                                                 batch_exp_mode,
+                                                parameters.parallel_grain,
                                             )
                                             .expect("could not apply powers of tau to alpha_g1 elements");
 
@@ -173,6 +176,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                                 &powers,
                                                 Some(&key.beta),
                                                 batch_exp_mode,
+                                                parameters.parallel_grain,
                                             )
                                             .expect("could not apply powers of tau to beta_g1 elements");
 
@@ -208,6 +212,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         &g2_inverse_powers,
                         None,
                         batch_exp_mode,
+                        parameters.parallel_grain,
                     )
                     .expect("could not apply powers of tau to tau_g2 elements");
 
@@ -224,6 +229,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         &g1_degree_powers,
                         Some(&key.alpha),
                         batch_exp_mode,
+                        parameters.parallel_grain,
                     )
                     .expect("could not apply powers of tau to tau_g2 elements");
 
@@ -237,6 +243,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         &powers,
                         Some(&key.alpha),
                         batch_exp_mode,
+                        parameters.parallel_grain,
                     )
                     .expect("could not apply powers of tau alpha to tau_g1 elements");
 
@@ -249,6 +256,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         &powers,
                         None,
                         batch_exp_mode,
+                        parameters.parallel_grain,
                     )
                     .expect("could not apply powers of tau to initial tau_g2 elements");
                 }
@@ -287,6 +295,7 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                 &powers,
                                 None,
                                 batch_exp_mode,
+                                parameters.parallel_grain,
                             )
                             .expect("could not apply powers of tau to tau_g1 elements");
                         });
@@ -482,4 +491,37 @@ mod tests {
         // Works even if the batch is larger than the powers
         curve_computation_test::<BW6_761>(6, 128, UseCompression::No, UseCompression::No);
     }
+
+    #[test]
+    fn test_computation_with_finer_parallel_grain() {
+        // The output does not depend on how finely the work is split
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let parameters = Phase1Parameters::<Bls12_377>::new_full(*proving_system, 6, 64);
+            let mut fine_parameters = parameters.clone();
+            fine_parameters.parallel_grain = 5;
+
+            let (input, _) = generate_input(&parameters, UseCompression::No, CheckForCorrectness::No);
+            let mut rng = derive_rng_from_seed(b"test_computation_with_finer_parallel_grain");
+            let (_, privkey) =
+                Phase1::key_generation(&mut rng, blank_hash().as_ref()).expect("could not generate keypair");
+
+            let mut outputs = vec![];
+            for parameters in &[parameters, fine_parameters] {
+                let mut output = vec![0; parameters.get_length(UseCompression::Yes)];
+                Phase1::computation(
+                    &input,
+                    &mut output,
+                    UseCompression::No,
+                    UseCompression::Yes,
+                    CheckForCorrectness::Full,
+                    BatchExpMode::Auto,
+                    &privkey,
+                    parameters,
+                )
+                .unwrap();
+                outputs.push(output);
+            }
+            assert_eq!(outputs[0], outputs[1]);
+        }
+    }
 }
@@ -7,9 +7,7 @@ use setup_utils::{BatchDeserializer, BatchSerializer, Deserializer, Serializer,
 use zexe_algebra::{AffineCurve, PairingEngine};
 
 #[cfg(not(feature = "wasm"))]
-use setup_utils::SubgroupCheckMode;
-#[cfg(not(feature = "wasm"))]
-use {crate::ContributionMode, zexe_algebra::batch_verify_in_subgroup};
+use crate::ContributionMode;
 
 #[allow(type_alias_bounds)]
 type AccumulatorElements<E: PairingEngine> = (
@@ -32,142 +30,208 @@ type AccumulatorElementsRef<'a, E: PairingEngine> = (
 
 cfg_if! {
     if #[cfg(not(feature = "wasm"))] {
-        use zexe_algebra::{PrimeField, FpParameters, cfg_iter, Zero};
-        #[cfg(feature = "parallel")]
-        use rayon::prelude::*;
-        use tracing::debug;
-
-        use crate::PublicKey;
-        /// Given a public key and the accumulator's digest, it hashes each G1 element
-        /// along with the digest, and then hashes it to G2.
-        pub(crate) fn compute_g2_s_key<E: PairingEngine>(key: &PublicKey<E>, digest: &[u8]) -> Result<[E::G2Affine; 3]> {
-            Ok([
-                compute_g2_s::<E>(&digest, &key.tau_g1.0, &key.tau_g1.1, 0)?,
-                compute_g2_s::<E>(&digest, &key.alpha_g1.0, &key.alpha_g1.1, 1)?,
-                compute_g2_s::<E>(&digest, &key.beta_g1.0, &key.beta_g1.1, 2)?,
-            ])
-        }
+        use crate::helpers::pool::BufferPool;
+        use tracing::warn;
 
-        /// Reads a list of G1 elements from the buffer to the provided `elements` slice
-        /// and then checks that their powers pairs ratio matches the one from the
-        /// provided `check` pair
-        pub(crate) fn check_power_ratios<E: PairingEngine>(
-            (buffer, compression, check_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+        /// Takes a compressed input buffer and decompresses it `batch_size` elements at a time,
+        /// reading each batch into a buffer of the pool.
+        fn decompress_buffer<C: AffineCurve>(
+            output: &mut [u8],
+            input: &[u8],
+            check_input_for_correctness: CheckForCorrectness,
             (start, end): (usize, usize),
-            elements: &mut [E::G1Affine],
-            check: &(E::G2Affine, E::G2Affine),
+            batch_size: usize,
+            pool: &BufferPool<C>,
         ) -> Result<()> {
-            let size = buffer_size::<E::G1Affine>(compression);
-            buffer[start * size..end * size].read_batch_preallocated(
-                &mut elements[0..end - start],
-                compression,
-                check_for_correctness,
-            )?;
-            check_same_ratio::<E>(&power_pairs(&elements[..end - start]), check, "Power pairs")?;
+            let in_size = buffer_size::<C>(UseCompression::Yes);
+            let out_size = buffer_size::<C>(UseCompression::No);
+            for batch_start in (start..end).step_by(batch_size) {
+                let batch_end = std::cmp::min(batch_start + batch_size, end);
+                // read the compressed input
+                let elements = pool.read_batch(
+                    &input[batch_start * in_size..batch_end * in_size],
+                    UseCompression::Yes,
+                    check_input_for_correctness,
+                )?;
+                // write it back uncompressed
+                output[batch_start * out_size..batch_end * out_size].write_batch(&elements[..], UseCompression::No)?;
+            }
+
             Ok(())
         }
 
-        /// Reads a list of G2 elements from the buffer to the provided `elements` slice
-        /// and then checks that their powers pairs ratio matches the one from the
-        /// provided `check` pair
-        pub(crate) fn check_power_ratios_g2<E: PairingEngine>(
-            (buffer, compression, check_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
-            (start, end): (usize, usize),
-            elements: &mut [E::G2Affine],
-            check: &(E::G1Affine, E::G1Affine),
+        /// Decompresses the compressed elements batch by batch and checks that they are
+        /// byte-for-byte equal to the uncompressed elements.
+        fn compare_decompressed<C: AffineCurve>(
+            compressed: &[u8],
+            uncompressed: &[u8],
+            element_type: ElementType,
+            check_input_for_correctness: CheckForCorrectness,
+            batch_size: usize,
+            pool: &BufferPool<C>,
         ) -> Result<()> {
-            let size = buffer_size::<E::G2Affine>(compression);
-            buffer[start * size..end * size].read_batch_preallocated(
-                &mut elements[0..end - start],
-                compression,
-                check_for_correctness,
-            )?;
-            check_same_ratio::<E>(check, &power_pairs(&elements[..end - start]), "Power pairs")?;
+            let in_size = buffer_size::<C>(UseCompression::Yes);
+            let out_size = buffer_size::<C>(UseCompression::No);
+            let num_elements = compressed.len() / in_size;
+            if uncompressed.len() != num_elements * out_size {
+                return Err(Error::InvalidLength {
+                    expected: num_elements * out_size,
+                    got: uncompressed.len(),
+                });
+            }
+
+            let mut decompressed = vec![0; batch_size * out_size];
+            for start in (0..num_elements).step_by(batch_size) {
+                let end = std::cmp::min(start + batch_size, num_elements);
+                decompress_buffer::<C>(
+                    &mut decompressed,
+                    &compressed[start * in_size..end * in_size],
+                    check_input_for_correctness,
+                    (0, end - start),
+                    batch_size,
+                    pool,
+                )?;
+                let expected = &uncompressed[start * out_size..end * out_size];
+                let mismatch = (0..end - start).find(|i| {
+                    decompressed[i * out_size..(i + 1) * out_size] != expected[i * out_size..(i + 1) * out_size]
+                });
+                if let Some(i) = mismatch {
+                    return Err(Error::CompressionMismatch(element_type, start + i));
+                }
+            }
+
             Ok(())
         }
 
-        /// Reads a list of group elements from the buffer to the provided `elements` slice
-        /// and then checks that the elements are nonzero and in the prime order subgroup.
-        pub(crate) fn check_elements_are_nonzero_and_in_prime_order_subgroup<C: AffineCurve>(
-            (buffer, compression): (&[u8], UseCompression),
-            (start, end): (usize, usize),
-            elements: &mut [C],
-            subgroup_check_mode: SubgroupCheckMode,
+        /// Checks that decompressing the compressed accumulator yields exactly the
+        /// uncompressed accumulator, failing on the first element which differs.
+        pub fn verify_compression<E: PairingEngine>(
+            compressed: &[u8],
+            uncompressed: &[u8],
+            check_input_for_correctness: CheckForCorrectness,
+            parameters: &Phase1Parameters<E>,
         ) -> Result<()> {
-            let size = buffer_size::<C>(compression);
-            buffer[start * size..end * size].read_batch_preallocated(
-                &mut elements[0..end - start],
-                compression,
-                CheckForCorrectness::OnlyNonZero,
-            )?;
-            const SECURITY_PARAM: usize = 128;
-            const BATCH_SIZE: usize = 1 << 12;
-            let now = std::time::Instant::now();
-            let all_in_prime_order_subgroup = match (elements.len() > BATCH_SIZE, subgroup_check_mode) {
-                (true, SubgroupCheckMode::Auto) | (_, SubgroupCheckMode::Batched) => {
-                    match batch_verify_in_subgroup(elements, SECURITY_PARAM, &mut rand::thread_rng()) {
-                        Ok(()) => true,
-                        _ => false,
-                    }
-                }
-                (false, SubgroupCheckMode::Auto) | (_, SubgroupCheckMode::Direct) => {
-                    cfg_iter!(elements).all(|p| {
-                        p.mul(<<C::ScalarField as PrimeField>::Params as FpParameters>::MODULUS)
-                            .is_zero()
-                    })
+            let (in_tau_g1, in_tau_g2, in_alpha_g1, in_beta_g1, in_beta_g2) =
+                split(compressed, parameters, UseCompression::Yes);
+            let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = split(uncompressed, parameters, UseCompression::No);
+            let check = check_input_for_correctness;
+            let batch_size = parameters.batch_size;
+            // The batches of all the sections are read into recycled buffers
+            let g1_pool = BufferPool::<E::G1Affine>::new(parameters.buffer_pool_size);
+            let g2_pool = BufferPool::<E::G2Affine>::new(parameters.buffer_pool_size);
+
+            compare_decompressed(in_tau_g1, tau_g1, ElementType::TauG1, check, batch_size, &g1_pool)?;
+            compare_decompressed(in_tau_g2, tau_g2, ElementType::TauG2, check, batch_size, &g2_pool)?;
+            compare_decompressed(in_alpha_g1, alpha_g1, ElementType::AlphaG1, check, batch_size, &g1_pool)?;
+            compare_decompressed(in_beta_g1, beta_g1, ElementType::BetaG1, check, batch_size, &g1_pool)?;
+            compare_decompressed(in_beta_g2, beta_g2, ElementType::BetaG2, check, batch_size, &g2_pool)?;
+
+            Ok(())
+        }
+
+        /// Calls `action` with the section, the index within the section and the serialized
+        /// bytes of every element of the accumulator, in order. The elements are not
+        /// deserialized, so this only reads the buffer once and does not allocate.
+        pub fn for_each_element<E: PairingEngine>(
+            input: &[u8],
+            compressed: UseCompression,
+            parameters: &Phase1Parameters<E>,
+            mut action: impl FnMut(ElementType, usize, &[u8]) -> Result<()>,
+        ) -> Result<()> {
+            for (element_type, buffer, size, start) in sections(input, compressed, parameters).iter() {
+                for (i, element) in buffer.chunks(*size).enumerate() {
+                    action(*element_type, start + i, element)?;
                 }
-            };
-            debug!("Subgroup verification for {} elems: {}us", end - start, now.elapsed().as_micros());
-            if !all_in_prime_order_subgroup {
-                return Err(Error::IncorrectSubgroup);
             }
+
             Ok(())
         }
 
-        /// Reads a chunk of 2 elements from the buffer
-        pub(crate) fn read_initial_elements<C: AffineCurve>(
-            buffer: &[u8],
+        /// Returns the serialized bytes of the element at `index` of the section, indexed
+        /// like `for_each_element`. Only that element of the buffer is read, and an index
+        /// outside of the section, or outside of the chunk, is rejected.
+        pub fn element<'a, E: PairingEngine>(
+            input: &'a [u8],
             compressed: UseCompression,
-            check_input_for_correctness: CheckForCorrectness,
-        ) -> Result<Vec<C>> {
-            read_initial_elements_with_amount(buffer, 2, compressed, check_input_for_correctness)
+            parameters: &Phase1Parameters<E>,
+            element_type: ElementType,
+            index: usize,
+        ) -> Result<&'a [u8]> {
+            let (_, buffer, size, start) = sections(input, compressed, parameters)
+                .iter()
+                .find(|(section, ..)| *section == element_type)
+                .copied()
+                .expect("every section is listed");
+            let num_elements = buffer.len() / size;
+            if index < start || index >= start + num_elements {
+                return Err(Error::PositionError(
+                    element_type,
+                    (start + num_elements).saturating_sub(1),
+                    index,
+                ));
+            }
+
+            let offset = (index - start) * size;
+            Ok(&buffer[offset..offset + size])
         }
 
-        /// Reads a chunk of {amount} elements from the buffer
-        pub(crate) fn read_initial_elements_with_amount<C: AffineCurve>(
-            buffer: &[u8],
-            amount: usize,
+        /// The sections of the accumulator with the size of their elements and the index of
+        /// their first element
+        fn sections<'a, E: PairingEngine>(
+            input: &'a [u8],
             compressed: UseCompression,
-            check_input_for_correctness: CheckForCorrectness,
-        ) -> Result<Vec<C>> {
-            let batch = amount;
-            let size = buffer_size::<C>(compressed);
-            let result = buffer[0..batch * size].read_batch(compressed, check_input_for_correctness)?;
-            if result.len() != batch {
-                return Err(Error::InvalidLength {
-                    expected: batch,
-                    got: result.len(),
-                });
-            }
-            Ok(result)
+            parameters: &Phase1Parameters<E>,
+        ) -> [(ElementType, &'a [u8], usize, usize); 5] {
+            let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = split(input, parameters, compressed);
+            let g1_size = buffer_size::<E::G1Affine>(compressed);
+            let g2_size = buffer_size::<E::G2Affine>(compressed);
+
+            // The chunked sections start at the first element of the chunk, while the
+            // Marlin tau_g2 and alpha_g1 sections and beta_g2 are always complete
+            let chunk_start = match parameters.contribution_mode {
+                ContributionMode::Chunked => parameters.chunk_index * parameters.chunk_size,
+                ContributionMode::Full => 0,
+            };
+            let other_start = match parameters.proving_system {
+                ProvingSystem::Groth16 => chunk_start,
+                ProvingSystem::Marlin => 0,
+            };
+
+            [
+                (ElementType::TauG1, tau_g1, g1_size, chunk_start),
+                (ElementType::TauG2, tau_g2, g2_size, other_start),
+                (ElementType::AlphaG1, alpha_g1, g1_size, other_start),
+                (ElementType::BetaG1, beta_g1, g1_size, chunk_start),
+                (ElementType::BetaG2, beta_g2, g2_size, 0),
+            ]
         }
 
-        /// Takes a compressed input buffer and decompresses it.
-        fn decompress_buffer<C: AffineCurve>(
-            output: &mut [u8],
-            input: &[u8],
+        /// Decompresses the input buffer into the output buffer, unless both buffers use the
+        /// same compression, in which case the input is copied over as-is. Going from an
+        /// uncompressed input to a compressed output is not supported.
+        pub fn maybe_decompress<E: PairingEngine>(
+            (input, compressed_input): (&[u8], UseCompression),
+            (output, compressed_output): (&mut [u8], UseCompression),
             check_input_for_correctness: CheckForCorrectness,
-            (start, end): (usize, usize),
+            parameters: &Phase1Parameters<E>,
         ) -> Result<()> {
-            let in_size = buffer_size::<C>(UseCompression::Yes);
-            let out_size = buffer_size::<C>(UseCompression::No);
-            // read the compressed input
-            let elements =
-                input[start * in_size..end * in_size].read_batch::<C>(UseCompression::Yes, check_input_for_correctness)?;
-            // write it back uncompressed
-            output[start * out_size..end * out_size].write_batch(&elements, UseCompression::No)?;
-
-            Ok(())
+            match (compressed_input, compressed_output) {
+                (UseCompression::Yes, UseCompression::No) => {
+                    decompress(input, output, check_input_for_correctness, parameters)
+                }
+                (UseCompression::No, UseCompression::Yes) => Err(Error::InvalidDecompressionParametersError),
+                _ => {
+                    warn!("input and output compression match ({}), skipping decompression", compressed_input);
+                    if input.len() != output.len() {
+                        return Err(Error::InvalidLength {
+                            expected: output.len(),
+                            got: input.len(),
+                        });
+                    }
+                    output.copy_from_slice(input);
+                    Ok(())
+                }
+            }
         }
 
         /// Takes a compressed input buffer and decompresses it into the output buffer.
@@ -179,6 +243,9 @@ cfg_if! {
         ) -> Result<()> {
             let compressed_input = UseCompression::Yes;
             let compressed_output = UseCompression::No;
+            // The batches of all the sections are read into recycled buffers
+            let g1_pool = BufferPool::<E::G1Affine>::new(parameters.buffer_pool_size);
+            let g2_pool = BufferPool::<E::G2Affine>::new(parameters.buffer_pool_size);
 
             match parameters.proving_system {
                 ProvingSystem::Groth16 => {
@@ -204,6 +271,8 @@ cfg_if! {
                                 in_tau_g1,
                                 check_input_for_correctness,
                                 (0, parameters.g1_chunk_size),
+                                parameters.batch_size,
+                                &g1_pool,
                             )
                             .expect("could not decompress the tau_g1 elements")
                         });
@@ -215,6 +284,8 @@ cfg_if! {
                                         in_tau_g2,
                                         check_input_for_correctness,
                                         (0, parameters.other_chunk_size),
+                                        parameters.batch_size,
+                                        &g2_pool,
                                     )
                                     .expect("could not decompress the tau_g2 elements")
                                 });
@@ -224,6 +295,8 @@ cfg_if! {
                                         in_alpha_g1,
                                         check_input_for_correctness,
                                         (0, parameters.other_chunk_size),
+                                        parameters.batch_size,
+                                        &g1_pool,
                                     )
                                     .expect("could not decompress the alpha_g1 elements")
                                 });
@@ -233,6 +306,8 @@ cfg_if! {
                                         in_beta_g1,
                                         check_input_for_correctness,
                                         (0, parameters.other_chunk_size),
+                                        parameters.batch_size,
+                                        &g1_pool,
                                     )
                                     .expect("could not decompress the beta_g1 elements")
                                 });
@@ -254,8 +329,10 @@ cfg_if! {
                             in_alpha_g1,
                             check_input_for_correctness,
                             (0, num_alpha_powers + 3*parameters.total_size_in_log2),
+                            parameters.batch_size,
+                            &g1_pool,
                         )?;
-                        decompress_buffer::<E::G2Affine>(tau_g2, in_tau_g2, check_input_for_correctness, (0, parameters.total_size_in_log2 + 2))?;
+                        decompress_buffer::<E::G2Affine>(tau_g2, in_tau_g2, check_input_for_correctness, (0, parameters.total_size_in_log2 + 2), parameters.batch_size, &g2_pool)?;
                     }
 
                     rayon::scope(|t| {
@@ -265,6 +342,8 @@ cfg_if! {
                                 in_tau_g1,
                                 check_input_for_correctness,
                                 (0, parameters.g1_chunk_size),
+                                parameters.batch_size,
+                                &g1_pool,
                             )
                             .expect("could not decompress the tau_g1 elements")
                         });
@@ -345,13 +424,16 @@ mod tests {
         // Allocate the decompressed buffer.
         let len = num_els * buffer_size::<C>(UseCompression::No);
         let mut out = vec![0; len];
-        // Perform the decompression.
-        decompress_buffer::<C>(&mut out, &input, CheckForCorrectness::Full, (0, num_els)).unwrap();
+        // Perform the decompression, in batches which do not divide the number of elements.
+        let pool = BufferPool::<C>::new(1);
+        decompress_buffer::<C>(&mut out, &input, CheckForCorrectness::Full, (0, num_els), 3, &pool).unwrap();
         let deserialized = out
             .read_batch::<C>(UseCompression::No, CheckForCorrectness::Full)
             .unwrap();
         // Ensure they match.
         assert_eq!(deserialized, elements);
+        // One buffer is recycled for the full batches, and another is allocated for the last one
+        assert_eq!(pool.allocations(), 2);
     }
 
     #[test]
@@ -2,8 +2,11 @@ use crate::{ContributionMode, Phase1Parameters, ProvingSystem};
 use setup_utils::{BatchDeserializer, BatchSerializer, *};
 
 use zexe_algebra::{AffineCurve, PairingEngine};
+use zexe_fft::{cfg_chunks, cfg_chunks_mut};
 
 use itertools::{Itertools, MinMaxResult};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Buffer, compression
 type Input<'a> = (&'a [u8], UseCompression, CheckForCorrectness);
@@ -15,34 +18,37 @@ type Output<'a> = (&'a mut [u8], UseCompression);
 type SplitBufMut<'a> = (&'a mut [u8], &'a mut [u8], &'a mut [u8], &'a mut [u8], &'a mut [u8]);
 
 /// Immutable slices with format [TauG1, TauG2, AlphaG1, BetaG1, BetaG2]
-type SplitBuf<'a> = (&'a [u8], &'a [u8], &'a [u8], &'a [u8], &'a [u8]);
+pub(crate) type SplitBuf<'a> = (&'a [u8], &'a [u8], &'a [u8], &'a [u8], &'a [u8]);
+
+/// The range of elements of the accumulator covered by the chunk being processed,
+/// which is the entire accumulator in full contribution mode.
+pub(crate) fn chunk_range(parameters: &Phase1Parameters<impl PairingEngine>) -> (usize, usize) {
+    // Determine the number of elements to process based on the proof system's requirement.
+    let upper_bound = match parameters.proving_system {
+        ProvingSystem::Groth16 => parameters.powers_g1_length,
+        ProvingSystem::Marlin => parameters.powers_length,
+    };
+
+    // In chunked contribution mode, select the chunk to iterate over.
+    // In full contribution mode, select the entire range up to the upper bound.
+    match parameters.contribution_mode {
+        ContributionMode::Chunked => (
+            parameters.chunk_index * parameters.chunk_size,
+            std::cmp::min((parameters.chunk_index + 1) * parameters.chunk_size, upper_bound),
+        ),
+        ContributionMode::Full => (0, upper_bound),
+    }
+}
 
-/// Helper function to iterate over the accumulator in chunks.
-/// `action` will perform an action on the chunk
+/// Helper function to iterate over the accumulator in chunks of `batch_size - 1` elements.
+/// `action` will perform an action on the chunk, after which we sleep
+/// for `throttle_ms` if throttling was requested
 pub(crate) fn iter_chunk(
     parameters: &Phase1Parameters<impl PairingEngine>,
     mut action: impl FnMut(usize, usize) -> Result<()>,
 ) -> Result<()> {
     // Determine the range to iterate over.
-    let (min, max) = {
-        // Determine the number of elements to process based on the proof system's requirement.
-        let upper_bound = match parameters.proving_system {
-            ProvingSystem::Groth16 => parameters.powers_g1_length,
-            ProvingSystem::Marlin => parameters.powers_length,
-        };
-
-        // In chunked contribution mode, select the chunk to iterate over.
-        // In full contribution mode, select the entire range up to the upper bound.
-        let (min, max) = match parameters.contribution_mode {
-            ContributionMode::Chunked => (
-                parameters.chunk_index * parameters.chunk_size,
-                std::cmp::min((parameters.chunk_index + 1) * parameters.chunk_size, upper_bound),
-            ),
-            ContributionMode::Full => (0, upper_bound),
-        };
-
-        (min, max)
-    };
+    let (min, max) = chunk_range(parameters);
 
     // Iterate over the range, processing each element with the given input.
     (min..max)
@@ -54,13 +60,18 @@ pub(crate) fn iter_chunk(
                 MinMaxResult::OneElement(start) => (start, if start >= max - 1 { start + 1 } else { start + 2 }),
                 _ => return Err(Error::InvalidChunk),
             };
-            action(start, end)
+            action(start, end)?;
+            if parameters.throttle_ms > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(parameters.throttle_ms));
+            }
+            Ok(())
         })
         .collect::<Result<_>>()
 }
 
 /// Takes a buffer, reads the group elements in it, exponentiates them to the
-/// provided `powers` and maybe to the `coeff`, and then writes them back
+/// provided `powers` and maybe to the `coeff`, and then writes them back.
+/// The elements are processed in grains of `parallel_grain` elements, in parallel
 pub(crate) fn apply_powers<C: AffineCurve>(
     (output, output_compressed): Output,
     (input, input_compressed, check_input_for_correctness): Input,
@@ -68,23 +79,46 @@ pub(crate) fn apply_powers<C: AffineCurve>(
     powers: &[C::ScalarField],
     coeff: Option<&C::ScalarField>,
     batch_exp_mode: BatchExpMode,
+    parallel_grain: usize,
 ) -> Result<()> {
     let in_size = buffer_size::<C>(input_compressed);
     let out_size = buffer_size::<C>(output_compressed);
+    let grain = std::cmp::max(parallel_grain, 1);
+
+    // The grains do not overlap, so that each of them can be written to independently
+    cfg_chunks_mut!(output[start * out_size..end * out_size], grain * out_size)
+        .zip(cfg_chunks!(input[start * in_size..end * in_size], grain * in_size))
+        .zip(cfg_chunks!(powers[..end - start], grain))
+        .map(|((output, input), powers)| {
+            // Read the input
+            let mut elements = input.read_batch::<C>(input_compressed, check_input_for_correctness)?;
+            // calculate the powers
+            batch_exp(&mut elements, powers, coeff, batch_exp_mode)?;
+            // write back
+            output.write_batch(&elements, output_compressed)
+        })
+        .collect::<Result<()>>()
+}
 
-    // Read the input
-    let mut elements =
-        &mut input[start * in_size..end * in_size].read_batch::<C>(input_compressed, check_input_for_correctness)?;
-    // calculate the powers
-    batch_exp(&mut elements, &powers[..end - start], coeff, batch_exp_mode)?;
-    // write back
-    output[start * out_size..end * out_size].write_batch(&elements, output_compressed)?;
+/// Exponentiates the group elements of `buffer` in place, the i-th element to `powers[i]`,
+/// multiplied by `coeff` if one is provided, so that custom accumulator layouts can reuse
+/// the transform applied by a contribution. `powers` must hold exactly one power per
+/// element of the buffer, otherwise an `InvalidLength` error is returned.
+pub fn transform_elements<C: AffineCurve>(
+    buffer: &mut [u8],
+    powers: &[C::ScalarField],
+    coeff: Option<&C::ScalarField>,
+    compression: UseCompression,
+) -> Result<()> {
+    let mut elements = buffer.read_batch::<C>(compression, CheckForCorrectness::Full)?;
+    batch_exp(&mut elements, powers, coeff, BatchExpMode::Auto)?;
+    buffer.write_batch(&elements, compression)?;
 
     Ok(())
 }
 
 #[cfg(not(feature = "wasm"))]
-/// Splits the full buffer in 5 non overlapping mutable slice for a given chunk and batch size.
+/// Splits the full buffer in 5 non overlapping immutable slice for a given chunk and batch size.
 /// Each slice corresponds to the group elements in the following order
 /// [TauG1, TauG2, AlphaG1, BetaG1, BetaG2]
 pub(crate) fn split_at_chunk<'a, E: PairingEngine>(
@@ -124,14 +158,14 @@ pub(crate) fn split_at_chunk<'a, E: PairingEngine>(
             let (alpha_g1, others) = others.split_at(g1_size * parameters.powers_length);
             let (beta_g1, beta_g2) = others.split_at(g1_size * parameters.powers_length);
 
-            // We take up to g2_size for beta_g2, since there might be other
-            // elements after it at the end of the buffer.
+            // We only take the single G2 elements for beta_g2, since there might be
+            // other elements after them at the end of the buffer.
             (
                 buffer_to_chunk(tau_g1, g1_size, false),
                 buffer_to_chunk(tau_g2, g2_size, true),
                 buffer_to_chunk(alpha_g1, g1_size, true),
                 buffer_to_chunk(beta_g1, g1_size, true),
-                &beta_g2[0..g2_size],
+                &beta_g2[0..g2_size * parameters.single_element_counts.g2],
             )
         }
         ProvingSystem::Marlin => {
@@ -153,36 +187,19 @@ pub(crate) fn split_at_chunk<'a, E: PairingEngine>(
 }
 
 #[cfg(not(feature = "wasm"))]
-/// Splits the full buffer in 5 non overlapping mutable slice for a given chunk and batch size.
-/// Each slice corresponds to the group elements in the following order
-/// [TauG1, TauG2, AlphaG1, BetaG1, BetaG2]
-pub(crate) fn split_at_chunk_mut<'a, E: PairingEngine>(
+/// Splits the full buffer into `num_chunks` groups of 5 non overlapping mutable slices,
+/// one group per chunk of size `parameters.chunk_size`. Each slice corresponds to the
+/// group elements of that chunk in the following order [TauG1, TauG2, AlphaG1, BetaG1, BetaG2]
+/// Since the groups do not overlap, the chunks can be processed concurrently.
+pub(crate) fn split_into_chunks_mut<'a, E: PairingEngine>(
     buffer: &'a mut [u8],
-    parameters: &'a Phase1Parameters<E>,
+    parameters: &Phase1Parameters<E>,
     compressed: UseCompression,
-) -> SplitBufMut<'a> {
+    num_chunks: usize,
+) -> Vec<SplitBufMut<'a>> {
     let g1_size = buffer_size::<E::G1Affine>(compressed);
     let g2_size = buffer_size::<E::G2Affine>(compressed);
-
-    let buffer_to_chunk = |buffer: &'a mut [u8], element_size: usize, is_other: bool| -> &'a mut [u8] {
-        // Determine whether to return an empty chunk based on the size of 'other'.
-        if is_other && parameters.other_chunk_size == 0 {
-            return &mut [];
-        }
-
-        // Determine the chunk size based on the proof system.
-        let chunk_size = match (parameters.proving_system, is_other) {
-            (ProvingSystem::Groth16, true) => parameters.other_chunk_size,
-            (ProvingSystem::Groth16, false) => parameters.g1_chunk_size,
-            (ProvingSystem::Marlin, true) => return &mut [],
-            (ProvingSystem::Marlin, false) => parameters.g1_chunk_size,
-        };
-
-        let start = parameters.chunk_index * parameters.chunk_size * element_size;
-        let end = start + chunk_size * element_size;
-
-        &mut buffer[start..end]
-    };
+    let chunk_size = parameters.chunk_size;
 
     match parameters.proving_system {
         ProvingSystem::Groth16 => {
@@ -193,22 +210,29 @@ pub(crate) fn split_at_chunk_mut<'a, E: PairingEngine>(
             let (alpha_g1, others) = others.split_at_mut(g1_size * parameters.powers_length);
             let (beta_g1, beta_g2) = others.split_at_mut(g1_size * parameters.powers_length);
 
-            // We take up to g2_size for beta_g2, since there might be other
-            // elements after it at the end of the buffer.
-            (
-                buffer_to_chunk(tau_g1, g1_size, false),
-                buffer_to_chunk(tau_g2, g2_size, true),
-                buffer_to_chunk(alpha_g1, g1_size, true),
-                buffer_to_chunk(beta_g1, g1_size, true),
-                &mut beta_g2[0..g2_size],
-            )
+            let mut tau_g1 = tau_g1.chunks_mut(chunk_size * g1_size);
+            let mut tau_g2 = tau_g2.chunks_mut(chunk_size * g2_size);
+            let mut alpha_g1 = alpha_g1.chunks_mut(chunk_size * g1_size);
+            let mut beta_g1 = beta_g1.chunks_mut(chunk_size * g1_size);
+            // We only take the single G2 elements for beta_g2, since there might be
+            // other elements after them at the end of the buffer.
+            let mut beta_g2 = Some(&mut beta_g2[0..g2_size * parameters.single_element_counts.g2]);
+
+            (0..num_chunks)
+                .map(|_| {
+                    (
+                        tau_g1.next().unwrap_or_default(),
+                        tau_g2.next().unwrap_or_default(),
+                        alpha_g1.next().unwrap_or_default(),
+                        beta_g1.next().unwrap_or_default(),
+                        beta_g2.take().unwrap_or_default(),
+                    )
+                })
+                .collect()
         }
         ProvingSystem::Marlin => {
-            let (g2_chunk_size, alpha_chunk_size) = if parameters.chunk_index == 0 {
-                (parameters.total_size_in_log2 + 2, 3 + 3 * parameters.total_size_in_log2)
-            } else {
-                (0, 0)
-            };
+            let g2_chunk_size = parameters.total_size_in_log2 + 2;
+            let alpha_chunk_size = 3 + 3 * parameters.total_size_in_log2;
 
             // leave the first 64 bytes for the hash
             let (_, others) = buffer.split_at_mut(parameters.hash_size);
@@ -216,13 +240,112 @@ pub(crate) fn split_at_chunk_mut<'a, E: PairingEngine>(
             let (tau_g2, others) = others.split_at_mut(g2_size * g2_chunk_size);
             let (alpha_g1, _) = others.split_at_mut(g1_size * alpha_chunk_size);
 
-            (
-                buffer_to_chunk(tau_g1, g1_size, false),
-                tau_g2,
-                alpha_g1,
-                &mut [],
-                &mut [],
-            )
+            let mut tau_g1 = tau_g1.chunks_mut(chunk_size * g1_size);
+            // The tau_g2 and alpha_g1 elements all belong to the first chunk
+            let mut tau_g2 = Some(tau_g2);
+            let mut alpha_g1 = Some(alpha_g1);
+
+            (0..num_chunks)
+                .map(|_| {
+                    (
+                        tau_g1.next().unwrap_or_default(),
+                        tau_g2.take().unwrap_or_default(),
+                        alpha_g1.take().unwrap_or_default(),
+                        Default::default(),
+                        Default::default(),
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(not(feature = "wasm"))]
+/// Immutable counterpart of `split_into_chunks_mut`, returning the slices of the full
+/// buffer which belong to each of the first `num_chunks` chunks.
+pub(crate) fn split_into_chunks<'a, E: PairingEngine>(
+    buffer: &'a [u8],
+    parameters: &Phase1Parameters<E>,
+    compressed: UseCompression,
+    num_chunks: usize,
+) -> Vec<SplitBuf<'a>> {
+    let g1_size = buffer_size::<E::G1Affine>(compressed);
+    let g2_size = buffer_size::<E::G2Affine>(compressed);
+    let chunk_size = parameters.chunk_size;
+
+    match parameters.proving_system {
+        ProvingSystem::Groth16 => {
+            let (_, others) = buffer.split_at(parameters.hash_size);
+            let (tau_g1, others) = others.split_at(g1_size * parameters.powers_g1_length);
+            let (tau_g2, others) = others.split_at(g2_size * parameters.powers_length);
+            let (alpha_g1, others) = others.split_at(g1_size * parameters.powers_length);
+            let (beta_g1, beta_g2) = others.split_at(g1_size * parameters.powers_length);
+
+            let mut tau_g1 = tau_g1.chunks(chunk_size * g1_size);
+            let mut tau_g2 = tau_g2.chunks(chunk_size * g2_size);
+            let mut alpha_g1 = alpha_g1.chunks(chunk_size * g1_size);
+            let mut beta_g1 = beta_g1.chunks(chunk_size * g1_size);
+            let mut beta_g2 = Some(&beta_g2[0..g2_size * parameters.single_element_counts.g2]);
+
+            (0..num_chunks)
+                .map(|_| {
+                    (
+                        tau_g1.next().unwrap_or_default(),
+                        tau_g2.next().unwrap_or_default(),
+                        alpha_g1.next().unwrap_or_default(),
+                        beta_g1.next().unwrap_or_default(),
+                        beta_g2.take().unwrap_or_default(),
+                    )
+                })
+                .collect()
+        }
+        ProvingSystem::Marlin => {
+            let g2_chunk_size = parameters.total_size_in_log2 + 2;
+            let alpha_chunk_size = 3 + 3 * parameters.total_size_in_log2;
+
+            let (_, others) = buffer.split_at(parameters.hash_size);
+            let (tau_g1, others) = others.split_at(g1_size * parameters.powers_length);
+            let (tau_g2, others) = others.split_at(g2_size * g2_chunk_size);
+            let (alpha_g1, _) = others.split_at(g1_size * alpha_chunk_size);
+
+            let mut tau_g1 = tau_g1.chunks(chunk_size * g1_size);
+            let mut tau_g2 = Some(tau_g2);
+            let mut alpha_g1 = Some(alpha_g1);
+
+            (0..num_chunks)
+                .map(|_| {
+                    (
+                        tau_g1.next().unwrap_or_default(),
+                        tau_g2.take().unwrap_or_default(),
+                        alpha_g1.take().unwrap_or_default(),
+                        Default::default(),
+                        Default::default(),
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+/// Returns the number of elements of each of the 5 sections of the buffer, in the order of `split`
+pub(crate) fn section_lengths<E: PairingEngine>(
+    parameters: &Phase1Parameters<E>,
+) -> (usize, usize, usize, usize, usize) {
+    match parameters.proving_system {
+        ProvingSystem::Groth16 => (
+            parameters.g1_chunk_size,
+            parameters.other_chunk_size,
+            parameters.other_chunk_size,
+            parameters.other_chunk_size,
+            parameters.single_element_counts.g2,
+        ),
+        ProvingSystem::Marlin => {
+            let (g2_chunk_size, alpha_chunk_size) = if parameters.chunk_index == 0 {
+                (parameters.total_size_in_log2 + 2, 3 + 3 * parameters.total_size_in_log2)
+            } else {
+                (0, 0)
+            };
+            (parameters.g1_chunk_size, g2_chunk_size, alpha_chunk_size, 0, 0)
         }
     }
 }
@@ -249,9 +372,15 @@ pub(crate) fn split_mut<'a, E: PairingEngine>(
             let (alpha_g1, others) = others.split_at_mut(g1_size * other_chunk_size);
             let (beta_g1, beta_g2) = others.split_at_mut(g1_size * other_chunk_size);
 
-            // We take up to g2_size for beta_g2, since there might be other
-            // elements after it at the end of the buffer.
-            (tau_g1, tau_g2, alpha_g1, beta_g1, &mut beta_g2[0..g2_size])
+            // We only take the single G2 elements for beta_g2, since there might be
+            // other elements after them at the end of the buffer.
+            (
+                tau_g1,
+                tau_g2,
+                alpha_g1,
+                beta_g1,
+                &mut beta_g2[0..g2_size * parameters.single_element_counts.g2],
+            )
         }
         ProvingSystem::Marlin => {
             let g1_size = buffer_size::<E::G1Affine>(compressed);
@@ -299,9 +428,15 @@ pub(crate) fn split<'a, E: PairingEngine>(
             // Check that tau_g1 is not empty.
             assert!(tau_g1.len() > 0);
 
-            // We take up to g2_size for beta_g2, since there might be other
-            // elements after it at the end of the buffer.
-            (tau_g1, tau_g2, alpha_g1, beta_g1, &beta_g2[0..g2_size])
+            // We only take the single G2 elements for beta_g2, since there might be
+            // other elements after them at the end of the buffer.
+            (
+                tau_g1,
+                tau_g2,
+                alpha_g1,
+                beta_g1,
+                &beta_g2[0..g2_size * parameters.single_element_counts.g2],
+            )
         }
         ProvingSystem::Marlin => {
             let g1_size = buffer_size::<E::G1Affine>(compressed);
@@ -326,3 +461,44 @@ pub(crate) fn split<'a, E: PairingEngine>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::testing::random_point_vec;
+
+    use zexe_algebra::{bls12_377::Bls12_377, UniformRand};
+
+    use rand::thread_rng;
+
+    fn transform_elements_curve_test<C: AffineCurve>(compression: UseCompression) {
+        let mut rng = thread_rng();
+        let num_els = 10;
+        let elements: Vec<C> = random_point_vec(num_els, &mut rng);
+        let powers = (0..num_els).map(|_| C::ScalarField::rand(&mut rng)).collect::<Vec<_>>();
+        let coeff = C::ScalarField::rand(&mut rng);
+
+        let mut buffer = vec![0; num_els * buffer_size::<C>(compression)];
+        buffer.write_batch(&elements, compression).unwrap();
+        transform_elements::<C>(&mut buffer, &powers, Some(&coeff), compression).unwrap();
+
+        let transformed = buffer.read_batch::<C>(compression, CheckForCorrectness::Full).unwrap();
+        let expected = elements
+            .iter()
+            .zip(&powers)
+            .map(|(element, power)| element.mul(*power * coeff).into_affine())
+            .collect::<Vec<_>>();
+        assert_eq!(transformed, expected);
+
+        // there must be exactly one power per element
+        assert!(transform_elements::<C>(&mut buffer, &powers[1..], None, compression).is_err());
+    }
+
+    #[test]
+    fn test_transform_elements() {
+        for compression in &[UseCompression::Yes, UseCompression::No] {
+            transform_elements_curve_test::<<Bls12_377 as PairingEngine>::G1Affine>(*compression);
+            transform_elements_curve_test::<<Bls12_377 as PairingEngine>::G2Affine>(*compression);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{ContributionMode, ProvingSystem};
-use setup_utils::{BatchExpMode, SubgroupCheckMode};
+use setup_utils::{BatchExpMode, ElementType, Endianness, SectionMask, SubgroupCheckMode, UseCompression};
 
 #[derive(Debug, Clone)]
 pub enum CurveKind {
@@ -59,3 +59,58 @@ pub fn subgroup_check_mode_from_str(src: &str) -> Result<SubgroupCheckMode, Stri
     };
     Ok(subgroup_check_mode)
 }
+
+pub fn use_compression_from_str(src: &str) -> Result<UseCompression, String> {
+    let compression = match src.to_lowercase().as_str() {
+        "yes" => UseCompression::Yes,
+        "no" => UseCompression::No,
+        _ => return Err("unsupported compression. Currently supported: yes, no".to_string()),
+    };
+    Ok(compression)
+}
+
+pub fn endianness_from_str(src: &str) -> Result<Endianness, String> {
+    let endianness = match src.to_lowercase().as_str() {
+        "little" => Endianness::Little,
+        "big" => Endianness::Big,
+        _ => return Err("unsupported endianness. Currently supported: little, big".to_string()),
+    };
+    Ok(endianness)
+}
+
+pub fn section_mask_from_str(src: &str) -> Result<SectionMask, String> {
+    if src.to_lowercase() == "all" {
+        return Ok(SectionMask::all());
+    }
+    let mut sections = SectionMask::none();
+    for section in src.split(',') {
+        match section.trim().to_lowercase().as_str() {
+            "tau_g1" => sections.tau_g1 = true,
+            "tau_g2" => sections.tau_g2 = true,
+            "alpha_g1" => sections.alpha_g1 = true,
+            "beta_g1" => sections.beta_g1 = true,
+            _ => {
+                return Err(
+                    "unsupported section. Currently supported: all, tau_g1, tau_g2, alpha_g1, beta_g1".to_string(),
+                );
+            }
+        }
+    }
+    Ok(sections)
+}
+
+pub fn element_type_from_str(src: &str) -> Result<ElementType, String> {
+    let element_type = match src.to_lowercase().as_str() {
+        "tau_g1" => ElementType::TauG1,
+        "tau_g2" => ElementType::TauG2,
+        "alpha_g1" => ElementType::AlphaG1,
+        "beta_g1" => ElementType::BetaG1,
+        "beta_g2" => ElementType::BetaG2,
+        _ => {
+            return Err(
+                "unsupported section. Currently supported: tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2".to_string(),
+            );
+        }
+    };
+    Ok(element_type)
+}
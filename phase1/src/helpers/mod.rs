@@ -2,9 +2,18 @@ pub mod accumulator;
 pub use accumulator::*;
 
 pub mod buffers;
+pub use buffers::transform_elements;
 
 pub mod converters;
 pub use converters::*;
 
+pub mod pool;
+
+#[cfg(not(feature = "wasm"))]
+pub mod validation_cache;
+
+pub mod verification_core;
+pub use verification_core::*;
+
 #[cfg(feature = "testing")]
 pub mod testing;
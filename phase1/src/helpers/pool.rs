@@ -0,0 +1,160 @@
+use setup_utils::{buffer_size, BatchDeserializer, CheckForCorrectness, Result, UseCompression};
+
+use zexe_algebra::{AffineCurve, Zero};
+
+use std::{
+    collections::HashMap,
+    mem,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// A pool of element buffers keyed by their length, so that each batch is read into a
+/// recycled buffer instead of a freshly allocated one. Each pool holds a single element
+/// type, e.g. one for G1 and one for G2.
+pub struct BufferPool<C> {
+    limit: usize,
+    idle: Mutex<HashMap<usize, Vec<Vec<C>>>>,
+    allocations: AtomicUsize,
+}
+
+/// A buffer which is given back to its pool when dropped
+pub struct PooledBuffer<'a, C> {
+    pool: &'a BufferPool<C>,
+    buffer: Vec<C>,
+}
+
+impl<C: Zero + Clone> BufferPool<C> {
+    /// Constructs a pool which keeps up to `limit` idle buffers of each length.
+    /// A limit of 0 disables pooling, so that every buffer is allocated.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            idle: Mutex::new(HashMap::new()),
+            allocations: AtomicUsize::new(0),
+        }
+    }
+
+    /// Takes a buffer of `len` elements from the pool, allocating one if none is idle.
+    /// A recycled buffer still holds the elements it was last filled with.
+    pub fn take(&self, len: usize) -> PooledBuffer<C> {
+        let buffer = if self.limit > 0 {
+            let mut idle = self.idle.lock().expect("should have locked the buffer pool");
+            idle.get_mut(&len).and_then(|buffers| buffers.pop())
+        } else {
+            None
+        };
+        let buffer = buffer.unwrap_or_else(|| {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            vec![C::zero(); len]
+        });
+        PooledBuffer { pool: self, buffer }
+    }
+
+    /// The number of buffers which had to be allocated so far
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Relaxed)
+    }
+}
+
+impl<C: AffineCurve> BufferPool<C> {
+    /// Same as `read_batch`, except that the elements are read into a buffer of the pool
+    pub fn read_batch(
+        &self,
+        buffer: &[u8],
+        compression: UseCompression,
+        check_correctness: CheckForCorrectness,
+    ) -> Result<PooledBuffer<C>> {
+        let mut elements = self.take(buffer.len() / buffer_size::<C>(compression));
+        buffer.read_batch_preallocated(&mut elements, compression, check_correctness)?;
+        Ok(elements)
+    }
+}
+
+impl<'a, C> Drop for PooledBuffer<'a, C> {
+    fn drop(&mut self) {
+        if self.pool.limit > 0 {
+            let mut idle = self.pool.idle.lock().expect("should have locked the buffer pool");
+            let buffers = idle.entry(self.buffer.len()).or_insert_with(Vec::new);
+            if buffers.len() < self.pool.limit {
+                buffers.push(mem::take(&mut self.buffer));
+            }
+        }
+    }
+}
+
+impl<'a, C> Deref for PooledBuffer<'a, C> {
+    type Target = [C];
+
+    fn deref(&self) -> &[C] {
+        &self.buffer
+    }
+}
+
+impl<'a, C> DerefMut for PooledBuffer<'a, C> {
+    fn deref_mut(&mut self) -> &mut [C] {
+        &mut self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use setup_utils::BatchSerializer;
+
+    use zexe_algebra::{Bls12_377, PairingEngine};
+
+    type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+
+    #[test]
+    fn test_buffer_pool_recycles_buffers() {
+        let pool = BufferPool::<G1>::new(2);
+        for _ in 0..10 {
+            let _buffer = pool.take(8);
+        }
+        assert_eq!(pool.allocations(), 1);
+
+        // buffers are only recycled for the same length
+        {
+            let _buffer = pool.take(4);
+            let _other = pool.take(8);
+            let _another = pool.take(8);
+        }
+        assert_eq!(pool.allocations(), 3);
+
+        // at most `limit` idle buffers of each length are kept
+        {
+            let _buffers = (0..4).map(|_| pool.take(8)).collect::<Vec<_>>();
+        }
+        assert_eq!(pool.allocations(), 5);
+        {
+            let _buffers = (0..4).map(|_| pool.take(8)).collect::<Vec<_>>();
+        }
+        assert_eq!(pool.allocations(), 7);
+
+        // without pooling, every buffer is allocated
+        let pool = BufferPool::<G1>::new(0);
+        for _ in 0..10 {
+            let _buffer = pool.take(8);
+        }
+        assert_eq!(pool.allocations(), 10);
+    }
+
+    #[test]
+    fn test_buffer_pool_read_batch() {
+        let elements = vec![G1::prime_subgroup_generator(); 4];
+        let pool = BufferPool::<G1>::new(1);
+        for compression in &[UseCompression::Yes, UseCompression::No] {
+            let mut buffer = vec![0; elements.len() * buffer_size::<G1>(*compression)];
+            buffer.write_batch(&elements, *compression).unwrap();
+            let read = pool
+                .read_batch(&buffer, *compression, CheckForCorrectness::Full)
+                .unwrap();
+            assert_eq!(&read[..], &elements[..]);
+        }
+        assert_eq!(pool.allocations(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{helpers::CurveKind, Phase1Parameters};
+use setup_utils::{calculate_hash, ElementType, Result, UseCompression};
+
+use zexe_algebra::PairingEngine;
+
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// An on-disk record of the ranges of elements which already passed the prime order subgroup
+/// check, so that verifying the same bytes again, e.g. when many parties verify the same
+/// response, can skip that check. A range is keyed by the curve and the parameters of the
+/// ceremony, the section of its elements, their compression, the range of indices and a hash
+/// of the exact bytes, so that a cached range never vouches for any other bytes.
+pub struct ValidationCache {
+    path: PathBuf,
+    context: String,
+    validated: Mutex<HashSet<String>>,
+}
+
+impl ValidationCache {
+    /// Loads the cache of the ceremony with these parameters from `path`, which holds one hex
+    /// encoded key per line, or starts an empty cache there if the file does not exist yet
+    pub fn open<E: PairingEngine>(
+        path: impl AsRef<Path>,
+        curve_kind: &CurveKind,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let validated = match File::open(&path) {
+            Ok(file) => {
+                let mut validated = HashSet::new();
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        validated.insert(line.trim().to_string());
+                    }
+                }
+                validated
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let context = format!(
+            "{:?}:{:?}:{}:{:?}:{}:{}",
+            curve_kind,
+            parameters.proving_system,
+            parameters.total_size_in_log2,
+            parameters.contribution_mode,
+            parameters.chunk_size,
+            parameters.chunk_index
+        );
+        Ok(Self {
+            path,
+            context,
+            validated: Mutex::new(validated),
+        })
+    }
+
+    /// The key of the elements of `element_type` in `(start, end)`, serialized in `buffer`
+    pub fn key(
+        &self,
+        element_type: ElementType,
+        buffer: &[u8],
+        compression: UseCompression,
+        (start, end): (usize, usize),
+    ) -> String {
+        let bytes_hash = calculate_hash(buffer);
+        let mut preimage = format!(
+            "{}:{}:{}:{}:{}:",
+            self.context,
+            element_type,
+            compression == UseCompression::Yes,
+            start,
+            end
+        )
+        .into_bytes();
+        preimage.extend_from_slice(&bytes_hash);
+        calculate_hash(&preimage)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Whether the elements with this key already passed the subgroup check
+    pub fn contains(&self, key: &str) -> bool {
+        self.validated
+            .lock()
+            .expect("validation cache lock was poisoned")
+            .contains(key)
+    }
+
+    /// Records that the elements with this key passed the subgroup check
+    pub fn insert(&self, key: String) {
+        self.validated
+            .lock()
+            .expect("validation cache lock was poisoned")
+            .insert(key);
+    }
+
+    /// The number of validated ranges in the cache
+    pub fn len(&self) -> usize {
+        self.validated.lock().expect("validation cache lock was poisoned").len()
+    }
+
+    /// Whether the cache holds no validated range
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the cache back to its file, replacing it atomically
+    pub fn save(&self) -> Result<()> {
+        let mut keys = self
+            .validated
+            .lock()
+            .expect("validation cache lock was poisoned")
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let mut file = File::create(&tmp_path)?;
+            for key in keys {
+                writeln!(file, "{}", key)?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{helpers::testing::random_point_vec, ContributionMode, ProvingSystem};
+    use setup_utils::{buffer_size, BatchSerializer};
+
+    use zexe_algebra::{Bls12_377, BW6_761};
+
+    use rand::thread_rng;
+
+    type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_validation_cache_keys() {
+        let elements: Vec<G1> = random_point_vec(4, &mut thread_rng());
+        let mut buffer = vec![0; elements.len() * buffer_size::<G1>(UseCompression::Yes)];
+        buffer.write_batch(&elements, UseCompression::Yes).unwrap();
+
+        let path = temp_path("validation_cache_keys");
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 3, 4);
+        let cache = ValidationCache::open(&path, &CurveKind::Bls12_377, &parameters).unwrap();
+        let key = cache.key(ElementType::TauG1, &buffer, UseCompression::Yes, (0, 4));
+        assert_eq!(key, cache.key(ElementType::TauG1, &buffer, UseCompression::Yes, (0, 4)));
+        // every part of the key matters
+        assert_ne!(
+            key,
+            cache.key(ElementType::AlphaG1, &buffer, UseCompression::Yes, (0, 4))
+        );
+        assert_ne!(key, cache.key(ElementType::TauG1, &buffer, UseCompression::No, (0, 4)));
+        assert_ne!(key, cache.key(ElementType::TauG1, &buffer, UseCompression::Yes, (4, 8)));
+        let other_curve = ValidationCache::open(
+            &path,
+            &CurveKind::BW6,
+            &Phase1Parameters::<BW6_761>::new_full(ProvingSystem::Groth16, 3, 4),
+        )
+        .unwrap();
+        assert_ne!(
+            key,
+            other_curve.key(ElementType::TauG1, &buffer, UseCompression::Yes, (0, 4))
+        );
+        let other_power = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 4, 4);
+        let other_power = ValidationCache::open(&path, &CurveKind::Bls12_377, &other_power).unwrap();
+        assert_ne!(
+            key,
+            other_power.key(ElementType::TauG1, &buffer, UseCompression::Yes, (0, 4))
+        );
+        let other_chunk = parameters.into_chunk_parameters(ContributionMode::Chunked, 1, 4);
+        let other_chunk = ValidationCache::open(&path, &CurveKind::Bls12_377, &other_chunk).unwrap();
+        assert_ne!(
+            key,
+            other_chunk.key(ElementType::TauG1, &buffer, UseCompression::Yes, (0, 4))
+        );
+        buffer[0] ^= 1;
+        assert_ne!(key, cache.key(ElementType::TauG1, &buffer, UseCompression::Yes, (0, 4)));
+    }
+
+    #[test]
+    fn test_validation_cache_persists() {
+        let path = temp_path("validation_cache");
+        let _ = fs::remove_file(&path);
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 3, 4);
+
+        let cache = ValidationCache::open(&path, &CurveKind::Bls12_377, &parameters).unwrap();
+        assert!(cache.is_empty());
+        cache.insert("a".to_string());
+        cache.insert("b".to_string());
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("c"));
+        cache.save().unwrap();
+
+        let cache = ValidationCache::open(&path, &CurveKind::Bls12_377, &parameters).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains("a") && cache.contains("b"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,954 @@
+//! Verification primitives which operate purely on in-memory buffers.
+//!
+//! Nothing in here touches the filesystem or reads the clock, so the same checks the CLI
+//! performs on memory mapped files can be run on plain byte slices, e.g. from the browser
+//! when compiled with the `wasm` feature. With the `parallel` feature, the subgroup checks
+//! run on rayon's thread pool, otherwise everything runs on the calling thread.
+
+use crate::{
+    helpers::buffers::{split, SplitBuf},
+    ContributionMode, Phase1Parameters, ProvingSystem, PublicKey,
+};
+use setup_utils::{BatchDeserializer, *};
+
+use zexe_algebra::{batch_verify_in_subgroup, cfg_iter, AffineCurve, FpParameters, PairingEngine, PrimeField, Zero};
+
+use rand::{thread_rng, Rng};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use tracing::debug;
+
+#[cfg(not(feature = "wasm"))]
+use tracing::error;
+
+/// Given a public key and the accumulator's digest, it hashes each G1 element
+/// along with the digest, and then hashes it to G2.
+pub fn compute_g2_s_key<E: PairingEngine>(key: &PublicKey<E>, digest: &[u8]) -> Result<[E::G2Affine; 3]> {
+    Ok([
+        compute_g2_s::<E>(&digest, &key.tau_g1.0, &key.tau_g1.1, 0)?,
+        compute_g2_s::<E>(&digest, &key.alpha_g1.0, &key.alpha_g1.1, 1)?,
+        compute_g2_s::<E>(&digest, &key.beta_g1.0, &key.beta_g1.1, 2)?,
+    ])
+}
+
+/// Binds the digest to the chunk index in chunked mode if `bind_chunk_index` is set, so that
+/// the proofs of knowledge of a response are only valid for the chunk it was produced for,
+/// even if the challenges of two chunks are identical. Otherwise the digest is used as-is.
+pub fn bind_digest_to_chunk<E: PairingEngine>(digest: &[u8], parameters: &Phase1Parameters<E>) -> Vec<u8> {
+    match parameters.contribution_mode {
+        ContributionMode::Chunked if parameters.bind_chunk_index => {
+            let mut preimage = digest.to_vec();
+            preimage.extend_from_slice(b"chunk");
+            preimage.extend_from_slice(&(parameters.chunk_index as u64).to_le_bytes());
+            calculate_hash(&preimage).to_vec()
+        }
+        _ => digest.to_vec(),
+    }
+}
+
+/// Returns the randomness of the verification of the batch of `section` starting at `start`.
+/// With a verification seed, it is derived from the seed and the position of the batch, so
+/// that the batches, which are verified concurrently, draw the same randomness on every run.
+pub fn verification_rng<E: PairingEngine>(
+    section: ElementType,
+    start: usize,
+    parameters: &Phase1Parameters<E>,
+) -> impl Rng {
+    let entropy = match parameters.verification_seed {
+        Some(seed) => {
+            let mut preimage = seed.to_vec();
+            preimage.extend_from_slice(section.to_string().as_bytes());
+            preimage.extend_from_slice(&(parameters.chunk_index as u64).to_le_bytes());
+            preimage.extend_from_slice(&(start as u64).to_le_bytes());
+            derive_entropy_from_seed(&preimage)
+        }
+        None => thread_rng().gen(),
+    };
+    rng_from_entropy(entropy)
+}
+
+/// Checks that every point of the key is nonzero and in the prime order subgroup, so that a
+/// malformed key is reported as such instead of as a failed ratio check further down.
+pub fn check_public_key<E: PairingEngine>(key: &PublicKey<E>) -> Result<()> {
+    let g1_points = [
+        (key.tau_g1.0, "tau_g1.0"),
+        (key.tau_g1.1, "tau_g1.1"),
+        (key.alpha_g1.0, "alpha_g1.0"),
+        (key.alpha_g1.1, "alpha_g1.1"),
+        (key.beta_g1.0, "beta_g1.0"),
+        (key.beta_g1.1, "beta_g1.1"),
+    ];
+    let g2_points = [
+        (key.tau_g2, "tau_g2"),
+        (key.alpha_g2, "alpha_g2"),
+        (key.beta_g2, "beta_g2"),
+    ];
+
+    for (point, name) in g1_points.iter() {
+        if point.is_zero() || !elements_are_in_prime_order_subgroup(&[*point], SubgroupCheckMode::Direct) {
+            return Err(VerificationError::InvalidPublicKey(name).into());
+        }
+    }
+    for (point, name) in g2_points.iter() {
+        if point.is_zero() || !elements_are_in_prime_order_subgroup(&[*point], SubgroupCheckMode::Direct) {
+            return Err(VerificationError::InvalidPublicKey(name).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the proofs of knowledge of tau, alpha and beta in the key, which are bound to the
+/// digest, and returns the G2 points their G1 pairs were hashed to.
+pub fn verify_proofs_of_knowledge<E: PairingEngine>(key: &PublicKey<E>, digest: &[u8]) -> Result<[E::G2Affine; 3]> {
+    check_public_key(key)?;
+    let [tau_g2_s, alpha_g2_s, beta_g2_s] = compute_g2_s_key(&key, &digest)?;
+
+    let check_ratios = &[
+        (&(key.tau_g1.0, key.tau_g1.1), &(tau_g2_s, key.tau_g2), "Tau G1<>G2"),
+        (
+            &(key.alpha_g1.0, key.alpha_g1.1),
+            &(alpha_g2_s, key.alpha_g2),
+            "Alpha G1<>G2",
+        ),
+        (
+            &(key.beta_g1.0, key.beta_g1.1),
+            &(beta_g2_s, key.beta_g2),
+            "Beta G1<>G2",
+        ),
+    ];
+
+    for (a, b, err) in check_ratios {
+        check_same_ratio::<E>(a, b, err)?;
+    }
+    debug!("key ratios were correctly produced");
+
+    Ok([tau_g2_s, alpha_g2_s, beta_g2_s])
+}
+
+/// Attaches the section and the element range to a failed ratio check, so that
+/// a rejected contribution can be traced back to the exact batch which failed.
+#[cfg(not(feature = "wasm"))]
+fn ratio_error_in_range(err: Error, section: ElementType, (start, end): (usize, usize)) -> Error {
+    match err {
+        Error::VerificationError(VerificationError::InvalidRatio(context)) => {
+            error!(
+                "{} ratio check failed for {} elements in range [{}, {})",
+                context, section, start, end
+            );
+            VerificationError::InvalidRatioInRange {
+                context,
+                section,
+                start,
+                end,
+            }
+            .into()
+        }
+        err => err,
+    }
+}
+
+/// Reads a list of G1 elements from the buffer to the provided `elements` slice
+/// and then checks that their powers pairs ratio matches the one from the
+/// provided `check` pair. `offset` is the index in `section` of the first element
+/// of the buffer, so that a failure is reported at its position in the section.
+#[cfg(not(feature = "wasm"))]
+pub fn check_power_ratios<E: PairingEngine, R: Rng>(
+    (buffer, compression, check_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    (start, end): (usize, usize),
+    elements: &mut [E::G1Affine],
+    check: &(E::G2Affine, E::G2Affine),
+    (section, offset): (ElementType, usize),
+    rng: &mut R,
+) -> Result<()> {
+    let size = buffer_size::<E::G1Affine>(compression);
+    buffer[start * size..end * size].read_batch_preallocated(
+        &mut elements[0..end - start],
+        compression,
+        check_for_correctness,
+    )?;
+    check_same_ratio::<E>(
+        &power_pairs_with_rng(&elements[..end - start], rng),
+        check,
+        "Power pairs",
+    )
+    .map_err(|e| ratio_error_in_range(e, section, (offset + start, offset + end)))?;
+    Ok(())
+}
+
+/// Reads a list of G2 elements from the buffer to the provided `elements` slice
+/// and then checks that their powers pairs ratio matches the one from the
+/// provided `check` pair. `offset` is the index in `section` of the first element
+/// of the buffer, so that a failure is reported at its position in the section.
+#[cfg(not(feature = "wasm"))]
+pub fn check_power_ratios_g2<E: PairingEngine, R: Rng>(
+    (buffer, compression, check_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    (start, end): (usize, usize),
+    elements: &mut [E::G2Affine],
+    check: &(E::G1Affine, E::G1Affine),
+    (section, offset): (ElementType, usize),
+    rng: &mut R,
+) -> Result<()> {
+    let size = buffer_size::<E::G2Affine>(compression);
+    buffer[start * size..end * size].read_batch_preallocated(
+        &mut elements[0..end - start],
+        compression,
+        check_for_correctness,
+    )?;
+    check_same_ratio::<E>(
+        check,
+        &power_pairs_with_rng(&elements[..end - start], rng),
+        "Power pairs",
+    )
+    .map_err(|e| ratio_error_in_range(e, section, (offset + start, offset + end)))?;
+    Ok(())
+}
+
+/// Checks that all the elements are in the prime order subgroup, either one by one
+/// or with a randomized batch check depending on the `subgroup_check_mode`
+pub fn elements_are_in_prime_order_subgroup<C: AffineCurve>(
+    elements: &[C],
+    subgroup_check_mode: SubgroupCheckMode,
+) -> bool {
+    elements_are_in_prime_order_subgroup_with_rng(elements, subgroup_check_mode, &mut thread_rng())
+}
+
+/// Same as `elements_are_in_prime_order_subgroup`, except that the randomness of a batch
+/// check is drawn from `rng`, so that it can be reproduced
+pub fn elements_are_in_prime_order_subgroup_with_rng<C: AffineCurve, R: Rng>(
+    elements: &[C],
+    subgroup_check_mode: SubgroupCheckMode,
+    rng: &mut R,
+) -> bool {
+    const SECURITY_PARAM: usize = 128;
+    const BATCH_SIZE: usize = 1 << 12;
+    match (elements.len() > BATCH_SIZE, subgroup_check_mode) {
+        (true, SubgroupCheckMode::Auto) | (_, SubgroupCheckMode::Batched) => {
+            batch_verify_in_subgroup(elements, SECURITY_PARAM, rng).is_ok()
+        }
+        (false, SubgroupCheckMode::Auto) | (_, SubgroupCheckMode::Direct) => cfg_iter!(elements).all(|p| {
+            p.mul(<<C::ScalarField as PrimeField>::Params as FpParameters>::MODULUS)
+                .is_zero()
+        }),
+    }
+}
+
+/// Reads a list of group elements from the buffer to the provided `elements` slice
+/// and then checks that the elements are nonzero and in the prime order subgroup,
+/// drawing the randomness of a batch check from `rng`.
+pub fn check_elements_are_nonzero_and_in_prime_order_subgroup<C: AffineCurve, R: Rng>(
+    (buffer, compression): (&[u8], UseCompression),
+    (start, end): (usize, usize),
+    elements: &mut [C],
+    subgroup_check_mode: SubgroupCheckMode,
+    rng: &mut R,
+) -> Result<()> {
+    let size = buffer_size::<C>(compression);
+    buffer[start * size..end * size].read_batch_preallocated(
+        &mut elements[0..end - start],
+        compression,
+        CheckForCorrectness::OnlyNonZero,
+    )?;
+    if !elements_are_in_prime_order_subgroup_with_rng(&elements[0..end - start], subgroup_check_mode, rng) {
+        return Err(Error::IncorrectSubgroup);
+    }
+    Ok(())
+}
+
+/// Reads a chunk of 2 elements from the buffer
+pub fn read_initial_elements<C: AffineCurve>(
+    buffer: &[u8],
+    compressed: UseCompression,
+    check_input_for_correctness: CheckForCorrectness,
+) -> Result<Vec<C>> {
+    read_initial_elements_with_amount(buffer, 2, compressed, check_input_for_correctness)
+}
+
+/// Reads a chunk of {amount} elements from the buffer
+pub fn read_initial_elements_with_amount<C: AffineCurve>(
+    buffer: &[u8],
+    amount: usize,
+    compressed: UseCompression,
+    check_input_for_correctness: CheckForCorrectness,
+) -> Result<Vec<C>> {
+    let batch = amount;
+    let size = buffer_size::<C>(compressed);
+    let result = buffer[0..batch * size].read_batch(compressed, check_input_for_correctness)?;
+    if result.len() != batch {
+        return Err(Error::InvalidLength {
+            expected: batch,
+            got: result.len(),
+        });
+    }
+    Ok(result)
+}
+
+/// Checks the proofs of knowledge in the public key, and that the first elements of
+/// the output were correctly derived from the input with the key. This only applies to
+/// full contributions and to the first chunk, since the other chunks do not contain the
+/// initial elements.
+pub fn verify_initial_elements<E: PairingEngine>(
+    (input, compressed_input, check_input_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    key: &PublicKey<E>,
+    digest: &[u8],
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    verify_initial_sections(
+        (
+            split(input, parameters, compressed_input),
+            compressed_input,
+            check_input_for_correctness,
+        ),
+        (
+            split(output, parameters, compressed_output),
+            compressed_output,
+            check_output_for_correctness,
+        ),
+        key,
+        digest,
+        parameters,
+    )
+}
+
+/// Same as `verify_initial_elements`, on the sections of the input and of the output, of
+/// which only the first elements are read. Each section can thus be a window over the
+/// start of the section rather than the whole section.
+pub(crate) fn verify_initial_sections<E: PairingEngine>(
+    (input, compressed_input, check_input_for_correctness): (SplitBuf, UseCompression, CheckForCorrectness),
+    (output, compressed_output, check_output_for_correctness): (SplitBuf, UseCompression, CheckForCorrectness),
+    key: &PublicKey<E>,
+    digest: &[u8],
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = output;
+    let (in_tau_g1, in_tau_g2, in_alpha_g1, in_beta_g1, in_beta_g2) = input;
+
+    // Ensure the key ratios are correctly produced.
+    let [tau_g2_s, alpha_g2_s, beta_g2_s] = verify_proofs_of_knowledge(key, digest)?;
+
+    // Compose into tuple form for convenience.
+    let tau_single_g1_check = &(key.tau_g1.0, key.tau_g1.1);
+    let tau_single_g2_check = &(tau_g2_s, key.tau_g2);
+    // let alpha_single_g1_check = &(key.alpha_g1.0, key.alpha_g1.1);
+    let alpha_single_g2_check = &(alpha_g2_s, key.alpha_g2);
+    let beta_single_g1_check = &(key.beta_g1.0, key.beta_g1.1);
+    let beta_single_g2_check = &(beta_g2_s, key.beta_g2);
+
+    // Ensure that the initial conditions are correctly formed (first 2 elements).
+    // We allocate a G1 vector of length 2 and re-use it for our G1 elements.
+    // We keep the values of the tau_g1 / tau_g2 elements for later use.
+
+    // Check that tau^i was computed correctly in G1.
+    let (mut before_g1, mut after_g1) = {
+        // Previous iteration of tau_g1[0].
+        let before_g1 = read_initial_elements::<E::G1Affine>(in_tau_g1, compressed_input, check_input_for_correctness)?;
+        // Current iteration of tau_g1[0].
+        let after_g1 = read_initial_elements::<E::G1Affine>(tau_g1, compressed_output, check_output_for_correctness)?;
+
+        // Check tau_g1[0] is the prime subgroup generator.
+        if after_g1[0] != E::G1Affine::prime_subgroup_generator() {
+            return Err(VerificationError::InvalidGenerator(ElementType::TauG1).into());
+        }
+
+        // Check that tau^1 was multiplied correctly.
+        check_same_ratio::<E>(
+            &(before_g1[1], after_g1[1]),
+            tau_single_g2_check,
+            "Before-After: tau_g1",
+        )?;
+
+        (before_g1, after_g1)
+    };
+
+    // Check that tau^i was computed correctly in G2.
+    // We keep the first two tau_g2 elements to check the single alpha / beta elements.
+    let tau_g2_check = {
+        // Previous iteration of tau_g2[0].
+        let before_g2 = read_initial_elements::<E::G2Affine>(in_tau_g2, compressed_input, check_input_for_correctness)?;
+        // Current iteration of tau_g2[0].
+        let after_g2 = read_initial_elements::<E::G2Affine>(tau_g2, compressed_output, check_output_for_correctness)?;
+
+        // Check tau_g2[0] is the prime subgroup generator.
+        if after_g2[0] != E::G2Affine::prime_subgroup_generator() {
+            return Err(VerificationError::InvalidGenerator(ElementType::TauG2).into());
+        }
+
+        // Check that tau^1 was multiplied correctly.
+        check_same_ratio::<E>(
+            tau_single_g1_check,
+            &(before_g2[1], after_g2[1]),
+            "Before-After: tau_g2",
+        )?;
+
+        (after_g2[0], after_g2[1])
+    };
+
+    // Check that alpha_g1[0] and beta_g1[0] were computed correctly.
+    {
+        // Determine the check based on the proof system's requirements.
+        let checks = match parameters.proving_system {
+            ProvingSystem::Groth16 => vec![
+                (
+                    in_alpha_g1,
+                    alpha_g1,
+                    alpha_single_g2_check,
+                    "Single-Powers: alpha_g1[0] / alpha_g1[1]",
+                ),
+                (
+                    in_beta_g1,
+                    beta_g1,
+                    beta_single_g2_check,
+                    "Single-Powers: beta_g1[0] / beta_g1[1]",
+                ),
+            ],
+            ProvingSystem::Marlin => vec![(
+                in_alpha_g1,
+                alpha_g1,
+                alpha_single_g2_check,
+                "Single-Powers: alpha_g1[0] / alpha_g1[1]",
+            )],
+        };
+
+        // Check that alpha_g1[0] and beta_g1[0] was multiplied correctly.
+        for (before, after, check, consistency_context) in &checks {
+            before.read_batch_preallocated(&mut before_g1, compressed_input, check_input_for_correctness)?;
+            after.read_batch_preallocated(&mut after_g1, compressed_output, check_output_for_correctness)?;
+            check_same_ratio::<E>(
+                &(before_g1[0], after_g1[0]),
+                check,
+                "Before-After: alpha_g1[0] / beta_g1[0]",
+            )?;
+
+            // The single element must also be consistent with the powers following it, so
+            // that it cannot be tampered with independently of the series
+            check_same_ratio::<E>(&(after_g1[0], after_g1[1]), &tau_g2_check, consistency_context)?;
+        }
+    }
+
+    // Check that beta_g2[0] was computed correctly.
+    {
+        if parameters.proving_system == ProvingSystem::Groth16 {
+            // Read in the first beta_g2 element from the previous iteration and current iteration.
+            let before_beta_g2 =
+                (&*in_beta_g2).read_element::<E::G2Affine>(compressed_input, check_input_for_correctness)?;
+            let after_beta_g2 =
+                (&*beta_g2).read_element::<E::G2Affine>(compressed_output, check_output_for_correctness)?;
+
+            // Check that beta_g2[0] was multiplied correctly.
+            check_same_ratio::<E>(
+                beta_single_g1_check,
+                &(before_beta_g2, after_beta_g2),
+                "Before-After: beta_g2[0]",
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Only checks that the contributor knew the secrets of their key and, if the chunk contains
+/// them, that the first elements of the output are the generators. Neither the output's
+/// relation to the input nor its other elements are checked, so this is only a quick screen
+/// to run before a full verification.
+pub fn verify_pok<E: PairingEngine>(
+    (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    key: &PublicKey<E>,
+    digest: &[u8],
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    let output_length = parameters.get_length(compressed_output);
+    if output.len() < output_length {
+        return Err(Error::InvalidLength {
+            expected: output_length,
+            got: output.len(),
+        });
+    }
+
+    verify_proofs_of_knowledge(key, &bind_digest_to_chunk(digest, parameters))?;
+
+    if parameters.contribution_mode == ContributionMode::Full || parameters.chunk_index == 0 {
+        check_generators((output, compressed_output, check_output_for_correctness), parameters)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the first TauG1 and TauG2 elements of an accumulator are the generators.
+/// Only the first elements are read, so this is a quick sanity check of a large file. It
+/// only applies to full accumulators and to the first chunk, the other chunks do not
+/// contain the first elements.
+pub fn check_generators<E: PairingEngine>(
+    (input, compressed, check_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    let (tau_g1, tau_g2, _, _, _) = split(input, parameters, compressed);
+    let tau_g1 = read_initial_elements::<E::G1Affine>(tau_g1, compressed, check_for_correctness)?;
+    if tau_g1[0] != E::G1Affine::prime_subgroup_generator() {
+        return Err(VerificationError::InvalidGenerator(ElementType::TauG1).into());
+    }
+    let tau_g2 = read_initial_elements::<E::G2Affine>(tau_g2, compressed, check_for_correctness)?;
+    if tau_g2[0] != E::G2Affine::prime_subgroup_generator() {
+        return Err(VerificationError::InvalidGenerator(ElementType::TauG2).into());
+    }
+
+    Ok(())
+}
+
+/// Checks that the single BetaG2 element of an accumulator is nonzero, in the prime order
+/// subgroup and the G2 counterpart of the first BetaG1 element. None of the power ratio
+/// checks read it, so this is the only check of it in a verification of the ratios.
+#[cfg(not(feature = "wasm"))]
+pub fn check_beta_g2<E: PairingEngine>(
+    (beta_g1, beta_g2): (&[u8], &[u8]),
+    compressed: UseCompression,
+    check_for_correctness: CheckForCorrectness,
+) -> Result<()> {
+    let beta_g2 = (&*beta_g2).read_element::<E::G2Affine>(compressed, check_for_correctness)?;
+    if beta_g2.is_zero() {
+        return Err(Error::PointAtInfinity);
+    }
+    if !elements_are_in_prime_order_subgroup(&[beta_g2], SubgroupCheckMode::Direct) {
+        return Err(Error::IncorrectSubgroup);
+    }
+    let beta_g1 = read_initial_elements_with_amount::<E::G1Affine>(beta_g1, 1, compressed, check_for_correctness)?;
+    check_same_ratio::<E>(
+        &(E::G1Affine::prime_subgroup_generator(), beta_g1[0]),
+        &(E::G2Affine::prime_subgroup_generator(), beta_g2),
+        "BetaG1<>BetaG2",
+    )
+}
+
+/// Verifies a single contribution without spawning threads: the initial elements are
+/// checked against the public key if the chunk contains them, and every element of the
+/// output is checked to be nonzero and in the prime order subgroup, `batch_size`
+/// elements at a time. This is slower than `Phase1::verification`, but it can run in
+/// environments without threads, e.g. WASM.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_chunk<E: PairingEngine>(
+    (input, compressed_input, check_input_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+    key: &PublicKey<E>,
+    digest: &[u8],
+    subgroup_check_mode: SubgroupCheckMode,
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    let input_length = parameters.get_length(compressed_input);
+    if input.len() < input_length {
+        return Err(Error::InvalidLength {
+            expected: input_length,
+            got: input.len(),
+        });
+    }
+    let output_length = parameters.get_length(compressed_output);
+    if output.len() < output_length {
+        return Err(Error::InvalidLength {
+            expected: output_length,
+            got: output.len(),
+        });
+    }
+
+    // The proofs of knowledge may be bound to the chunk the response was produced for
+    let digest = &bind_digest_to_chunk(digest, parameters);
+    if parameters.contribution_mode == ContributionMode::Full || parameters.chunk_index == 0 {
+        verify_initial_elements(
+            (input, compressed_input, check_input_for_correctness),
+            (output, compressed_output, check_output_for_correctness),
+            key,
+            digest,
+            parameters,
+        )?;
+    } else if parameters.bind_chunk_index {
+        verify_proofs_of_knowledge(key, digest)?;
+    }
+
+    let (tau_g1, tau_g2, alpha_g1, beta_g1, _) = split(output, parameters, compressed_output);
+    check_section_in_batches::<E, E::G1Affine>(
+        (tau_g1, ElementType::TauG1),
+        compressed_output,
+        subgroup_check_mode,
+        parameters,
+    )?;
+    check_section_in_batches::<E, E::G2Affine>(
+        (tau_g2, ElementType::TauG2),
+        compressed_output,
+        subgroup_check_mode,
+        parameters,
+    )?;
+    check_section_in_batches::<E, E::G1Affine>(
+        (alpha_g1, ElementType::AlphaG1),
+        compressed_output,
+        subgroup_check_mode,
+        parameters,
+    )?;
+    check_section_in_batches::<E, E::G1Affine>(
+        (beta_g1, ElementType::BetaG1),
+        compressed_output,
+        subgroup_check_mode,
+        parameters,
+    )?;
+    debug!("all elements are nonzero and in the prime order subgroup");
+
+    Ok(())
+}
+
+/// Checks all the elements of a section, `batch_size` elements at a time
+fn check_section_in_batches<E: PairingEngine, C: AffineCurve>(
+    (section, element_type): (&[u8], ElementType),
+    compressed: UseCompression,
+    subgroup_check_mode: SubgroupCheckMode,
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    let batch_size = parameters.batch_size;
+    let num_elements = section.len() / buffer_size::<C>(compressed);
+    let mut elements = vec![C::zero(); batch_size];
+    for start in (0..num_elements).step_by(batch_size) {
+        let end = std::cmp::min(start + batch_size, num_elements);
+        check_elements_are_nonzero_and_in_prime_order_subgroup(
+            (section, compressed),
+            (start, end),
+            &mut elements,
+            subgroup_check_mode,
+            &mut verification_rng(element_type, start, parameters),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        helpers::testing::{generate_input, generate_output, random_point, random_point_vec, setup_verify},
+        Phase1,
+    };
+    use setup_utils::BatchSerializer;
+
+    use zexe_algebra::{bls12_377::Bls12_377, ProjectiveCurve};
+
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn test_check_power_ratios_reports_range() {
+        type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+        type G2 = <Bls12_377 as PairingEngine>::G2Affine;
+
+        // Random points are not powers of anything, so the ratio check must fail.
+        let mut rng = thread_rng();
+        let num_els = 4;
+        let elements: Vec<G1> = random_point_vec(num_els, &mut rng);
+        let mut buffer = vec![0; num_els * buffer_size::<G1>(UseCompression::No)];
+        buffer.write_batch(&elements, UseCompression::No).unwrap();
+        let check: (G2, G2) = (random_point(&mut rng), random_point(&mut rng));
+
+        let mut g1 = vec![G1::zero(); num_els];
+        let err = check_power_ratios::<Bls12_377, _>(
+            (&buffer, UseCompression::No, CheckForCorrectness::No),
+            (1, num_els),
+            &mut g1,
+            &check,
+            (ElementType::TauG1, 8),
+            &mut rng,
+        )
+        .unwrap_err();
+        match err {
+            Error::VerificationError(VerificationError::InvalidRatioInRange {
+                section, start, end, ..
+            }) => {
+                assert_eq!(section, ElementType::TauG1);
+                // The range is reported relative to the section, not to the buffer
+                assert_eq!((start, end), (8 + 1, 8 + num_els));
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_verification_rng_is_reproducible_with_a_seed() {
+        let mut parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 2, 4);
+        parameters.verification_seed = Some([1; 32]);
+        let draw = |section, start| verification_rng(section, start, &parameters).gen::<[u8; 32]>();
+
+        assert_eq!(draw(ElementType::TauG1, 0), draw(ElementType::TauG1, 0));
+        // Each batch of each section draws its own randomness
+        assert_ne!(draw(ElementType::TauG1, 0), draw(ElementType::TauG1, 4));
+        assert_ne!(draw(ElementType::TauG1, 0), draw(ElementType::AlphaG1, 0));
+    }
+
+    #[test]
+    fn test_subgroup_check_modes_agree() {
+        type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+
+        let mut rng = thread_rng();
+        let num_els = 8;
+        let elements: Vec<G1> = random_point_vec(num_els, &mut rng);
+        let mut buffer = vec![0; num_els * buffer_size::<G1>(UseCompression::Yes)];
+        buffer.write_batch(&elements, UseCompression::Yes).unwrap();
+
+        for mode in &[
+            SubgroupCheckMode::Auto,
+            SubgroupCheckMode::Direct,
+            SubgroupCheckMode::Batched,
+        ] {
+            assert!(elements_are_in_prime_order_subgroup(&elements, *mode));
+            let mut read = vec![G1::zero(); num_els];
+            check_elements_are_nonzero_and_in_prime_order_subgroup(
+                (&buffer, UseCompression::Yes),
+                (0, num_els),
+                &mut read,
+                *mode,
+                &mut rng,
+            )
+            .unwrap();
+            assert_eq!(read, elements);
+        }
+    }
+
+    #[test]
+    fn test_verify_chunk() {
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let powers = 5;
+            let batch = 4;
+            let chunk_size = 3 + 3 * powers + 1;
+            let full = Phase1Parameters::<Bls12_377>::new_full(*proving_system, powers, batch);
+            for parameters in &[
+                full.clone(),
+                full.into_chunk_parameters(ContributionMode::Chunked, 0, chunk_size),
+                full.into_chunk_parameters(ContributionMode::Chunked, 1, chunk_size),
+            ] {
+                let (input, _) = generate_input(parameters, UseCompression::No, CheckForCorrectness::No);
+                let mut output = generate_output(parameters, UseCompression::Yes);
+                let digest = blank_hash();
+                let mut rng = derive_rng_from_seed(b"test_verify_chunk");
+                let (pubkey, privkey) = Phase1::key_generation(&mut rng, digest.as_ref()).unwrap();
+                Phase1::computation(
+                    &input,
+                    &mut output,
+                    UseCompression::No,
+                    UseCompression::Yes,
+                    CheckForCorrectness::No,
+                    BatchExpMode::Auto,
+                    &privkey,
+                    parameters,
+                )
+                .unwrap();
+
+                verify_chunk(
+                    (&input, UseCompression::No, CheckForCorrectness::No),
+                    (&output, UseCompression::Yes, CheckForCorrectness::Full),
+                    &pubkey,
+                    digest.as_ref(),
+                    SubgroupCheckMode::Auto,
+                    parameters,
+                )
+                .unwrap();
+
+                // The proofs of knowledge are bound to the digest of the challenge,
+                // which is only checked by the chunk containing the initial elements
+                let res = verify_chunk(
+                    (&input, UseCompression::No, CheckForCorrectness::No),
+                    (&output, UseCompression::Yes, CheckForCorrectness::Full),
+                    &pubkey,
+                    &[1; 64],
+                    SubgroupCheckMode::Auto,
+                    parameters,
+                );
+                assert_eq!(res.is_err(), parameters.chunk_index == 0);
+
+                // Truncated responses are rejected upfront
+                let truncated = &output[..output.len() - 1];
+                let res = verify_chunk(
+                    (&input, UseCompression::No, CheckForCorrectness::No),
+                    (truncated, UseCompression::Yes, CheckForCorrectness::Full),
+                    &pubkey,
+                    digest.as_ref(),
+                    SubgroupCheckMode::Auto,
+                    parameters,
+                );
+                assert!(res.is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_response_is_bound_to_its_chunk() {
+        let powers = 5;
+        let batch = 4;
+        // Small enough for the first two chunks to have identical layouts and challenges
+        let chunk_size = 4;
+        let mut full = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, powers, batch);
+        full.bind_chunk_index = true;
+        let chunk_0 = full.into_chunk_parameters(ContributionMode::Chunked, 0, chunk_size);
+        let chunk_1 = full.into_chunk_parameters(ContributionMode::Chunked, 1, chunk_size);
+
+        let (input, _) = generate_input(&chunk_0, UseCompression::No, CheckForCorrectness::No);
+        let (input_1, _) = generate_input(&chunk_1, UseCompression::No, CheckForCorrectness::No);
+        assert_eq!(input, input_1);
+
+        // Contribute to the first chunk
+        let digest = blank_hash();
+        let mut rng = derive_rng_from_seed(b"test_response_is_bound_to_its_chunk");
+        let (pubkey, privkey) = Phase1::key_generation(&mut rng, &bind_digest_to_chunk(&digest, &chunk_0)).unwrap();
+        let mut output = generate_output(&chunk_0, UseCompression::Yes);
+        Phase1::computation(
+            &input,
+            &mut output,
+            UseCompression::No,
+            UseCompression::Yes,
+            CheckForCorrectness::No,
+            BatchExpMode::Auto,
+            &privkey,
+            &chunk_0,
+        )
+        .unwrap();
+
+        let verify = |parameters| {
+            verify_chunk(
+                (&input, UseCompression::No, CheckForCorrectness::No),
+                (&output, UseCompression::Yes, CheckForCorrectness::Full),
+                &pubkey,
+                digest.as_ref(),
+                SubgroupCheckMode::Auto,
+                parameters,
+            )
+        };
+        assert!(verify(&chunk_0).is_ok());
+        // Replaying the response as the second chunk is rejected
+        assert!(verify(&chunk_1).is_err());
+
+        // Without the binding, the replay goes unnoticed
+        let mut unbound_chunk_1 = chunk_1.clone();
+        unbound_chunk_1.bind_chunk_index = false;
+        assert!(verify(&unbound_chunk_1).is_ok());
+    }
+
+    #[test]
+    fn test_single_elements_are_consistent_with_powers() {
+        type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+        type G2 = <Bls12_377 as PairingEngine>::G2Affine;
+
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 5, 4);
+        let compressed = UseCompression::No;
+        let check = CheckForCorrectness::Full;
+        let (mut input, mut output, pubkey, digest) =
+            setup_verify(compressed, check, compressed, BatchExpMode::Auto, &parameters);
+        let verify = |input: &[u8], output: &[u8]| {
+            verify_initial_elements(
+                (input, compressed, check),
+                (output, compressed, check),
+                &pubkey,
+                &bind_digest_to_chunk(&digest, &parameters),
+                &parameters,
+            )
+        };
+        verify(&input, &output).unwrap();
+
+        // Scale alpha_g1[0] by the same factor before and after the contribution, so that
+        // the ratio between them still matches the key, but not the powers which follow it
+        let g1_size = buffer_size::<G1>(compressed);
+        let alpha_g1 = parameters.hash_size
+            + parameters.powers_g1_length * g1_size
+            + parameters.powers_length * buffer_size::<G2>(compressed);
+        for buffer in &mut [&mut input, &mut output] {
+            let alpha = (&buffer[alpha_g1..alpha_g1 + g1_size])
+                .read_element::<G1>(compressed, check)
+                .unwrap();
+            let tampered = alpha.mul(<Bls12_377 as PairingEngine>::Fr::from(2u64)).into_affine();
+            (&mut buffer[alpha_g1..alpha_g1 + g1_size])
+                .write_element(&tampered, compressed)
+                .unwrap();
+        }
+        match verify(&input, &output) {
+            Err(Error::VerificationError(VerificationError::InvalidRatio(context))) => {
+                assert_eq!(context, "Single-Powers: alpha_g1[0] / alpha_g1[1]")
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_verify_pok() {
+        let powers = 5;
+        let batch = 4;
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, powers, batch);
+        let (input, _) = generate_input(&parameters, UseCompression::No, CheckForCorrectness::No);
+        let mut output = generate_output(&parameters, UseCompression::Yes);
+        let digest = blank_hash();
+        let mut rng = derive_rng_from_seed(b"test_verify_pok");
+        let (pubkey, privkey) = Phase1::key_generation(&mut rng, digest.as_ref()).unwrap();
+        Phase1::computation(
+            &input,
+            &mut output,
+            UseCompression::No,
+            UseCompression::Yes,
+            CheckForCorrectness::No,
+            BatchExpMode::Auto,
+            &privkey,
+            &parameters,
+        )
+        .unwrap();
+
+        let check = CheckForCorrectness::Full;
+        verify_pok(
+            (&output, UseCompression::Yes, check),
+            &pubkey,
+            digest.as_ref(),
+            &parameters,
+        )
+        .unwrap();
+        // A key for another digest does not prove knowledge for this one
+        assert!(verify_pok((&output, UseCompression::Yes, check), &pubkey, &[1; 64], &parameters).is_err());
+
+        // The first element must be the generator
+        let g1_size = buffer_size::<<Bls12_377 as PairingEngine>::G1Affine>(UseCompression::Yes);
+        let first = parameters.hash_size;
+        let second = output[first + g1_size..first + 2 * g1_size].to_vec();
+        output[first..first + g1_size].copy_from_slice(&second);
+        match verify_pok(
+            (&output, UseCompression::Yes, check),
+            &pubkey,
+            digest.as_ref(),
+            &parameters,
+        ) {
+            Err(Error::VerificationError(VerificationError::InvalidGenerator(ElementType::TauG1))) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_check_generators() {
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 5, 4);
+        let (mut input, _) = generate_input(&parameters, UseCompression::No, CheckForCorrectness::No);
+        let check = CheckForCorrectness::Full;
+        check_generators((&input, UseCompression::No, check), &parameters).unwrap();
+
+        // The section whose first element is not the generator is reported
+        type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+        type G2 = <Bls12_377 as PairingEngine>::G2Affine;
+        let g2_size = buffer_size::<G2>(UseCompression::No);
+        let tau_g2 = parameters.hash_size + parameters.powers_g1_length * buffer_size::<G1>(UseCompression::No);
+        let point: G2 = random_point(&mut thread_rng());
+        (&mut input[tau_g2..tau_g2 + g2_size])
+            .write_element(&point, UseCompression::No)
+            .unwrap();
+        match check_generators((&input, UseCompression::No, check), &parameters) {
+            Err(Error::VerificationError(VerificationError::InvalidGenerator(ElementType::TauG2))) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_public_key_with_zero_point_is_rejected() {
+        let digest = blank_hash();
+        let mut rng = derive_rng_from_seed(b"test_public_key_with_zero_point_is_rejected");
+        let (mut pubkey, _) = Phase1::<Bls12_377>::key_generation(&mut rng, digest.as_ref()).unwrap();
+        check_public_key(&pubkey).unwrap();
+        verify_proofs_of_knowledge(&pubkey, digest.as_ref()).unwrap();
+
+        pubkey.alpha_g2 = <Bls12_377 as PairingEngine>::G2Affine::zero();
+        match verify_proofs_of_knowledge(&pubkey, digest.as_ref()) {
+            Err(Error::VerificationError(VerificationError::InvalidPublicKey("alpha_g2"))) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
@@ -54,6 +54,55 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
 
         Ok(())
     }
+
+    /// Same as `initialization`, except that the accumulator is produced one batch at a time
+    /// instead of in a single buffer. `write_batch` is called in order with the byte offset
+    /// of each batch and its bytes, covering everything after the hash, so that a large
+    /// accumulator can be written out without holding it in memory.
+    pub fn initialization_in_batches(
+        compressed_output: UseCompression,
+        parameters: &'a Phase1Parameters<E>,
+        mut write_batch: impl FnMut(usize, &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let span = info_span!("phase1-initialization-in-batches");
+        let _ = span.enter();
+
+        let g1_size = buffer_size::<E::G1Affine>(compressed_output);
+        let g2_size = buffer_size::<E::G2Affine>(compressed_output);
+        let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = section_lengths(parameters);
+
+        let mut offset = parameters.hash_size;
+        for (length, in_g2) in &[
+            (tau_g1, false),
+            (tau_g2, true),
+            (alpha_g1, false),
+            (beta_g1, false),
+            (beta_g2, true),
+        ] {
+            let size = if *in_g2 { g2_size } else { g1_size };
+            for start in (0..*length).step_by(parameters.batch_size) {
+                let end = std::cmp::min(start + parameters.batch_size, *length);
+                let mut batch = vec![0; (end - start) * size];
+                if *in_g2 {
+                    batch.init_element(&E::G2Affine::prime_subgroup_generator(), compressed_output)?;
+                } else {
+                    batch.init_element(&E::G1Affine::prime_subgroup_generator(), compressed_output)?;
+                }
+                write_batch(offset + start * size, &batch)?;
+            }
+            offset += length * size;
+        }
+
+        // The single elements which are not initialized are left zeroed, as in `initialization`
+        let length = parameters.get_length(compressed_output);
+        if offset < length {
+            write_batch(offset, &vec![0; length - offset])?;
+        }
+
+        info!("phase1-initialization-in-batches complete");
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -105,6 +154,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_initialization_in_batches() {
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            for compression in &[UseCompression::Yes, UseCompression::No] {
+                let parameters = Phase1Parameters::<Bls12_377>::new_full(*proving_system, 4, 4);
+                let mut expected = vec![0; parameters.get_length(*compression)];
+                Phase1::initialization(&mut expected, *compression, &parameters).unwrap();
+
+                let mut output = vec![0; parameters.hash_size];
+                Phase1::initialization_in_batches(*compression, &parameters, |offset, batch| {
+                    assert_eq!(offset, output.len());
+                    assert!(batch.len() <= parameters.batch_size * parameters.curve.g2_size);
+                    output.extend_from_slice(batch);
+                    Ok(())
+                })
+                .unwrap();
+                assert_eq!(output, expected);
+            }
+        }
+    }
+
     #[test]
     fn test_initialization_bls12_377_compressed() {
         curve_initialization_test::<Bls12_377>(4, 4, UseCompression::Yes);
@@ -11,6 +11,8 @@ mod key_generation;
 mod serialization;
 #[cfg(not(feature = "wasm"))]
 mod verification;
+#[cfg(not(feature = "wasm"))]
+pub use verification::{BatchTiming, ChunkResult, SubgroupWarning, VerificationReport};
 
 use crate::helpers::{
     accumulator::{self},
@@ -19,7 +21,7 @@ use crate::helpers::{
 use setup_utils::*;
 
 #[cfg(not(feature = "wasm"))]
-use crate::helpers::accumulator::*;
+use crate::helpers::{accumulator::*, verification_core::*};
 
 #[cfg(not(feature = "wasm"))]
 use zexe_algebra::Zero;
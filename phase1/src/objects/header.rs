@@ -0,0 +1,173 @@
+use crate::{helpers::CurveKind, ContributionMode, Phase1Parameters, ProvingSystem};
+use setup_utils::{Error, UseCompression};
+
+use std::convert::TryInto;
+
+/// The magic bytes which mark a framed response file
+pub const HEADER_MAGIC: [u8; 4] = *b"PH1R";
+/// The current version of the header layout
+pub const HEADER_VERSION: u8 = 1;
+/// The size of the header, including some reserved space for future fields
+pub const HEADER_SIZE: usize = 32;
+
+/// An optional self-describing header which is written in front of framed response files.
+/// It records the parameters the file was produced with, so that reading a file with
+/// mismatched parameters fails immediately instead of misinterpreting its contents.
+///
+/// Layout (integers are little endian):
+/// magic (4) | version (1) | curve (1) | proving system (1) | compression (1) |
+/// power (4) | chunk index (8) | chunk size (8) | reserved (4)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseHeader {
+    pub curve_id: u8,
+    pub proving_system_id: u8,
+    pub compressed: bool,
+    pub power: u32,
+    pub chunk_index: u64,
+    pub chunk_size: u64,
+}
+
+impl ResponseHeader {
+    /// Constructs the header which describes a file produced with the provided parameters
+    pub fn new<E>(curve: &CurveKind, compression: UseCompression, parameters: &Phase1Parameters<E>) -> Self {
+        let (chunk_index, chunk_size) = match parameters.contribution_mode {
+            ContributionMode::Chunked => (parameters.chunk_index, parameters.chunk_size),
+            ContributionMode::Full => (0, 0),
+        };
+        Self {
+            curve_id: match curve {
+                CurveKind::Bls12_377 => 0,
+                CurveKind::BW6 => 1,
+            },
+            proving_system_id: match parameters.proving_system {
+                ProvingSystem::Groth16 => 0,
+                ProvingSystem::Marlin => 1,
+            },
+            compressed: compression == UseCompression::Yes,
+            power: parameters.total_size_in_log2 as u32,
+            chunk_index: chunk_index as u64,
+            chunk_size: chunk_size as u64,
+        }
+    }
+
+    /// Writes the header to the start of the buffer
+    pub fn write(&self, output: &mut [u8]) -> Result<(), Error> {
+        if output.len() < HEADER_SIZE {
+            return Err(Error::InvalidLength {
+                expected: HEADER_SIZE,
+                got: output.len(),
+            });
+        }
+        let output = &mut output[..HEADER_SIZE];
+        output[0..4].copy_from_slice(&HEADER_MAGIC);
+        output[4] = HEADER_VERSION;
+        output[5] = self.curve_id;
+        output[6] = self.proving_system_id;
+        output[7] = self.compressed as u8;
+        output[8..12].copy_from_slice(&self.power.to_le_bytes());
+        output[12..20].copy_from_slice(&self.chunk_index.to_le_bytes());
+        output[20..28].copy_from_slice(&self.chunk_size.to_le_bytes());
+        output[28..32].copy_from_slice(&[0; 4]);
+        Ok(())
+    }
+
+    /// Reads the header from the start of the buffer. Returns `None` if the buffer is
+    /// not framed, so that headerless files remain readable.
+    pub fn read(input: &[u8]) -> Result<Option<Self>, Error> {
+        if input.len() < HEADER_SIZE || input[0..4] != HEADER_MAGIC {
+            return Ok(None);
+        }
+        if input[4] != HEADER_VERSION {
+            return Err(Error::InvalidHeader {
+                field: "version",
+                expected: HEADER_VERSION.to_string(),
+                got: input[4].to_string(),
+            });
+        }
+        Ok(Some(Self {
+            curve_id: input[5],
+            proving_system_id: input[6],
+            compressed: input[7] != 0,
+            power: u32::from_le_bytes(input[8..12].try_into().expect("slice has the right length")),
+            chunk_index: u64::from_le_bytes(input[12..20].try_into().expect("slice has the right length")),
+            chunk_size: u64::from_le_bytes(input[20..28].try_into().expect("slice has the right length")),
+        }))
+    }
+
+    /// Checks that the header matches the expected one, failing on the first field which differs
+    pub fn validate(&self, expected: &Self) -> Result<(), Error> {
+        let fields = [
+            ("curve", self.curve_id as u64, expected.curve_id as u64),
+            (
+                "proving system",
+                self.proving_system_id as u64,
+                expected.proving_system_id as u64,
+            ),
+            ("compression", self.compressed as u64, expected.compressed as u64),
+            ("power", self.power as u64, expected.power as u64),
+            ("chunk index", self.chunk_index, expected.chunk_index),
+            ("chunk size", self.chunk_size, expected.chunk_size),
+        ];
+        for (field, got, want) in fields.iter() {
+            if got != want {
+                return Err(Error::InvalidHeader {
+                    field: *field,
+                    expected: want.to_string(),
+                    got: got.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the contents of the buffer after the header, after validating the header
+    /// against the expected one. Headerless buffers are returned unchanged.
+    pub fn strip<'a>(input: &'a [u8], expected: &Self) -> Result<&'a [u8], Error> {
+        match Self::read(input)? {
+            Some(header) => {
+                header.validate(expected)?;
+                Ok(&input[HEADER_SIZE..])
+            }
+            None => Ok(input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zexe_algebra::Bls12_377;
+
+    #[test]
+    fn test_header_roundtrip() {
+        let parameters =
+            Phase1Parameters::<Bls12_377>::new_chunk(ContributionMode::Chunked, 3, 64, ProvingSystem::Groth16, 10, 8);
+        let header = ResponseHeader::new(&CurveKind::Bls12_377, UseCompression::Yes, &parameters);
+
+        let mut buf = vec![1; HEADER_SIZE + 10];
+        header.write(&mut buf).unwrap();
+        assert_eq!(ResponseHeader::read(&buf).unwrap(), Some(header.clone()));
+        assert_eq!(ResponseHeader::strip(&buf, &header).unwrap(), &[1; 10][..]);
+
+        // headerless buffers are left alone
+        let headerless = vec![1; HEADER_SIZE + 10];
+        assert_eq!(ResponseHeader::read(&headerless).unwrap(), None);
+        assert_eq!(ResponseHeader::strip(&headerless, &header).unwrap(), &headerless[..]);
+    }
+
+    #[test]
+    fn test_header_mismatch() {
+        let parameters =
+            Phase1Parameters::<Bls12_377>::new_chunk(ContributionMode::Chunked, 3, 64, ProvingSystem::Groth16, 10, 8);
+        let header = ResponseHeader::new(&CurveKind::Bls12_377, UseCompression::Yes, &parameters);
+        let mut buf = vec![0; HEADER_SIZE];
+        header.write(&mut buf).unwrap();
+
+        let other = parameters.into_chunk_parameters(ContributionMode::Chunked, 4, 64);
+        let expected = ResponseHeader::new(&CurveKind::Bls12_377, UseCompression::Yes, &other);
+        match ResponseHeader::strip(&buf, &expected) {
+            Err(Error::InvalidHeader { field, .. }) => assert_eq!(field, "chunk index"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
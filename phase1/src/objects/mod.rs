@@ -1,3 +1,6 @@
+pub mod header;
+pub use header::*;
+
 pub mod parameters;
 pub use parameters::*;
 
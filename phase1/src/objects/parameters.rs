@@ -1,4 +1,4 @@
-use setup_utils::UseCompression;
+use setup_utils::{Error, UseCompression};
 
 use zexe_algebra::{ConstantSerializedSize, PairingEngine};
 
@@ -27,9 +27,24 @@ pub struct CurveParameters<E> {
     pub g1_compressed_size: usize,
     /// Size of a compressed G2 Element
     pub g2_compressed_size: usize,
+    /// Only a phantom tag, the sizes above are not required to match the engine's
     engine_type: PhantomData<E>,
 }
 
+impl<E> CurveParameters<E> {
+    /// Constructs the curve parameters from explicitly provided element sizes. This
+    /// does not require a real engine, so `E` is then only used as a phantom tag.
+    pub fn from_sizes(g1_size: usize, g2_size: usize, g1_compressed_size: usize, g2_compressed_size: usize) -> Self {
+        CurveParameters {
+            g1_size,
+            g2_size,
+            g1_compressed_size,
+            g2_compressed_size,
+            engine_type: PhantomData,
+        }
+    }
+}
+
 impl<E: PairingEngine> CurveParameters<E> {
     pub fn new() -> CurveParameters<E> {
         CurveParameters {
@@ -42,6 +57,36 @@ impl<E: PairingEngine> CurveParameters<E> {
     }
 }
 
+/// The number of single elements of each group which follow the powers in the Groth16
+/// layout, the G2 ones first. They are not chunked, every chunk holds all of them.
+///
+/// Only the sizes account for other counts so far: the contribution, aggregation and
+/// verification only handle beta in G2, so other counts are rejected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SingleElementCounts {
+    /// The number of single G1 elements
+    pub g1: usize,
+    /// The number of single G2 elements
+    pub g2: usize,
+}
+
+impl Default for SingleElementCounts {
+    /// The counts of Groth16, whose only single element is beta in G2
+    fn default() -> Self {
+        Self { g1: 0, g2: 1 }
+    }
+}
+
+impl SingleElementCounts {
+    /// Returns the size of the single elements, compressed or not
+    pub fn size<E>(&self, curve: &CurveParameters<E>, compressed: UseCompression) -> usize {
+        match compressed {
+            UseCompression::Yes => self.g1 * curve.g1_compressed_size + self.g2 * curve.g2_compressed_size,
+            UseCompression::No => self.g1 * curve.g1_size + self.g2 * curve.g2_size,
+        }
+    }
+}
+
 /// The parameters used for the trusted setup ceremony
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Phase1Parameters<E> {
@@ -76,6 +121,34 @@ pub struct Phase1Parameters<E> {
     pub contribution_size: usize,
     /// Size of the hash of the previous contribution
     pub hash_size: usize,
+    /// Milliseconds to sleep between batches, trading speed for lower sustained resource usage.
+    /// Defaults to 0, which disables throttling.
+    pub throttle_ms: u64,
+    /// The number of elements of a batch which are exponentiated together, the grains of a
+    /// batch being processed in parallel. This can be lower than `batch_size - 1` to divide
+    /// the work more finely without shrinking the read buffers. Defaults to `batch_size - 1`.
+    pub parallel_grain: usize,
+    /// The maximum number of sections verified at once, bounding the memory which is
+    /// allocated for them concurrently. Defaults to 0, which means unlimited.
+    pub max_inflight: usize,
+    /// The maximum number of idle buffers of each length kept for reuse when reading batches,
+    /// reducing allocations at the cost of memory. Defaults to 0, which disables pooling.
+    pub buffer_pool_size: usize,
+    /// Whether aggregation and splitting, which otherwise trust the elements they read,
+    /// fully check them. Defaults to false.
+    pub strict_correctness_checks: bool,
+    /// Whether the proofs of knowledge of chunked contributions are bound to their chunk index,
+    /// so that a response is only valid for the chunk it was produced for, and are checked in
+    /// every chunk. The contributors and the verifiers of a ceremony must agree on it. Defaults
+    /// to false, the unbound digest of earlier ceremonies.
+    pub bind_chunk_index: bool,
+    /// The seed the randomness of the verification is derived from, i.e. the random linear
+    /// combinations of the ratio checks and of the batched subgroup checks, so that two runs
+    /// follow the same verification path. Defaults to None, which draws fresh randomness.
+    pub verification_seed: Option<[u8; 32]>,
+    /// The number of single elements after the powers, which only the Groth16 layout has.
+    /// Defaults to the Groth16 counts.
+    pub single_element_counts: SingleElementCounts,
 }
 
 impl<E: PairingEngine> Phase1Parameters<E> {
@@ -117,6 +190,39 @@ impl<E: PairingEngine> Phase1Parameters<E> {
         )
     }
 
+    /// Same as `new`, except that the parameters are checked to describe a ceremony: the
+    /// batch size must be positive, and so must the chunk size in chunked mode. The chunk
+    /// size is ignored in full mode.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        contribution_mode: ContributionMode,
+        chunk_index: usize,
+        chunk_size: usize,
+        curve: CurveParameters<E>,
+        proving_system: ProvingSystem,
+        total_size_in_log2: usize,
+        batch_size: usize,
+    ) -> Result<Self, Error> {
+        if batch_size == 0 {
+            return Err(Error::InvalidParameters("the batch size must be positive"));
+        }
+        if contribution_mode == ContributionMode::Chunked && chunk_size == 0 {
+            return Err(Error::InvalidParameters(
+                "the chunk size must be positive in chunked mode",
+            ));
+        }
+
+        Ok(Self::new(
+            contribution_mode,
+            chunk_index,
+            chunk_size,
+            curve,
+            proving_system,
+            total_size_in_log2,
+            batch_size,
+        ))
+    }
+
     /// Constructs a new ceremony parameters object from the directly provided curve with parameters
     /// Consider using the `new` method if you want to use one of the pre-implemented curves
     pub fn new(
@@ -127,6 +233,43 @@ impl<E: PairingEngine> Phase1Parameters<E> {
         proving_system: ProvingSystem,
         total_size_in_log2: usize,
         batch_size: usize,
+    ) -> Self {
+        Self::new_with_single_element_counts(
+            contribution_mode,
+            chunk_index,
+            chunk_size,
+            curve,
+            proving_system,
+            total_size_in_log2,
+            batch_size,
+            SingleElementCounts::default(),
+        )
+    }
+
+    /// Returns the same parameters, but with `single_element_counts` single elements after
+    /// the powers, resizing the accumulator and the contribution accordingly. Fails for any
+    /// counts but the default ones, whose single elements would not be transformed or verified.
+    pub fn with_single_element_counts(&self, single_element_counts: SingleElementCounts) -> Result<Self, Error> {
+        if single_element_counts != SingleElementCounts::default() {
+            return Err(Error::Unsupported(
+                "only beta in G2 is transformed and verified, other single element counts are unsupported",
+            ));
+        }
+        let mut parameters = self.clone();
+        parameters.single_element_counts = single_element_counts;
+        Ok(parameters.into_chunk_parameters(self.contribution_mode, self.chunk_index, self.chunk_size))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_single_element_counts(
+        contribution_mode: ContributionMode,
+        chunk_index: usize,
+        chunk_size: usize,
+        curve: CurveParameters<E>,
+        proving_system: ProvingSystem,
+        total_size_in_log2: usize,
+        batch_size: usize,
+        single_element_counts: SingleElementCounts,
     ) -> Self {
         // assume we're using a 64 byte long hash function such as Blake
         let hash_size = 64;
@@ -145,8 +288,8 @@ impl<E: PairingEngine> Phase1Parameters<E> {
                 g1_chunk_size * curve.g1_size +
                     // G2 Tau Powers + Alpha Tau powers + Beta Tau powers
                     (other_chunk_size * (curve.g2_size + (curve.g1_size * 2))) +
-                    // Beta in G2
-                    curve.g2_size +
+                    // Beta in G2, and any other single element
+                    single_element_counts.size(&curve, UseCompression::No) +
                     // Hash of the previous contribution
                     hash_size
             }
@@ -178,8 +321,8 @@ impl<E: PairingEngine> Phase1Parameters<E> {
                 g1_chunk_size * curve.g1_compressed_size +
                     // G2 Tau Powers + Alpha Tau powers + Beta Tau powers (compressed)
                     (other_chunk_size * (curve.g2_compressed_size + (curve.g1_compressed_size * 2))) +
-                    // Beta in G2
-                    curve.g2_compressed_size +
+                    // Beta in G2, and any other single element
+                    single_element_counts.size(&curve, UseCompression::Yes) +
                     // Hash of the previous contribution
                     hash_size +
                     // The public key of the previous contributor
@@ -224,6 +367,14 @@ impl<E: PairingEngine> Phase1Parameters<E> {
             public_key_size,
             contribution_size,
             hash_size,
+            throttle_ms: 0,
+            parallel_grain: batch_size - 1,
+            max_inflight: 0,
+            buffer_pool_size: 0,
+            strict_correctness_checks: false,
+            bind_chunk_index: false,
+            verification_seed: None,
+            single_element_counts,
         }
     }
 
@@ -233,7 +384,7 @@ impl<E: PairingEngine> Phase1Parameters<E> {
         chunk_index: usize,
         chunk_size: usize,
     ) -> Self {
-        Self::new(
+        let mut parameters = Self::new_with_single_element_counts(
             contribution_mode,
             chunk_index,
             chunk_size,
@@ -241,7 +392,16 @@ impl<E: PairingEngine> Phase1Parameters<E> {
             self.proving_system,
             self.total_size_in_log2,
             self.batch_size,
-        )
+            self.single_element_counts,
+        );
+        parameters.throttle_ms = self.throttle_ms;
+        parameters.parallel_grain = self.parallel_grain;
+        parameters.max_inflight = self.max_inflight;
+        parameters.buffer_pool_size = self.buffer_pool_size;
+        parameters.strict_correctness_checks = self.strict_correctness_checks;
+        parameters.bind_chunk_index = self.bind_chunk_index;
+        parameters.verification_seed = self.verification_seed;
+        parameters
     }
 
     /// Returns the length of the serialized accumulator depending on if it's compressed or not
@@ -252,6 +412,13 @@ impl<E: PairingEngine> Phase1Parameters<E> {
         }
     }
 
+    /// Returns the length of a serialized response, i.e. the accumulator followed by the
+    /// public key of its contributor, depending on if it's compressed or not. The public key
+    /// is always compressed.
+    pub fn get_response_length(&self, compressed: UseCompression) -> usize {
+        self.get_length(compressed) + self.public_key_size
+    }
+
     fn chunk_sizes(
         contribution_mode: ContributionMode,
         chunk_index: usize,
@@ -320,4 +487,122 @@ mod tests {
         curve_parameters_test::<Bls12_381>(96, 192, 48, 96);
         curve_parameters_test::<BW6_761>(192, 192, 96, 96);
     }
+
+    #[test]
+    fn test_zero_chunk_size_is_rejected() {
+        let new = |contribution_mode, chunk_size, batch_size| {
+            Phase1Parameters::<Bls12_377>::try_new(
+                contribution_mode,
+                0,
+                chunk_size,
+                CurveParameters::new(),
+                ProvingSystem::Groth16,
+                2,
+                batch_size,
+            )
+        };
+        assert!(matches!(
+            new(ContributionMode::Chunked, 0, 2),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            new(ContributionMode::Chunked, 2, 0),
+            Err(Error::InvalidParameters(_))
+        ));
+        // Full mode ignores the chunk size
+        assert!(new(ContributionMode::Full, 0, 2).is_ok());
+        assert!(new(ContributionMode::Chunked, 2, 2).is_ok());
+    }
+
+    #[test]
+    fn test_parameter_sizes_from_sizes() {
+        assert_eq!(
+            CurveParameters::<Bls12_377>::from_sizes(96, 192, 48, 96),
+            CurveParameters::<Bls12_377>::new()
+        );
+
+        // 1 G1 and 1 G2 element of 1 byte each, plus the hash and the public key
+        let curve = CurveParameters::<Bls12_377>::from_sizes(1, 1, 1, 1);
+        let parameters = Phase1Parameters::new(ContributionMode::Full, 0, 0, curve, ProvingSystem::Groth16, 1, 2);
+        assert_eq!(parameters.public_key_size, 9);
+        // 3 tau_g1, 2 * (tau_g2, alpha_g1, beta_g1), beta_g2 and the hash
+        assert_eq!(parameters.accumulator_size, 3 + 2 * 3 + 1 + 64);
+        assert_eq!(parameters.contribution_size, parameters.accumulator_size + 9);
+    }
+
+    #[test]
+    fn test_lengths_with_and_without_public_key() {
+        let curve = CurveParameters::<Bls12_377>::from_sizes(2, 3, 1, 2);
+        let full = Phase1Parameters::new(ContributionMode::Full, 0, 0, curve, ProvingSystem::Groth16, 2, 4);
+        // (tau, alpha, beta) in G2 and 3 pairs in G1, all compressed
+        assert_eq!(full.public_key_size, 3 * 2 + 6);
+
+        let lengths = |parameters: &Phase1Parameters<Bls12_377>| {
+            (
+                parameters.get_length(UseCompression::No),
+                parameters.get_length(UseCompression::Yes),
+                parameters.get_response_length(UseCompression::No),
+                parameters.get_response_length(UseCompression::Yes),
+            )
+        };
+        // 7 tau_g1, 4 * (tau_g2, alpha_g1, beta_g1), beta_g2 and the hash
+        assert_eq!(
+            lengths(&full),
+            (7 * 2 + 4 * 7 + 3 + 64, 7 + 4 * 4 + 2 + 64, 109 + 12, 89 + 12)
+        );
+        assert_eq!(full.contribution_size, full.get_response_length(UseCompression::Yes));
+
+        // The first chunk holds 4 of each power, the second only the 3 remaining tau_g1 powers
+        let chunk_0 = full.into_chunk_parameters(ContributionMode::Chunked, 0, 4);
+        assert_eq!(
+            lengths(&chunk_0),
+            (4 * 2 + 4 * 7 + 3 + 64, 4 + 4 * 4 + 2 + 64, 103 + 12, 86 + 12)
+        );
+        let chunk_1 = full.into_chunk_parameters(ContributionMode::Chunked, 1, 4);
+        assert_eq!(lengths(&chunk_1), (3 * 2 + 3 + 64, 3 + 2 + 64, 73 + 12, 69 + 12));
+        for parameters in &[chunk_0, chunk_1] {
+            assert_eq!(
+                parameters.contribution_size,
+                parameters.get_response_length(UseCompression::Yes)
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_element_counts() {
+        let curve = CurveParameters::<Bls12_377>::from_sizes(2, 3, 1, 2);
+        let full = Phase1Parameters::new(ContributionMode::Full, 0, 0, curve, ProvingSystem::Groth16, 2, 4);
+        assert_eq!(full.single_element_counts, SingleElementCounts { g1: 0, g2: 1 });
+
+        assert_eq!(
+            full.with_single_element_counts(SingleElementCounts::default()).unwrap(),
+            full
+        );
+
+        // Other counts are rejected, as their single elements would not be verified
+        let counts = SingleElementCounts { g1: 2, g2: 3 };
+        assert!(matches!(
+            full.with_single_element_counts(counts),
+            Err(Error::Unsupported(_))
+        ));
+
+        // but the sizes already account for them, with 2 single elements in G1 and 3 in G2
+        let parameters = Phase1Parameters::new_with_single_element_counts(
+            ContributionMode::Full,
+            0,
+            0,
+            full.curve.clone(),
+            ProvingSystem::Groth16,
+            2,
+            4,
+            counts,
+        );
+        assert_eq!(parameters.accumulator_size, full.accumulator_size - 3 + 2 * 2 + 3 * 3);
+        assert_eq!(parameters.contribution_size, full.contribution_size - 2 + 2 + 3 * 2);
+
+        // The counts are kept for the chunks, which all hold every single element
+        let chunk_1 = parameters.into_chunk_parameters(ContributionMode::Chunked, 1, 4);
+        assert_eq!(chunk_1.single_element_counts, counts);
+        assert_eq!(chunk_1.get_length(UseCompression::No), 3 * 2 + 2 * 2 + 3 * 3 + 64);
+    }
 }
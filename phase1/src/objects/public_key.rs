@@ -1,7 +1,7 @@
-use crate::Phase1Parameters;
-use setup_utils::{Error, UseCompression};
+use crate::{helpers::elements_are_in_prime_order_subgroup, Phase1Parameters};
+use setup_utils::{Error, SubgroupCheckMode, UseCompression};
 
-use zexe_algebra::{CanonicalDeserialize, CanonicalSerialize, PairingEngine, SerializationError};
+use zexe_algebra::{CanonicalDeserialize, CanonicalSerialize, PairingEngine, SerializationError, Zero};
 
 use std::io::{Read, Write};
 
@@ -67,4 +67,73 @@ impl<E: PairingEngine> PublicKey<E> {
         // The public key is written after the provided position
         Ok(PublicKey::deserialize(&mut &input_map[position..])?)
     }
+
+    /// Checks that no point of the key is the identity and that every point is in the prime
+    /// order subgroup, as is required of a key read from an untrusted response
+    pub fn validate(&self) -> Result<(), Error> {
+        let g1 = [
+            self.tau_g1.0,
+            self.tau_g1.1,
+            self.alpha_g1.0,
+            self.alpha_g1.1,
+            self.beta_g1.0,
+            self.beta_g1.1,
+        ];
+        let g2 = [self.tau_g2, self.alpha_g2, self.beta_g2];
+        if g1.iter().any(|p| p.is_zero()) || g2.iter().any(|p| p.is_zero()) {
+            return Err(Error::PointAtInfinity);
+        }
+        if !elements_are_in_prime_order_subgroup(&g1, SubgroupCheckMode::Direct)
+            || !elements_are_in_prime_order_subgroup(&g2, SubgroupCheckMode::Direct)
+        {
+            return Err(Error::IncorrectSubgroup);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the names of the secrets which are 1, and so leave the elements they multiply
+    /// unchanged, which is the case when both points of their G1 pair are equal. Such a
+    /// contribution passes verification, but adds no randomness to the accumulator.
+    pub fn identity_secrets(&self) -> Vec<&'static str> {
+        [("tau", self.tau_g1), ("alpha", self.alpha_g1), ("beta", self.beta_g1)]
+            .iter()
+            .filter(|(_, (s, s_x))| s == s_x)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Phase1;
+
+    use zexe_algebra::{AffineCurve, Bls12_377, One, ProjectiveCurve};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_public_key_validate() {
+        let (mut public_key, _) = Phase1::<Bls12_377>::key_generation(&mut thread_rng(), &[0; 64]).unwrap();
+        public_key.validate().unwrap();
+
+        public_key.alpha_g2 = <Bls12_377 as PairingEngine>::G2Affine::zero();
+        match public_key.validate() {
+            Err(Error::PointAtInfinity) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_public_key_identity_secrets() {
+        let (mut public_key, _) = Phase1::<Bls12_377>::key_generation(&mut thread_rng(), &[0; 64]).unwrap();
+        assert!(public_key.identity_secrets().is_empty());
+
+        // A contribution whose tau and beta are 1 leaves their pairs unchanged
+        let one = <Bls12_377 as PairingEngine>::Fr::one();
+        public_key.tau_g1.1 = public_key.tau_g1.0.mul(one).into_affine();
+        public_key.beta_g1.1 = public_key.beta_g1.0.mul(one).into_affine();
+        assert_eq!(public_key.identity_secrets(), vec!["tau", "beta"]);
+    }
 }
@@ -49,6 +49,50 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
         accumulator::decompress(input, output, check_input_for_correctness, parameters)?;
         Ok(())
     }
+
+    #[cfg(not(feature = "wasm"))]
+    pub fn maybe_decompress(
+        input: (&[u8], UseCompression),
+        output: (&mut [u8], UseCompression),
+        check_input_for_correctness: CheckForCorrectness,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<()> {
+        accumulator::maybe_decompress(input, output, check_input_for_correctness, parameters)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    pub fn for_each_element(
+        input: &[u8],
+        compression: UseCompression,
+        parameters: &'a Phase1Parameters<E>,
+        action: impl FnMut(ElementType, usize, &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        accumulator::for_each_element(input, compression, parameters, action)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    pub fn element<'b>(
+        input: &'b [u8],
+        compression: UseCompression,
+        parameters: &'a Phase1Parameters<E>,
+        element_type: ElementType,
+        index: usize,
+    ) -> Result<&'b [u8]> {
+        accumulator::element(input, compression, parameters, element_type, index)
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    pub fn verify_compression(
+        compressed: &[u8],
+        uncompressed: &[u8],
+        check_input_for_correctness: CheckForCorrectness,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<()> {
+        accumulator::verify_compression(compressed, uncompressed, check_input_for_correctness, parameters)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +132,139 @@ mod tests {
         }
     }
 
+    fn maybe_decompress_curve_test<E: PairingEngine>() {
+        let parameters = Phase1Parameters::<E>::new_full(ProvingSystem::Groth16, 2, 2);
+        for compression in &[UseCompression::Yes, UseCompression::No] {
+            // matching compressions are a plain copy
+            let (input, before) = generate_random_accumulator(&parameters, *compression);
+            let mut output = generate_output(&parameters, *compression);
+            Phase1::maybe_decompress(
+                (&input, *compression),
+                (&mut output, *compression),
+                CheckForCorrectness::No,
+                &parameters,
+            )
+            .unwrap();
+            assert_eq!(input, output);
+            let deserialized =
+                Phase1::deserialize(&output, *compression, CheckForCorrectness::No, &parameters).unwrap();
+            assert_eq!(deserialized, before);
+        }
+
+        // compressing an uncompressed input is not supported
+        let (input, _) = generate_random_accumulator(&parameters, UseCompression::No);
+        let mut output = generate_output(&parameters, UseCompression::Yes);
+        Phase1::maybe_decompress(
+            (&input, UseCompression::No),
+            (&mut output, UseCompression::Yes),
+            CheckForCorrectness::No,
+            &parameters,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_maybe_decompress_bls12_377() {
+        maybe_decompress_curve_test::<Bls12_377>();
+    }
+
+    fn verify_compression_curve_test<E: PairingEngine>() {
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let parameters = Phase1Parameters::<E>::new_full(*proving_system, 2, 2);
+            let (compressed, before) = generate_random_accumulator(&parameters, UseCompression::Yes);
+            let mut uncompressed = generate_output(&parameters, UseCompression::No);
+            before
+                .serialize(&mut uncompressed, UseCompression::No, &parameters)
+                .unwrap();
+
+            Phase1::verify_compression(&compressed, &uncompressed, CheckForCorrectness::No, &parameters).unwrap();
+
+            // corrupt the second tau_g1 element
+            let g1_size = buffer_size::<E::G1Affine>(UseCompression::No);
+            let other = generate_random_accumulator(&parameters, UseCompression::No).0;
+            let offset = parameters.hash_size + g1_size;
+            uncompressed[offset..offset + g1_size].copy_from_slice(&other[offset..offset + g1_size]);
+            match Phase1::verify_compression(&compressed, &uncompressed, CheckForCorrectness::No, &parameters) {
+                Err(Error::CompressionMismatch(ElementType::TauG1, 1)) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_compression_bls12_377() {
+        verify_compression_curve_test::<Bls12_377>();
+    }
+
+    fn for_each_element_curve_test<E: PairingEngine>() {
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let parameters = Phase1Parameters::<E>::new_full(*proving_system, 2, 2);
+            let (buffer, before) = generate_random_accumulator(&parameters, UseCompression::Yes);
+
+            let mut tau_g1 = vec![];
+            let mut num_elements = 0;
+            Phase1::for_each_element(
+                &buffer,
+                UseCompression::Yes,
+                &parameters,
+                |element_type, index, mut element| {
+                    if element_type == ElementType::TauG1 {
+                        assert_eq!(index, tau_g1.len());
+                        tau_g1.push(element.read_element::<E::G1Affine>(UseCompression::Yes, CheckForCorrectness::No)?);
+                    }
+                    num_elements += 1;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            assert_eq!(tau_g1, before.tau_powers_g1);
+            let expected_num_elements = match proving_system {
+                ProvingSystem::Groth16 => parameters.powers_g1_length + 3 * parameters.powers_length + 1,
+                ProvingSystem::Marlin => {
+                    before.tau_powers_g1.len() + before.tau_powers_g2.len() + before.alpha_tau_powers_g1.len()
+                }
+            };
+            assert_eq!(num_elements, expected_num_elements);
+        }
+    }
+
+    #[test]
+    fn test_for_each_element_bls12_377() {
+        for_each_element_curve_test::<Bls12_377>();
+    }
+
+    fn element_curve_test<E: PairingEngine>() {
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let parameters = Phase1Parameters::<E>::new_full(*proving_system, 2, 2);
+            let (buffer, before) = generate_random_accumulator(&parameters, UseCompression::Yes);
+
+            for (index, expected) in before.tau_powers_g1.iter().enumerate() {
+                let mut element =
+                    Phase1::element(&buffer, UseCompression::Yes, &parameters, ElementType::TauG1, index).unwrap();
+                let element = element.read_element::<E::G1Affine>(UseCompression::Yes, CheckForCorrectness::No);
+                assert_eq!(element.unwrap(), *expected);
+            }
+            let index = before.tau_powers_g2.len() - 1;
+            let mut element =
+                Phase1::element(&buffer, UseCompression::Yes, &parameters, ElementType::TauG2, index).unwrap();
+            let element = element.read_element::<E::G2Affine>(UseCompression::Yes, CheckForCorrectness::No);
+            assert_eq!(element.unwrap(), before.tau_powers_g2[index]);
+
+            // the index must be within the section
+            let index = before.tau_powers_g1.len();
+            assert!(Phase1::element(&buffer, UseCompression::Yes, &parameters, ElementType::TauG1, index).is_err());
+            if *proving_system == ProvingSystem::Marlin {
+                assert!(Phase1::element(&buffer, UseCompression::Yes, &parameters, ElementType::BetaG1, 0).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_element_bls12_377() {
+        element_curve_test::<Bls12_377>();
+    }
+
     #[test]
     fn test_serialization_bls12_377() {
         serialize_curve_test::<Bls12_377>(UseCompression::Yes, 2, 2);
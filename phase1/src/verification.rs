@@ -1,4 +1,330 @@
 use super::*;
+use crate::helpers::{pool::BufferPool, validation_cache::ValidationCache};
+
+use rayon::prelude::*;
+use std::{
+    fmt,
+    ops::{Deref, Range},
+    sync::{mpsc::Sender, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+/// The outcome of verifying a batch of a chunk of the accumulator, or the whole chunk,
+/// which is sent to a coordinator as soon as it completes, whether it passed or not
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkResult {
+    /// The index of the chunk which was verified
+    pub chunk_index: usize,
+    /// The index of the first element of the batch or chunk
+    pub start: usize,
+    /// The index after the last element of the batch or chunk
+    pub end: usize,
+    /// Whether the batch or chunk was verified successfully
+    pub success: bool,
+    /// How long the batch or chunk took to verify
+    pub duration: Duration,
+}
+
+/// How long a single batch of the chunk took to verify
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchTiming {
+    /// The index of the first element of the batch
+    pub start: usize,
+    /// The index after the last element of the batch
+    pub end: usize,
+    /// How long the batch took to verify
+    pub duration: Duration,
+}
+
+/// A summary of a successful verification, so that embedders can log or display what was
+/// checked rather than only that the verification passed
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationReport {
+    /// The number of batches of the chunk which were verified, each of up to `batch_size` elements
+    pub batches_checked: usize,
+    /// The number of elements of the verified sections, excluding beta_g2
+    pub elements_checked: usize,
+    /// The sections which were verified, which excludes the sections the chunk has no elements of
+    pub sections_verified: SectionMask,
+    /// How long the verification took
+    pub duration: Duration,
+}
+
+impl VerificationReport {
+    fn new<E: PairingEngine>(
+        batches_checked: usize,
+        sections: SectionMask,
+        started: Instant,
+        parameters: &Phase1Parameters<E>,
+    ) -> Self {
+        // The lengths of each section in the chunk, following the layout of `split`
+        let (tau_g2_length, alpha_g1_length, beta_g1_length) = match parameters.proving_system {
+            ProvingSystem::Groth16 => (
+                parameters.other_chunk_size,
+                parameters.other_chunk_size,
+                parameters.other_chunk_size,
+            ),
+            ProvingSystem::Marlin if parameters.chunk_index == 0 => (
+                parameters.total_size_in_log2 + 2,
+                3 + 3 * parameters.total_size_in_log2,
+                0,
+            ),
+            ProvingSystem::Marlin => (0, 0, 0),
+        };
+        let sections_verified = SectionMask {
+            tau_g1: sections.tau_g1 && parameters.g1_chunk_size > 0,
+            tau_g2: sections.tau_g2 && tau_g2_length > 0,
+            alpha_g1: sections.alpha_g1 && alpha_g1_length > 0,
+            beta_g1: sections.beta_g1 && beta_g1_length > 0,
+        };
+        let elements_checked = [
+            (sections_verified.tau_g1, parameters.g1_chunk_size),
+            (sections_verified.tau_g2, tau_g2_length),
+            (sections_verified.alpha_g1, alpha_g1_length),
+            (sections_verified.beta_g1, beta_g1_length),
+        ]
+        .iter()
+        .filter(|(verified, _)| *verified)
+        .map(|(_, length)| length)
+        .sum();
+
+        Self {
+            batches_checked,
+            elements_checked,
+            sections_verified,
+            duration: started.elapsed(),
+        }
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "verified {} elements of sections {} in {} batches, in {:?}",
+            self.elements_checked, self.sections_verified, self.batches_checked, self.duration
+        )
+    }
+}
+
+/// Same as `iter_chunk`, except that the outcome of each batch is sent over `progress`
+/// as soon as it completes, the duration of each successful batch is sent over `timings`,
+/// if provided, and the number of batches is returned. A receiver which hung up does
+/// not abort the verification.
+fn iter_chunk_timed<E: PairingEngine>(
+    parameters: &Phase1Parameters<E>,
+    progress: Option<&Sender<ChunkResult>>,
+    timings: Option<&Sender<BatchTiming>>,
+    mut action: impl FnMut(usize, usize) -> Result<()>,
+) -> Result<usize> {
+    let mut batches = 0;
+    iter_chunk(parameters, |start, end| {
+        let now = Instant::now();
+        let result = action(start, end);
+        if let Some(progress) = progress {
+            let _ = progress.send(ChunkResult {
+                chunk_index: parameters.chunk_index,
+                start,
+                end,
+                success: result.is_ok(),
+                duration: now.elapsed(),
+            });
+        }
+        result?;
+        if let Some(timings) = timings {
+            let _ = timings.send(BatchTiming {
+                start,
+                end,
+                duration: now.elapsed(),
+            });
+        }
+        batches += 1;
+        Ok(())
+    })?;
+    Ok(batches)
+}
+
+/// Sends the outcome of verifying the chunk over `progress` if provided. A receiver
+/// which hung up does not abort the verification.
+fn report_chunk(
+    progress: Option<&Sender<ChunkResult>>,
+    started: Instant,
+    success: bool,
+    parameters: &Phase1Parameters<impl PairingEngine>,
+) {
+    if let Some(progress) = progress {
+        let (start, end) = chunk_range(parameters);
+        let _ = progress.send(ChunkResult {
+            chunk_index: parameters.chunk_index,
+            start,
+            end,
+            success,
+            duration: started.elapsed(),
+        });
+    }
+}
+
+/// A batch of elements which failed the prime order subgroup check, but whose
+/// failure was tolerated because a legacy file was being audited
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubgroupWarning {
+    /// The section of the accumulator the elements belong to
+    pub element_type: ElementType,
+    /// The index of the first element of the batch (relative to the chunk)
+    pub start: usize,
+    /// The index after the last element of the batch (relative to the chunk)
+    pub end: usize,
+}
+
+/// The optional hooks of a verification, each of which is enabled by one of the
+/// `verification_with_*` variants
+#[derive(Clone, Copy, Default)]
+struct VerificationHooks<'h> {
+    /// Collects the batches which failed the subgroup check instead of aborting
+    subgroup_warnings: Option<&'h Mutex<Vec<SubgroupWarning>>>,
+    /// Receives the outcome of each batch and of the chunk
+    progress: Option<&'h Sender<ChunkResult>>,
+    /// Receives the duration of each batch
+    timings: Option<&'h Sender<BatchTiming>>,
+    /// Skips the subgroup check of the ranges which already passed it
+    validation_cache: Option<&'h ValidationCache>,
+}
+
+/// Records a subgroup check failure as a warning if warnings are being collected,
+/// otherwise the error is passed through unchanged.
+fn tolerate_subgroup_failure(
+    err: Error,
+    subgroup_warnings: Option<&Mutex<Vec<SubgroupWarning>>>,
+    element_type: ElementType,
+    (start, end): (usize, usize),
+) -> Result<()> {
+    match (err, subgroup_warnings) {
+        (Error::IncorrectSubgroup, Some(warnings)) => {
+            warn!(
+                "{} elements in range [{}, {}) are not in the prime order subgroup",
+                element_type, start, end
+            );
+            warnings
+                .lock()
+                .expect("subgroup warnings lock was poisoned")
+                .push(SubgroupWarning {
+                    element_type,
+                    start,
+                    end,
+                });
+            Ok(())
+        }
+        (err, _) => Err(err),
+    }
+}
+
+/// Same as `check_elements_are_nonzero_and_in_prime_order_subgroup`, except that the subgroup
+/// check is skipped for a range found in the validation cache, and a range which passes it is
+/// added to the cache
+fn check_elements_with_validation_cache<C: AffineCurve, R: Rng>(
+    (buffer, compression): (&[u8], UseCompression),
+    (start, end): (usize, usize),
+    elements: &mut [C],
+    element_type: ElementType,
+    subgroup_check_mode: SubgroupCheckMode,
+    validation_cache: Option<&ValidationCache>,
+    rng: &mut R,
+) -> Result<()> {
+    let validation_cache = match validation_cache {
+        Some(validation_cache) => validation_cache,
+        None => {
+            return check_elements_are_nonzero_and_in_prime_order_subgroup(
+                (buffer, compression),
+                (start, end),
+                elements,
+                subgroup_check_mode,
+                rng,
+            );
+        }
+    };
+
+    let size = buffer_size::<C>(compression);
+    let key = validation_cache.key(
+        element_type,
+        &buffer[start * size..end * size],
+        compression,
+        (start, end),
+    );
+    if validation_cache.contains(&key) {
+        buffer[start * size..end * size].read_batch_preallocated(
+            &mut elements[0..end - start],
+            compression,
+            CheckForCorrectness::OnlyNonZero,
+        )?;
+        return Ok(());
+    }
+    check_elements_are_nonzero_and_in_prime_order_subgroup(
+        (buffer, compression),
+        (start, end),
+        elements,
+        subgroup_check_mode,
+        rng,
+    )?;
+    validation_cache.insert(key);
+    Ok(())
+}
+
+/// Checks the elements of a batch of a section of the response, tolerating subgroup failures
+/// if warnings are being collected, and copies them to the same section of the new challenge
+fn check_and_copy_batch<E: PairingEngine, C: AffineCurve>(
+    (buffer, compressed_output): (&[u8], UseCompression),
+    (new_challenge, compressed_new_challenge): (&mut [u8], UseCompression),
+    (start, end): (usize, usize),
+    elements: &mut [C],
+    element_type: ElementType,
+    (subgroup_check_mode, subgroup_warnings, validation_cache): (
+        SubgroupCheckMode,
+        Option<&Mutex<Vec<SubgroupWarning>>>,
+        Option<&ValidationCache>,
+    ),
+    parameters: &Phase1Parameters<E>,
+) -> Result<()> {
+    check_elements_with_validation_cache::<C, _>(
+        (buffer, compressed_output),
+        (start, end),
+        elements,
+        element_type,
+        subgroup_check_mode,
+        validation_cache,
+        &mut verification_rng(element_type, start, parameters),
+    )
+    .or_else(|e| tolerate_subgroup_failure(e, subgroup_warnings, element_type, (start, end)))?;
+
+    let size = buffer_size::<C>(compressed_new_challenge);
+    new_challenge[start * size..end * size].write_batch(&mut elements[0..end - start], compressed_new_challenge)?;
+
+    trace!("{} verification was successful", element_type);
+
+    Ok(())
+}
+
+/// The byte offsets of the sections of a Groth16 accumulator, following the layout of `split`
+fn section_offsets<E: PairingEngine>(
+    compression: UseCompression,
+    parameters: &Phase1Parameters<E>,
+) -> (usize, usize, usize, usize, usize) {
+    let g1_size = buffer_size::<E::G1Affine>(compression);
+    let g2_size = buffer_size::<E::G2Affine>(compression);
+
+    let tau_g1_offset = parameters.hash_size;
+    let tau_g2_offset = tau_g1_offset + g1_size * parameters.g1_chunk_size;
+    let alpha_g1_offset = tau_g2_offset + g2_size * parameters.other_chunk_size;
+    let beta_g1_offset = alpha_g1_offset + g1_size * parameters.other_chunk_size;
+    let beta_g2_offset = beta_g1_offset + g1_size * parameters.other_chunk_size;
+    (
+        tau_g1_offset,
+        tau_g2_offset,
+        alpha_g1_offset,
+        beta_g1_offset,
+        beta_g2_offset,
+    )
+}
 
 impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
     /// Verifies that the accumulator was transformed correctly
@@ -20,6 +346,8 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
     /// that they're in the prime order subgroup. In the first chunk, it also checks
     /// the proofs of knowledge and that the elements were correctly multiplied.
     ///
+    /// Returns a summary of what was verified.
+    ///
     #[allow(clippy::too_many_arguments, clippy::cognitive_complexity)]
     pub fn verification(
         input: &[u8],
@@ -34,9 +362,254 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
         check_output_for_correctness: CheckForCorrectness,
         subgroup_check_mode: SubgroupCheckMode,
         parameters: &'a Phase1Parameters<E>,
-    ) -> Result<()> {
+    ) -> Result<VerificationReport> {
+        Self::verification_with_subgroup_policy(
+            input,
+            output,
+            new_challenge,
+            key,
+            digest,
+            compressed_input,
+            compressed_output,
+            compressed_new_challenge,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            subgroup_check_mode,
+            VerificationHooks::default(),
+            parameters,
+        )
+    }
+
+    ///
+    /// Phase 1 - Streaming Verification
+    ///
+    /// Same as `verification`, except that the outcome of each batch is sent over
+    /// `progress` as soon as it completes, followed by the outcome of the whole chunk,
+    /// including when it fails, so that a coordinator can track the verification live.
+    /// The overall result is still returned at the end.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn verification_with_progress(
+        input: &[u8],
+        output: &[u8],
+        new_challenge: &mut [u8],
+        key: &PublicKey<E>,
+        digest: &[u8],
+        compressed_input: UseCompression,
+        compressed_output: UseCompression,
+        compressed_new_challenge: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        subgroup_check_mode: SubgroupCheckMode,
+        progress: &Sender<ChunkResult>,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        Self::verification_with_subgroup_policy(
+            input,
+            output,
+            new_challenge,
+            key,
+            digest,
+            compressed_input,
+            compressed_output,
+            compressed_new_challenge,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            subgroup_check_mode,
+            VerificationHooks {
+                progress: Some(progress),
+                ..Default::default()
+            },
+            parameters,
+        )
+    }
+
+    ///
+    /// Phase 1 - Timed Verification
+    ///
+    /// Same as `verification`, except that the duration of each batch is sent over
+    /// `timings` as soon as it completes, e.g. to find out which batches are slow.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn verification_with_timings(
+        input: &[u8],
+        output: &[u8],
+        new_challenge: &mut [u8],
+        key: &PublicKey<E>,
+        digest: &[u8],
+        compressed_input: UseCompression,
+        compressed_output: UseCompression,
+        compressed_new_challenge: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        subgroup_check_mode: SubgroupCheckMode,
+        timings: &Sender<BatchTiming>,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        Self::verification_with_subgroup_policy(
+            input,
+            output,
+            new_challenge,
+            key,
+            digest,
+            compressed_input,
+            compressed_output,
+            compressed_new_challenge,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            subgroup_check_mode,
+            VerificationHooks {
+                timings: Some(timings),
+                ..Default::default()
+            },
+            parameters,
+        )
+    }
+
+    ///
+    /// Phase 1 - Legacy Verification
+    ///
+    /// Same as `verification`, except that elements which are not in the prime order
+    /// subgroup do not abort the verification. Instead, they are logged and returned
+    /// as warnings. This is NOT a full-security verification and must only be used for
+    /// auditing legacy ceremony files which predate the subgroup checks.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn verification_tolerating_subgroup_failures(
+        input: &[u8],
+        output: &[u8],
+        new_challenge: &mut [u8],
+        key: &PublicKey<E>,
+        digest: &[u8],
+        compressed_input: UseCompression,
+        compressed_output: UseCompression,
+        compressed_new_challenge: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        subgroup_check_mode: SubgroupCheckMode,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<Vec<SubgroupWarning>> {
+        let warnings = Mutex::new(vec![]);
+        Self::verification_with_subgroup_policy(
+            input,
+            output,
+            new_challenge,
+            key,
+            digest,
+            compressed_input,
+            compressed_output,
+            compressed_new_challenge,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            subgroup_check_mode,
+            VerificationHooks {
+                subgroup_warnings: Some(&warnings),
+                ..Default::default()
+            },
+            parameters,
+        )?;
+        Ok(warnings.into_inner().expect("subgroup warnings lock was poisoned"))
+    }
+
+    ///
+    /// Phase 1 - Cached Verification
+    ///
+    /// Same as `verification`, except that the ranges of elements found in `validation_cache`
+    /// skip the prime order subgroup check, and the ranges which pass it are added to the cache.
+    /// The cache is keyed by the exact bytes of each range, so this is as secure as `verification`
+    /// as long as the cache file is trusted.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn verification_with_validation_cache(
+        input: &[u8],
+        output: &[u8],
+        new_challenge: &mut [u8],
+        key: &PublicKey<E>,
+        digest: &[u8],
+        compressed_input: UseCompression,
+        compressed_output: UseCompression,
+        compressed_new_challenge: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        subgroup_check_mode: SubgroupCheckMode,
+        validation_cache: &ValidationCache,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        Self::verification_with_subgroup_policy(
+            input,
+            output,
+            new_challenge,
+            key,
+            digest,
+            compressed_input,
+            compressed_output,
+            compressed_new_challenge,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            subgroup_check_mode,
+            VerificationHooks {
+                validation_cache: Some(validation_cache),
+                ..Default::default()
+            },
+            parameters,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verification_with_subgroup_policy(
+        input: &[u8],
+        output: &[u8],
+        new_challenge: &mut [u8],
+        key: &PublicKey<E>,
+        digest: &[u8],
+        compressed_input: UseCompression,
+        compressed_output: UseCompression,
+        compressed_new_challenge: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        subgroup_check_mode: SubgroupCheckMode,
+        hooks: VerificationHooks,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        let started = Instant::now();
+        let result = Self::verify_chunk(
+            input,
+            output,
+            new_challenge,
+            key,
+            digest,
+            compressed_input,
+            compressed_output,
+            compressed_new_challenge,
+            check_input_for_correctness,
+            check_output_for_correctness,
+            subgroup_check_mode,
+            hooks,
+            parameters,
+        );
+        report_chunk(hooks.progress, started, result.is_ok(), parameters);
+        result
+    }
+
+    #[allow(clippy::too_many_arguments, clippy::cognitive_complexity)]
+    fn verify_chunk(
+        input: &[u8],
+        output: &[u8],
+        new_challenge: &mut [u8],
+        key: &PublicKey<E>,
+        digest: &[u8],
+        compressed_input: UseCompression,
+        compressed_output: UseCompression,
+        compressed_new_challenge: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        check_output_for_correctness: CheckForCorrectness,
+        subgroup_check_mode: SubgroupCheckMode,
+        hooks: VerificationHooks,
+        parameters: &'a Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
         let span = info_span!("phase1-verification");
         let _ = span.enter();
+        let started = Instant::now();
 
         info!("starting...");
 
@@ -50,141 +623,38 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
             new_challenge_beta_g2,
         ) = split_mut(new_challenge, parameters, compressed_new_challenge);
 
+        // The proofs of knowledge may be bound to the chunk the response was produced for
+        let digest = &bind_digest_to_chunk(digest, parameters);
         if parameters.contribution_mode == ContributionMode::Full || parameters.chunk_index == 0 {
             // Run proof of knowledge checks if contribution mode is on full, or this is the first chunk index.
-            // Split the input buffer into its components.
-            let (in_tau_g1, in_tau_g2, in_alpha_g1, in_beta_g1, in_beta_g2) =
-                split(input, parameters, compressed_input);
-
-            let [tau_g2_s, alpha_g2_s, beta_g2_s] = compute_g2_s_key(&key, &digest)?;
-
-            // Compose into tuple form for convenience.
-            let tau_single_g1_check = &(key.tau_g1.0, key.tau_g1.1);
-            let tau_single_g2_check = &(tau_g2_s, key.tau_g2);
-            // let alpha_single_g1_check = &(key.alpha_g1.0, key.alpha_g1.1);
-            let alpha_single_g2_check = &(alpha_g2_s, key.alpha_g2);
-            let beta_single_g1_check = &(key.beta_g1.0, key.beta_g1.1);
-            let beta_single_g2_check = &(beta_g2_s, key.beta_g2);
-
-            // Ensure the key ratios are correctly produced.
-            {
-                // Check the proofs of knowledge for tau, alpha, and beta.
-                let check_ratios = &[
-                    (&(key.tau_g1.0, key.tau_g1.1), &(tau_g2_s, key.tau_g2), "Tau G1<>G2"),
-                    (
-                        &(key.alpha_g1.0, key.alpha_g1.1),
-                        &(alpha_g2_s, key.alpha_g2),
-                        "Alpha G1<>G2",
-                    ),
-                    (
-                        &(key.beta_g1.0, key.beta_g1.1),
-                        &(beta_g2_s, key.beta_g2),
-                        "Beta G1<>G2",
-                    ),
-                ];
-
-                for (a, b, err) in check_ratios {
-                    check_same_ratio::<E>(a, b, err)?;
-                }
-                debug!("key ratios were correctly produced");
-            }
-
-            // Ensure that the initial conditions are correctly formed (first 2 elements).
-            // We allocate a G1 vector of length 2 and re-use it for our G1 elements.
-            // We keep the values of the tau_g1 / tau_g2 elements for later use.
-
-            // Check that tau^i was computed correctly in G1.
-            let (mut before_g1, mut after_g1) = {
-                // Previous iteration of tau_g1[0].
-                let before_g1 =
-                    read_initial_elements::<E::G1Affine>(in_tau_g1, compressed_input, check_input_for_correctness)?;
-                // Current iteration of tau_g1[0].
-                let after_g1 =
-                    read_initial_elements::<E::G1Affine>(tau_g1, compressed_output, check_output_for_correctness)?;
-
-                // Check tau_g1[0] is the prime subgroup generator.
-                if after_g1[0] != E::G1Affine::prime_subgroup_generator() {
-                    return Err(VerificationError::InvalidGenerator(ElementType::TauG1).into());
-                }
-
-                // Check that tau^1 was multiplied correctly.
-                check_same_ratio::<E>(
-                    &(before_g1[1], after_g1[1]),
-                    tau_single_g2_check,
-                    "Before-After: tau_g1",
-                )?;
-
-                (before_g1, after_g1)
-            };
-
-            // Check that tau^i was computed correctly in G2.
-            {
-                // Previous iteration of tau_g2[0].
-                let before_g2 =
-                    read_initial_elements::<E::G2Affine>(in_tau_g2, compressed_input, check_input_for_correctness)?;
-                // Current iteration of tau_g2[0].
-                let after_g2 =
-                    read_initial_elements::<E::G2Affine>(tau_g2, compressed_output, check_output_for_correctness)?;
-
-                // Check tau_g2[0] is the prime subgroup generator.
-                if after_g2[0] != E::G2Affine::prime_subgroup_generator() {
-                    return Err(VerificationError::InvalidGenerator(ElementType::TauG2).into());
-                }
-
-                // Check that tau^1 was multiplied correctly.
-                check_same_ratio::<E>(
-                    tau_single_g1_check,
-                    &(before_g2[1], after_g2[1]),
-                    "Before-After: tau_g2",
-                )?;
-            }
-
-            // Check that alpha_g1[0] and beta_g1[0] were computed correctly.
-            {
-                // Determine the check based on the proof system's requirements.
-                let checks = match parameters.proving_system {
-                    ProvingSystem::Groth16 => vec![
-                        (in_alpha_g1, alpha_g1, alpha_single_g2_check),
-                        (in_beta_g1, beta_g1, beta_single_g2_check),
-                    ],
-                    ProvingSystem::Marlin => vec![(in_alpha_g1, alpha_g1, alpha_single_g2_check)],
-                };
-
-                // Check that alpha_g1[0] and beta_g1[0] was multiplied correctly.
-                for (before, after, check) in &checks {
-                    before.read_batch_preallocated(&mut before_g1, compressed_input, check_input_for_correctness)?;
-                    after.read_batch_preallocated(&mut after_g1, compressed_output, check_output_for_correctness)?;
-                    check_same_ratio::<E>(
-                        &(before_g1[0], after_g1[0]),
-                        check,
-                        "Before-After: alpha_g1[0] / beta_g1[0]",
-                    )?;
-                }
-            }
-
-            // Check that beta_g2[0] was computed correctly.
-            {
-                if parameters.proving_system == ProvingSystem::Groth16 {
-                    // Read in the first beta_g2 element from the previous iteration and current iteration.
-                    let before_beta_g2 =
-                        (&*in_beta_g2).read_element::<E::G2Affine>(compressed_input, check_input_for_correctness)?;
-                    let after_beta_g2 =
-                        (&*beta_g2).read_element::<E::G2Affine>(compressed_output, check_output_for_correctness)?;
-                    new_challenge_beta_g2.write_element(&after_beta_g2, compressed_new_challenge)?;
-
-                    // Check that beta_g2[0] was multiplied correctly.
-                    check_same_ratio::<E>(
-                        beta_single_g1_check,
-                        &(before_beta_g2, after_beta_g2),
-                        "Before-After: beta_g2[0]",
-                    )?;
-                }
+            verify_initial_elements(
+                (input, compressed_input, check_input_for_correctness),
+                (output, compressed_output, check_output_for_correctness),
+                key,
+                digest,
+                parameters,
+            )?;
+
+            // Carry beta_g2 over to the new challenge.
+            if parameters.proving_system == ProvingSystem::Groth16 {
+                let after_beta_g2 =
+                    (&*beta_g2).read_element::<E::G2Affine>(compressed_output, check_output_for_correctness)?;
+                new_challenge_beta_g2.write_element(&after_beta_g2, compressed_new_challenge)?;
             }
+        } else if parameters.bind_chunk_index {
+            // Otherwise only the proofs of knowledge are checked, when they bind the response to its chunk.
+            verify_proofs_of_knowledge(key, digest)?;
         };
 
         debug!("initial elements were computed correctly");
 
-        iter_chunk(&parameters, |start, end| {
+        // The batches are read into recycled buffers
+        let g1_pool = BufferPool::<E::G1Affine>::new(parameters.buffer_pool_size);
+        let g2_pool = BufferPool::<E::G2Affine>::new(parameters.buffer_pool_size);
+
+        let subgroup_policy = (subgroup_check_mode, hooks.subgroup_warnings, hooks.validation_cache);
+
+        let batches_checked = iter_chunk_timed(&parameters, hooks.progress, hooks.timings, |start, end| {
             // Preallocate 2 vectors per batch.
             // Ensure that the pairs are created correctly (we do this in chunks!).
             // Load `batch_size` chunks on each iteration and perform the transformation.
@@ -203,6 +673,10 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                 ContributionMode::Full => (start, end),
             };
 
+            // The outcome of each section, which are verified concurrently
+            let (mut tau_g1_result, mut tau_g2_result, mut alpha_g1_result, mut beta_g1_result) =
+                (Ok(()), Ok(()), Ok(()), Ok(()));
+
             match parameters.proving_system {
                 ProvingSystem::Groth16 => {
                     rayon::scope(|t| {
@@ -212,22 +686,16 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         t.spawn(|_| {
                             let _enter = span.enter();
 
-                            let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
-                            check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine>(
+                            let mut g1 = g1_pool.take(parameters.batch_size);
+                            tau_g1_result = check_and_copy_batch(
                                 (tau_g1, compressed_output),
+                                (new_challenge_tau_g1, compressed_new_challenge),
                                 (start_chunk, end_chunk),
                                 &mut g1,
-                                subgroup_check_mode,
-                            )
-                            .expect("could not check element are non zero and in prime order subgroup");
-
-                            let size = buffer_size::<E::G1Affine>(compressed_new_challenge);
-                            new_challenge_tau_g1[start_chunk * size..end_chunk * size]
-                                .write_batch(&mut g1[0..end_chunk - start_chunk], compressed_new_challenge)
-                                .expect("Should have written tau_g1 to new challenge");
-
-                            trace!("tau_g1 verification was successful");
+                                ElementType::TauG1,
+                                subgroup_policy,
+                                parameters,
+                            );
                         });
 
                         if start < parameters.powers_length {
@@ -259,66 +727,48 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                 t.spawn(|_| {
                                     let _enter = span.enter();
 
-                                    let mut g2 = vec![E::G2Affine::zero(); parameters.batch_size];
-
-                                    check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G2Affine>(
+                                    let mut g2 = g2_pool.take(parameters.batch_size);
+                                    tau_g2_result = check_and_copy_batch(
                                         (tau_g2, compressed_output),
+                                        (new_challenge_tau_g2, compressed_new_challenge),
                                         (start_chunk, end_chunk),
                                         &mut g2,
-                                        subgroup_check_mode,
-                                    )
-                                    .expect("could not check element are non zero and in prime order subgroup");
-
-                                    let size = buffer_size::<E::G2Affine>(compressed_new_challenge);
-                                    new_challenge_tau_g2[start_chunk * size..end_chunk * size]
-                                        .write_batch(&mut g2[0..end_chunk - start_chunk], compressed_new_challenge)
-                                        .expect("Should have written tau_g2 to new challenge");
-
-                                    trace!("tau_g2 verification was successful");
+                                        ElementType::TauG2,
+                                        subgroup_policy,
+                                        parameters,
+                                    );
                                 });
 
                                 // Process alpha_g1 elements.
                                 t.spawn(|_| {
                                     let _enter = span.enter();
 
-                                    let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
-                                    check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine>(
+                                    let mut g1 = g1_pool.take(parameters.batch_size);
+                                    alpha_g1_result = check_and_copy_batch(
                                         (alpha_g1, compressed_output),
+                                        (new_challenge_alpha_g1, compressed_new_challenge),
                                         (start_chunk, end_chunk),
                                         &mut g1,
-                                        subgroup_check_mode,
-                                    )
-                                    .expect("could not check element are non zero and in prime order subgroup");
-
-                                    let size = buffer_size::<E::G1Affine>(compressed_new_challenge);
-                                    new_challenge_alpha_g1[start_chunk * size..end_chunk * size]
-                                        .write_batch(&mut g1[0..end_chunk - start_chunk], compressed_new_challenge)
-                                        .expect("Should have written alpha_g1 to new challenge");
-
-                                    trace!("alpha_g1 verification was successful");
+                                        ElementType::AlphaG1,
+                                        subgroup_policy,
+                                        parameters,
+                                    );
                                 });
 
                                 // Process beta_g1 elements.
                                 t.spawn(|_| {
                                     let _enter = span.enter();
 
-                                    let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
-                                    check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine>(
+                                    let mut g1 = g1_pool.take(parameters.batch_size);
+                                    beta_g1_result = check_and_copy_batch(
                                         (beta_g1, compressed_output),
+                                        (new_challenge_beta_g1, compressed_new_challenge),
                                         (start_chunk, end_chunk),
                                         &mut g1,
-                                        subgroup_check_mode,
-                                    )
-                                    .expect("could not check element are non zero and in prime order subgroup");
-
-                                    let size = buffer_size::<E::G1Affine>(compressed_new_challenge);
-                                    new_challenge_beta_g1[start_chunk * size..end_chunk * size]
-                                        .write_batch(&mut g1[0..end_chunk - start_chunk], compressed_new_challenge)
-                                        .expect("Should have written beta_g1 to new challenge");
-
-                                    trace!("beta_g1 verification was successful");
+                                        ElementType::BetaG1,
+                                        subgroup_policy,
+                                        parameters,
+                                    );
                                 });
                             });
                         }
@@ -332,75 +782,60 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                         t.spawn(|_| {
                             let _ = span.enter();
 
-                            let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
-                            check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine>(
+                            let mut g1 = g1_pool.take(parameters.batch_size);
+                            tau_g1_result = check_and_copy_batch(
                                 (tau_g1, compressed_output),
+                                (new_challenge_tau_g1, compressed_new_challenge),
                                 (start_chunk, end_chunk),
                                 &mut g1,
-                                subgroup_check_mode,
-                            )
-                            .expect("could not check ratios for tau_g1 elements");
-
-                            let size = buffer_size::<E::G1Affine>(compressed_new_challenge);
-                            new_challenge_tau_g1[start_chunk * size..end_chunk * size]
-                                .write_batch(&mut g1[0..end_chunk - start_chunk], compressed_new_challenge)
-                                .expect("Should have written tau_g1 to new challenge");
-
-                            trace!("tau_g1 verification was successful");
+                                ElementType::TauG1,
+                                subgroup_policy,
+                                parameters,
+                            );
                         });
 
                         if start == 0 {
                             t.spawn(|_| {
                                 let _ = span.enter();
 
-                                let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
                                 let num_alpha_powers = 3;
 
-                                let start_chunk = 0;
+                                // These sections do not depend on the batch size, which may be smaller
+                                // than them when verifying with a different batch size than contributed with
                                 let end_chunk = num_alpha_powers + 3 * parameters.total_size_in_log2;
-
-                                check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine>(
+                                let mut g1 = vec![E::G1Affine::zero(); end_chunk];
+                                alpha_g1_result = check_and_copy_batch(
                                     (alpha_g1, compressed_output),
-                                    (start_chunk, end_chunk),
+                                    (new_challenge_alpha_g1, compressed_new_challenge),
+                                    (0, end_chunk),
                                     &mut g1,
-                                    subgroup_check_mode,
-                                )
-                                .expect("could not check ratios for tau_g1 elements");
-
-                                let size = buffer_size::<E::G1Affine>(compressed_new_challenge);
-                                new_challenge_alpha_g1[start_chunk * size..end_chunk * size]
-                                    .write_batch(&mut g1[0..end_chunk - start_chunk], compressed_new_challenge)
-                                    .expect("Should have written alpha_g1 to new challenge");
-
-                                trace!("alpha_g1 verification was successful");
+                                    ElementType::AlphaG1,
+                                    subgroup_policy,
+                                    parameters,
+                                );
 
-                                let start_chunk = 0;
                                 let end_chunk = parameters.total_size_in_log2 + 2;
-
-                                let mut g2 = vec![E::G2Affine::zero(); parameters.batch_size];
-
-                                check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G2Affine>(
+                                let mut g2 = vec![E::G2Affine::zero(); end_chunk];
+                                tau_g2_result = check_and_copy_batch(
                                     (tau_g2, compressed_output),
-                                    (start_chunk, end_chunk),
+                                    (new_challenge_tau_g2, compressed_new_challenge),
+                                    (0, end_chunk),
                                     &mut g2,
-                                    subgroup_check_mode,
-                                )
-                                .expect("could not check element are non zero and in prime order subgroup");
-
-                                let size = buffer_size::<E::G2Affine>(compressed_new_challenge);
-                                new_challenge_tau_g2[start_chunk * size..end_chunk * size]
-                                    .write_batch(&mut g2[0..end_chunk - start_chunk], compressed_new_challenge)
-                                    .expect("Should have written tau_g2 to new challenge");
-
-                                trace!("tau_g2 verification was successful");
+                                    ElementType::TauG2,
+                                    subgroup_policy,
+                                    parameters,
+                                );
                             });
                         }
                     });
                 }
             }
 
+            tau_g1_result
+                .and(tau_g2_result)
+                .and(alpha_g1_result)
+                .and(beta_g1_result)?;
+
             debug!("batch verification successful");
 
             Ok(())
@@ -408,22 +843,96 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
 
         info!("phase1-verification complete");
 
-        Ok(())
+        Ok(VerificationReport::new(
+            batches_checked,
+            SectionMask::all(),
+            started,
+            parameters,
+        ))
     }
 
     /// Verifies that the accumulator was transformed correctly
     /// given the `PublicKey` and the so-far hash of the accumulator.
     /// This verifies the ratios in a given accumulator.
+    /// Returns a summary of what was verified.
     pub fn aggregate_verification(
         (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
         parameters: &Phase1Parameters<E>,
-    ) -> Result<()> {
+    ) -> Result<VerificationReport> {
+        Self::aggregate_verification_reporting(
+            (output, compressed_output, check_output_for_correctness),
+            SectionMask::all(),
+            None,
+            parameters,
+        )
+    }
+
+    /// Same as `aggregate_verification`, except that only the ratios of the sections
+    /// selected by `sections` are verified, e.g. to focus on a single section while
+    /// auditing or debugging. The initial elements are always verified.
+    pub fn aggregate_verification_of_sections(
+        (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+        sections: SectionMask,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        Self::aggregate_verification_reporting(
+            (output, compressed_output, check_output_for_correctness),
+            sections,
+            None,
+            parameters,
+        )
+    }
+
+    /// Same as `aggregate_verification`, except that the outcome of each batch is sent
+    /// over `progress` as soon as it completes, followed by the outcome of the whole
+    /// chunk, including when it fails.
+    pub fn aggregate_verification_with_progress(
+        (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+        progress: &Sender<ChunkResult>,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        Self::aggregate_verification_reporting(
+            (output, compressed_output, check_output_for_correctness),
+            SectionMask::all(),
+            Some(progress),
+            parameters,
+        )
+    }
+
+    fn aggregate_verification_reporting(
+        (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+        sections: SectionMask,
+        progress: Option<&Sender<ChunkResult>>,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        let started = Instant::now();
+        let result = Self::aggregate_verify_chunk(
+            (output, compressed_output, check_output_for_correctness),
+            sections,
+            progress,
+            parameters,
+        );
+        report_chunk(progress, started, result.is_ok(), parameters);
+        result
+    }
+
+    fn aggregate_verify_chunk(
+        (output, compressed_output, check_output_for_correctness): (&[u8], UseCompression, CheckForCorrectness),
+        sections: SectionMask,
+        progress: Option<&Sender<ChunkResult>>,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
         let span = info_span!("phase1-aggregate-verification");
         let _enter = span.enter();
+        let started = Instant::now();
 
-        info!("starting...");
+        info!("starting, verifying sections {}...", sections);
+
+        // The batches are read into recycled buffers
+        let g1_pool = BufferPool::<E::G1Affine>::new(parameters.buffer_pool_size);
+        let g2_pool = BufferPool::<E::G2Affine>::new(parameters.buffer_pool_size);
 
-        let (tau_g1, tau_g2, alpha_g1, beta_g1, _) = split(output, parameters, compressed_output);
+        let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = split(output, parameters, compressed_output);
 
         let (g1_check, g2_check, g1_alpha_check) = {
             // Ensure that the initial conditions are correctly formed (first 2 elements)
@@ -449,134 +958,142 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
             (g1_check, g2_check, g1_alpha_check)
         };
 
+        // Marlin accumulators have no beta elements, and only the first chunk contains beta_g1[0]
+        if parameters.proving_system == ProvingSystem::Groth16
+            && (parameters.contribution_mode == ContributionMode::Full || parameters.chunk_index == 0)
+        {
+            check_beta_g2::<E>((beta_g1, beta_g2), compressed_output, check_output_for_correctness)?;
+        }
+
         debug!("initial elements were computed correctly");
 
-        match parameters.proving_system {
+        let batches_checked = match parameters.proving_system {
             // preallocate 2 vectors per batch
             // Ensure that the pairs are created correctly (we do this in chunks!)
             // load `batch_size` chunks on each iteration and perform the transformation
             ProvingSystem::Groth16 => {
-                iter_chunk(&parameters, |start, end| {
+                iter_chunk_timed(&parameters, progress, None, |start, end| {
                     debug!("verifying batch from {} to {}", start, end);
 
                     let span = info_span!("batch", start, end);
                     let _enter = span.enter();
 
-                    rayon::scope(|t| {
-                        let _enter = span.enter();
-
-                        t.spawn(|_| {
-                            let _enter = span.enter();
-
-                            let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
-                            check_power_ratios::<E>(
-                                (tau_g1, compressed_output, check_output_for_correctness),
-                                (start, end),
-                                &mut g1,
-                                &g2_check,
-                            )
-                            .expect("could not check ratios for tau_g1 elements");
-
-                            trace!("tau_g1 verification successful");
-                        });
-
-                        if start < parameters.powers_length {
-                            // if the `end` would be out of bounds, then just process until
-                            // the end (this is necessary in case the last batch would try to
-                            // process more elements than available)
-                            let end = if start + parameters.batch_size > parameters.powers_length {
-                                parameters.powers_length
-                            } else {
-                                end
-                            };
-
-                            rayon::scope(|t| {
-                                let _enter = span.enter();
-
-                                t.spawn(|_| {
-                                    let _enter = span.enter();
-
-                                    let mut g2 = vec![E::G2Affine::zero(); parameters.batch_size];
-
-                                    check_power_ratios_g2::<E>(
-                                        (tau_g2, compressed_output, check_output_for_correctness),
-                                        (start, end),
-                                        &mut g2,
-                                        &g1_check,
-                                    )
-                                    .expect("could not check ratios for tau_g2 elements");
-
-                                    trace!("tau_g2 verification successful");
-                                });
-
-                                t.spawn(|_| {
-                                    let _enter = span.enter();
-
-                                    let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
-
-                                    check_power_ratios::<E>(
-                                        (alpha_g1, compressed_output, check_output_for_correctness),
-                                        (start, end),
-                                        &mut g1,
-                                        &g2_check,
-                                    )
-                                    .expect("could not check ratios for alpha_g1 elements");
-
-                                    trace!("alpha_g1 verification successful");
-                                });
+                    // if the `end` would be out of bounds, then just process until
+                    // the end (this is necessary in case the last batch would try to
+                    // process more elements than available)
+                    let other_end = if start + parameters.batch_size > parameters.powers_length {
+                        parameters.powers_length
+                    } else {
+                        end
+                    };
 
-                                t.spawn(|_| {
-                                    let _enter = span.enter();
+                    let check_section = |section: ElementType| -> Result<()> {
+                        let _enter = span.enter();
 
-                                    let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
+                        match section {
+                            ElementType::TauG1 => {
+                                let mut g1 = g1_pool.take(parameters.batch_size);
+                                check_power_ratios::<E, _>(
+                                    (tau_g1, compressed_output, check_output_for_correctness),
+                                    (start, end),
+                                    &mut g1,
+                                    &g2_check,
+                                    (section, 0),
+                                    &mut verification_rng(section, start, parameters),
+                                )?;
+                            }
+                            ElementType::TauG2 => {
+                                let mut g2 = g2_pool.take(parameters.batch_size);
+                                check_power_ratios_g2::<E, _>(
+                                    (tau_g2, compressed_output, check_output_for_correctness),
+                                    (start, other_end),
+                                    &mut g2,
+                                    &g1_check,
+                                    (section, 0),
+                                    &mut verification_rng(section, start, parameters),
+                                )?;
+                            }
+                            _ => {
+                                let buffer = if section == ElementType::AlphaG1 {
+                                    alpha_g1
+                                } else {
+                                    beta_g1
+                                };
+                                let mut g1 = g1_pool.take(parameters.batch_size);
+                                check_power_ratios::<E, _>(
+                                    (buffer, compressed_output, check_output_for_correctness),
+                                    (start, other_end),
+                                    &mut g1,
+                                    &g2_check,
+                                    (section, 0),
+                                    &mut verification_rng(section, start, parameters),
+                                )?;
+                            }
+                        }
 
-                                    check_power_ratios::<E>(
-                                        (beta_g1, compressed_output, check_output_for_correctness),
-                                        (start, end),
-                                        &mut g1,
-                                        &g2_check,
-                                    )
-                                    .expect("could not check ratios for beta_g1 elements");
+                        trace!("{} verification successful", section);
+                        Ok(())
+                    };
 
-                                    trace!("beta_g1 verification successful");
-                                });
-                            });
+                    let mut batch_sections = vec![];
+                    if sections.tau_g1 {
+                        batch_sections.push(ElementType::TauG1);
+                    }
+                    if start < parameters.powers_length {
+                        for section in &[ElementType::TauG2, ElementType::AlphaG1, ElementType::BetaG1] {
+                            if sections.contains(*section) {
+                                batch_sections.push(*section);
+                            }
                         }
-                    });
+                    }
+                    // The sections are verified in groups of at most `max_inflight`, one group after the other
+                    let group_size = match parameters.max_inflight {
+                        0 => batch_sections.len(),
+                        max_inflight => max_inflight,
+                    };
+                    for group in batch_sections.chunks(std::cmp::max(group_size, 1)) {
+                        group
+                            .par_iter()
+                            .map(|section| check_section(*section))
+                            .collect::<Result<()>>()?;
+                    }
 
                     debug!("chunk verification successful");
 
                     Ok(())
-                })?;
+                })?
             }
             ProvingSystem::Marlin => {
-                iter_chunk(&parameters, |start, end| {
+                iter_chunk_timed(&parameters, progress, None, |start, end| {
                     debug!("verifying batch from {} to {}", start, end);
 
                     let span = info_span!("batch", start, end);
                     let _enter = span.enter();
 
-                    rayon::scope(|t| {
-                        let _enter = span.enter();
-
-                        t.spawn(|_| {
+                    let (tau_g1_result, powers_of_two_result) = rayon::join(
+                        || -> Result<()> {
+                            if !sections.tau_g1 {
+                                return Ok(());
+                            }
                             let _enter = span.enter();
 
-                            let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
+                            let mut g1 = g1_pool.take(parameters.batch_size);
 
-                            check_power_ratios::<E>(
+                            check_power_ratios::<E, _>(
                                 (tau_g1, compressed_output, check_output_for_correctness),
                                 (start, end),
                                 &mut g1,
                                 &g2_check,
-                            )
-                            .expect("could not check ratios for tau_g1 elements");
+                                (ElementType::TauG1, 0),
+                                &mut verification_rng(ElementType::TauG1, start, parameters),
+                            )?;
 
                             trace!("tau_g1 verification successful");
-                        });
+                            Ok(())
+                        },
+                        || -> Result<()> {
+                            let _enter = span.enter();
 
-                        {
                             let powers_of_two_in_range = (0..parameters.total_size_in_log2)
                                 .map(|i| (i, parameters.powers_length as u64 - 1 - (1 << i) + 2))
                                 .map(|(i, p)| (i, p as usize))
@@ -588,72 +1105,77 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                                 let g2_size = buffer_size::<E::G2Affine>(compressed_output);
 
                                 let g1 = (&tau_g1[p * g1_size..(p + 1) * g1_size])
-                                    .read_element(compressed_output, check_output_for_correctness)
-                                    .expect("should have read g1 element");
+                                    .read_element(compressed_output, check_output_for_correctness)?;
                                 let g2 = (&tau_g2[(2 + i) * g2_size..(2 + i + 1) * g2_size])
-                                    .read_element(compressed_output, check_output_for_correctness)
-                                    .expect("should have read g2 element");
-                                check_same_ratio::<E>(
-                                    &(g1, E::G1Affine::prime_subgroup_generator()),
-                                    &(E::G2Affine::prime_subgroup_generator(), g2),
-                                    "G1<>G2",
-                                )
-                                .expect("should have checked same ratio");
+                                    .read_element(compressed_output, check_output_for_correctness)?;
+                                if sections.tau_g2 {
+                                    check_same_ratio::<E>(
+                                        &(g1, E::G1Affine::prime_subgroup_generator()),
+                                        &(E::G2Affine::prime_subgroup_generator(), g2),
+                                        "G1<>G2",
+                                    )?;
+                                }
+
+                                if !sections.alpha_g1 {
+                                    continue;
+                                }
 
                                 let mut alpha_g1_elements = vec![E::G1Affine::zero(); 3];
-                                (&alpha_g1[(3 + 3 * i) * g1_size..(3 + 3 * i + 3) * g1_size])
-                                    .read_batch_preallocated(
-                                        &mut alpha_g1_elements,
-                                        compressed_output,
-                                        check_output_for_correctness,
-                                    )
-                                    .expect("should have read alpha g1 elements");
+                                (&alpha_g1[(3 + 3 * i) * g1_size..(3 + 3 * i + 3) * g1_size]).read_batch_preallocated(
+                                    &mut alpha_g1_elements,
+                                    compressed_output,
+                                    check_output_for_correctness,
+                                )?;
                                 check_same_ratio::<E>(
                                     &(alpha_g1_elements[0], alpha_g1_elements[1]),
                                     &g2_check,
                                     "alpha_g1 ratio 1",
-                                )
-                                .expect("should have checked same ratio");
+                                )?;
                                 check_same_ratio::<E>(
                                     &(alpha_g1_elements[1], alpha_g1_elements[2]),
                                     &g2_check,
                                     "alpha_g1 ratio 2",
-                                )
-                                .expect("should have checked same ratio");
+                                )?;
                                 check_same_ratio::<E>(
                                     &(alpha_g1_elements[0], g1_alpha_check.0),
                                     &(E::G2Affine::prime_subgroup_generator(), g2),
                                     "alpha consistent",
-                                )
-                                .expect("should have checked same ratio");
+                                )?;
                             }
-                        }
-                    });
+                            Ok(())
+                        },
+                    );
+                    tau_g1_result?;
+                    powers_of_two_result?;
 
                     // This is the first batch, check alpha_g1. batch size is guaranteed to be of size >= 3
-                    if start == 0 {
+                    if start == 0 && sections.alpha_g1 {
                         let num_alpha_powers = 3;
                         let mut g1 = vec![E::G1Affine::zero(); num_alpha_powers];
 
-                        check_power_ratios::<E>(
+                        check_power_ratios::<E, _>(
                             (alpha_g1, compressed_output, check_output_for_correctness),
                             (0, num_alpha_powers),
                             &mut g1,
                             &g2_check,
-                        )
-                        .expect("could not check ratios for alpha_g1");
+                            (ElementType::AlphaG1, 0),
+                            &mut verification_rng(ElementType::AlphaG1, 0, parameters),
+                        )?;
 
                         trace!("alpha_g1 verification was successful");
+                    }
 
+                    if start == 0 && sections.tau_g2 {
                         let mut g2 = vec![E::G2Affine::zero(); 3];
 
-                        check_power_ratios_g2::<E>(
+                        check_power_ratios_g2::<E, _>(
                             (tau_g2, compressed_output, check_output_for_correctness),
                             (0, 2),
                             &mut g2,
                             &g1_check,
-                        )
-                        .expect("could not check ratios for tau_g2");
+                            (ElementType::TauG2, 0),
+                            &mut verification_rng(ElementType::TauG2, 0, parameters),
+                        )?;
 
                         trace!("tau_g2 verification was successful");
                     }
@@ -661,19 +1183,380 @@ impl<'a, E: PairingEngine + Sync> Phase1<'a, E> {
                     debug!("chunk verification successful");
 
                     Ok(())
-                })?;
+                })?
             }
+        };
+        info!("aggregate verification complete");
+        Ok(VerificationReport::new(batches_checked, sections, started, parameters))
+    }
+
+    /// Same as `aggregate_verification` for Groth16 accumulators, except that the accumulator
+    /// is never accessed as a whole. `read_window` is called with the byte range of each
+    /// section's current batch, so that callers can e.g. map only that range of a file in
+    /// memory, bounding the resident memory to roughly one batch.
+    pub fn aggregate_verification_windowed<W: Deref<Target = [u8]>>(
+        read_window: impl Fn(Range<usize>) -> Result<W>,
+        (compressed_output, check_output_for_correctness): (UseCompression, CheckForCorrectness),
+        sections: SectionMask,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<()> {
+        let span = info_span!("phase1-aggregate-verification-windowed");
+        let _enter = span.enter();
+
+        if parameters.proving_system != ProvingSystem::Groth16 {
+            return Err(Error::Unsupported(
+                "windowed verification is only available for Groth16",
+            ));
         }
 
-        info!("aggregate verification complete");
+        info!("starting...");
+
+        let g1_size = buffer_size::<E::G1Affine>(compressed_output);
+        let g2_size = buffer_size::<E::G2Affine>(compressed_output);
+        let (tau_g1_offset, tau_g2_offset, alpha_g1_offset, beta_g1_offset, beta_g2_offset) =
+            section_offsets(compressed_output, parameters);
+
+        let g1_window =
+            |offset: usize, (start, end): (usize, usize)| read_window(offset + start * g1_size..offset + end * g1_size);
+        let g2_window =
+            |offset: usize, (start, end): (usize, usize)| read_window(offset + start * g2_size..offset + end * g2_size);
+
+        let (g1_check, g2_check) = {
+            // Current iteration of tau_g1[0] and tau_g1[1].
+            let after_g1 = read_initial_elements::<E::G1Affine>(
+                &g1_window(tau_g1_offset, (0, 2))?,
+                compressed_output,
+                check_output_for_correctness,
+            )?;
+
+            // Current iteration of tau_g2[0] and tau_g2[1].
+            let after_g2 = read_initial_elements::<E::G2Affine>(
+                &g2_window(tau_g2_offset, (0, 2))?,
+                compressed_output,
+                check_output_for_correctness,
+            )?;
+
+            ((after_g1[0], after_g1[1]), (after_g2[0], after_g2[1]))
+        };
+
+        check_beta_g2::<E>(
+            (&g1_window(beta_g1_offset, (0, 1))?, &g2_window(beta_g2_offset, (0, 1))?),
+            compressed_output,
+            check_output_for_correctness,
+        )?;
+
+        debug!("initial elements were computed correctly");
+
+        let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
+        let mut g2 = vec![E::G2Affine::zero(); parameters.batch_size];
+
+        // The batches are verified one after the other, so that only one window is mapped at a time
+        iter_chunk(&parameters, |start, end| {
+            debug!("verifying batch from {} to {}", start, end);
+
+            let span = info_span!("batch", start, end);
+            let _enter = span.enter();
+
+            if sections.tau_g1 {
+                check_power_ratios::<E, _>(
+                    (
+                        &g1_window(tau_g1_offset, (start, end))?,
+                        compressed_output,
+                        check_output_for_correctness,
+                    ),
+                    (0, end - start),
+                    &mut g1,
+                    &g2_check,
+                    (ElementType::TauG1, start),
+                    &mut verification_rng(ElementType::TauG1, start, parameters),
+                )?;
+
+                trace!("tau_g1 verification successful");
+            }
+
+            if start < parameters.powers_length {
+                // if the `end` would be out of bounds, then just process until
+                // the end (this is necessary in case the last batch would try to
+                // process more elements than available)
+                let end = if start + parameters.batch_size > parameters.powers_length {
+                    parameters.powers_length
+                } else {
+                    end
+                };
+
+                if sections.tau_g2 {
+                    check_power_ratios_g2::<E, _>(
+                        (
+                            &g2_window(tau_g2_offset, (start, end))?,
+                            compressed_output,
+                            check_output_for_correctness,
+                        ),
+                        (0, end - start),
+                        &mut g2,
+                        &g1_check,
+                        (ElementType::TauG2, start),
+                        &mut verification_rng(ElementType::TauG2, start, parameters),
+                    )?;
+
+                    trace!("tau_g2 verification successful");
+                }
+
+                for (offset, section) in &[
+                    (alpha_g1_offset, ElementType::AlphaG1),
+                    (beta_g1_offset, ElementType::BetaG1),
+                ] {
+                    if !sections.contains(*section) {
+                        continue;
+                    }
+                    check_power_ratios::<E, _>(
+                        (
+                            &g1_window(*offset, (start, end))?,
+                            compressed_output,
+                            check_output_for_correctness,
+                        ),
+                        (0, end - start),
+                        &mut g1,
+                        &g2_check,
+                        (*section, start),
+                        &mut verification_rng(*section, start, parameters),
+                    )?;
+
+                    trace!("{} verification successful", section);
+                }
+            }
+
+            debug!("chunk verification successful");
+
+            Ok(())
+        })?;
+
+        info!("windowed aggregate verification complete");
         Ok(())
     }
+
+    ///
+    /// Phase 1 - Low Memory Verification
+    ///
+    /// Same as `verification` for Groth16 accumulators, except that neither the input, the
+    /// output nor the new challenge are accessed as a whole. `read_input_window` and
+    /// `read_output_window` are called with the byte range of each section's current batch,
+    /// and `write_new_challenge` with the offset and the bytes of each batch of the new
+    /// challenge, whose hash is left to the caller. The batches are verified one after the
+    /// other, so that the memory used is bounded by a few batches of `batch_size` elements,
+    /// regardless of the power or of the chunk size.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_streaming<R: Deref<Target = [u8]>, W: Deref<Target = [u8]>>(
+        read_input_window: impl Fn(Range<usize>) -> Result<R>,
+        read_output_window: impl Fn(Range<usize>) -> Result<W>,
+        mut write_new_challenge: impl FnMut(usize, &[u8]) -> Result<()>,
+        key: &PublicKey<E>,
+        digest: &[u8],
+        (compressed_input, check_input_for_correctness): (UseCompression, CheckForCorrectness),
+        (compressed_output, check_output_for_correctness): (UseCompression, CheckForCorrectness),
+        compressed_new_challenge: UseCompression,
+        subgroup_check_mode: SubgroupCheckMode,
+        parameters: &Phase1Parameters<E>,
+    ) -> Result<VerificationReport> {
+        let span = info_span!("phase1-verification-streaming");
+        let _enter = span.enter();
+        let started = Instant::now();
+
+        if parameters.proving_system != ProvingSystem::Groth16 {
+            return Err(Error::Unsupported(
+                "low memory verification is only available for Groth16",
+            ));
+        }
+
+        info!("starting...");
+
+        let input_offsets = section_offsets(compressed_input, parameters);
+        let output_offsets = section_offsets(compressed_output, parameters);
+        let new_challenge_offsets = section_offsets(compressed_new_challenge, parameters);
+
+        let (in_g1_size, in_g2_size) = (
+            buffer_size::<E::G1Affine>(compressed_input),
+            buffer_size::<E::G2Affine>(compressed_input),
+        );
+        let (g1_size, g2_size) = (
+            buffer_size::<E::G1Affine>(compressed_output),
+            buffer_size::<E::G2Affine>(compressed_output),
+        );
+        let (new_g1_size, new_g2_size) = (
+            buffer_size::<E::G1Affine>(compressed_new_challenge),
+            buffer_size::<E::G2Affine>(compressed_new_challenge),
+        );
+
+        // The windows of the elements [start, end) of the section at `offset`
+        let input_window = |offset: usize, size: usize, (start, end): (usize, usize)| {
+            read_input_window(offset + start * size..offset + end * size)
+        };
+        let output_window = |offset: usize, size: usize, (start, end): (usize, usize)| {
+            read_output_window(offset + start * size..offset + end * size)
+        };
+
+        // The proofs of knowledge may be bound to the chunk the response was produced for
+        let digest = &bind_digest_to_chunk(digest, parameters);
+        if parameters.contribution_mode == ContributionMode::Full || parameters.chunk_index == 0 {
+            // Only the first elements of each section are needed to check the initial elements
+            let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = input_offsets;
+            let input = [
+                input_window(tau_g1, in_g1_size, (0, 2))?,
+                input_window(tau_g2, in_g2_size, (0, 2))?,
+                input_window(alpha_g1, in_g1_size, (0, 2))?,
+                input_window(beta_g1, in_g1_size, (0, 2))?,
+                input_window(beta_g2, in_g2_size, (0, 1))?,
+            ];
+            let (tau_g1, tau_g2, alpha_g1, beta_g1, beta_g2) = output_offsets;
+            let output = [
+                output_window(tau_g1, g1_size, (0, 2))?,
+                output_window(tau_g2, g2_size, (0, 2))?,
+                output_window(alpha_g1, g1_size, (0, 2))?,
+                output_window(beta_g1, g1_size, (0, 2))?,
+                output_window(beta_g2, g2_size, (0, 1))?,
+            ];
+            verify_initial_sections(
+                (
+                    (&*input[0], &*input[1], &*input[2], &*input[3], &*input[4]),
+                    compressed_input,
+                    check_input_for_correctness,
+                ),
+                (
+                    (&*output[0], &*output[1], &*output[2], &*output[3], &*output[4]),
+                    compressed_output,
+                    check_output_for_correctness,
+                ),
+                key,
+                digest,
+                parameters,
+            )?;
+
+            // Carry beta_g2 over to the new challenge.
+            let after_beta_g2 =
+                (&*output[4]).read_element::<E::G2Affine>(compressed_output, check_output_for_correctness)?;
+            let mut new_beta_g2 = vec![];
+            new_beta_g2.write_element(&after_beta_g2, compressed_new_challenge)?;
+            write_new_challenge(new_challenge_offsets.4, &new_beta_g2)?;
+        } else if parameters.bind_chunk_index {
+            // Otherwise only the proofs of knowledge are checked, when they bind the response to its chunk.
+            verify_proofs_of_knowledge(key, digest)?;
+        }
+
+        debug!("initial elements were computed correctly");
+
+        // The only buffers, which are reused for every batch
+        let mut g1 = vec![E::G1Affine::zero(); parameters.batch_size];
+        let mut g2 = vec![E::G2Affine::zero(); parameters.batch_size];
+        let mut batch = vec![];
+
+        let batches_checked = iter_chunk_timed(parameters, None, None, |start, end| {
+            debug!("verifying batch from {} to {}", start, end);
+
+            let span = info_span!("batch", start, end);
+            let _enter = span.enter();
+
+            // Determine the chunk start and end indices based on the contribution mode.
+            let chunk_range = |start: usize, end: usize| match parameters.contribution_mode {
+                ContributionMode::Chunked => (
+                    start - parameters.chunk_index * parameters.chunk_size,
+                    end - parameters.chunk_index * parameters.chunk_size,
+                ),
+                ContributionMode::Full => (start, end),
+            };
+
+            let (start_chunk, end_chunk) = chunk_range(start, end);
+            check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine, _>(
+                (
+                    &*output_window(output_offsets.0, g1_size, (start_chunk, end_chunk))?,
+                    compressed_output,
+                ),
+                (0, end_chunk - start_chunk),
+                &mut g1,
+                subgroup_check_mode,
+                &mut verification_rng(ElementType::TauG1, start_chunk, parameters),
+            )?;
+            batch.resize((end_chunk - start_chunk) * new_g1_size, 0);
+            batch.write_batch(&g1[0..end_chunk - start_chunk], compressed_new_challenge)?;
+            write_new_challenge(new_challenge_offsets.0 + start_chunk * new_g1_size, &batch)?;
+
+            trace!("tau_g1 verification was successful");
+
+            if start < parameters.powers_length {
+                // If the `end` would be out of bounds, then just process until
+                // the end (this is necessary in case the last batch would try to
+                // process more elements than available).
+                let max = match parameters.contribution_mode {
+                    ContributionMode::Chunked => std::cmp::min(
+                        (parameters.chunk_index + 1) * parameters.chunk_size,
+                        parameters.powers_length,
+                    ),
+                    ContributionMode::Full => parameters.powers_length,
+                };
+                let end = if start + parameters.batch_size > max { max } else { end };
+                let (start_chunk, end_chunk) = chunk_range(start, end);
+
+                check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G2Affine, _>(
+                    (
+                        &*output_window(output_offsets.1, g2_size, (start_chunk, end_chunk))?,
+                        compressed_output,
+                    ),
+                    (0, end_chunk - start_chunk),
+                    &mut g2,
+                    subgroup_check_mode,
+                    &mut verification_rng(ElementType::TauG2, start_chunk, parameters),
+                )?;
+                batch.resize((end_chunk - start_chunk) * new_g2_size, 0);
+                batch.write_batch(&g2[0..end_chunk - start_chunk], compressed_new_challenge)?;
+                write_new_challenge(new_challenge_offsets.1 + start_chunk * new_g2_size, &batch)?;
+
+                trace!("tau_g2 verification was successful");
+
+                for (offset, new_challenge_offset, section) in &[
+                    (output_offsets.2, new_challenge_offsets.2, ElementType::AlphaG1),
+                    (output_offsets.3, new_challenge_offsets.3, ElementType::BetaG1),
+                ] {
+                    check_elements_are_nonzero_and_in_prime_order_subgroup::<E::G1Affine, _>(
+                        (
+                            &*output_window(*offset, g1_size, (start_chunk, end_chunk))?,
+                            compressed_output,
+                        ),
+                        (0, end_chunk - start_chunk),
+                        &mut g1,
+                        subgroup_check_mode,
+                        &mut verification_rng(*section, start_chunk, parameters),
+                    )?;
+                    batch.resize((end_chunk - start_chunk) * new_g1_size, 0);
+                    batch.write_batch(&g1[0..end_chunk - start_chunk], compressed_new_challenge)?;
+                    write_new_challenge(new_challenge_offset + start_chunk * new_g1_size, &batch)?;
+
+                    trace!("{} verification was successful", section);
+                }
+            }
+
+            debug!("batch verification successful");
+
+            Ok(())
+        })?;
+
+        info!("phase1-verification-streaming complete");
+
+        Ok(VerificationReport::new(
+            batches_checked,
+            SectionMask::all(),
+            started,
+            parameters,
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::testing::{generate_input, generate_new_challenge, generate_output};
+    use crate::helpers::{
+        section_mask_from_str,
+        testing::{generate_input, generate_new_challenge, generate_output, setup_verify},
+        CurveKind,
+    };
     use setup_utils::calculate_hash;
 
     use zexe_algebra::{Bls12_377, BW6_761};
@@ -732,6 +1615,43 @@ mod tests {
                 );
                 assert!(res.is_ok());
 
+                // verifying one window at a time writes the same new challenge
+                if *proving_system == ProvingSystem::Groth16 {
+                    let mut streamed_challenge = generate_new_challenge(&parameters, UseCompression::No);
+                    let res = Phase1::verify_streaming(
+                        |range| Ok(input[range].to_vec()),
+                        |range| Ok(output[range].to_vec()),
+                        |offset, bytes| {
+                            streamed_challenge[offset..offset + bytes.len()].copy_from_slice(bytes);
+                            Ok(())
+                        },
+                        &pubkey,
+                        &current_accumulator_hash,
+                        (compressed_input, CheckForCorrectness::No),
+                        (compressed_output, CheckForCorrectness::Full),
+                        UseCompression::No,
+                        SubgroupCheckMode::Auto,
+                        &parameters,
+                    );
+                    assert!(res.is_ok());
+                    assert_eq!(streamed_challenge, new_challenge);
+
+                    // the proofs of knowledge are bound to the digest
+                    let res = Phase1::verify_streaming(
+                        |range| Ok(input[range].to_vec()),
+                        |range| Ok(output[range].to_vec()),
+                        |_, _| Ok(()),
+                        &pubkey,
+                        &calculate_hash(&output),
+                        (compressed_input, CheckForCorrectness::No),
+                        (compressed_output, CheckForCorrectness::Full),
+                        UseCompression::No,
+                        SubgroupCheckMode::Auto,
+                        &parameters,
+                    );
+                    assert!(res.is_err());
+                }
+
                 // subsequent participants must use the hash of the accumulator they received
                 let current_accumulator_hash = calculate_hash(&output);
                 let (pubkey, privkey) = Phase1::key_generation(&mut rng, current_accumulator_hash.as_ref())
@@ -778,6 +1698,95 @@ mod tests {
                 );
                 assert!(res.is_ok());
 
+                // the same verification reports each batch, then the chunk, once they complete
+                let (sender, receiver) = std::sync::mpsc::channel();
+                let report = Phase1::aggregate_verification_with_progress(
+                    (&output_2, compressed_output, CheckForCorrectness::Full),
+                    &sender,
+                    &parameters,
+                )
+                .unwrap();
+                drop(sender);
+                let results = receiver.iter().collect::<Vec<_>>();
+                assert_eq!(results.len(), report.batches_checked + 1);
+                assert!(results
+                    .iter()
+                    .all(|r| r.success && r.chunk_index == parameters.chunk_index));
+                let (chunk_start, chunk_end) = chunk_range(&parameters);
+                let (batches, chunk) = results.split_at(report.batches_checked);
+                assert_eq!(batches[0].start, chunk_start);
+                for pair in batches.windows(2) {
+                    assert_eq!(pair[0].end, pair[1].start);
+                }
+                assert_eq!(batches[batches.len() - 1].end, chunk_end);
+                assert_eq!((chunk[0].start, chunk[0].end), (chunk_start, chunk_end));
+
+                // a chunk which fails verification is reported as well
+                let mut corrupted = output_2.clone();
+                let size = buffer_size::<E::G1Affine>(compressed_output);
+                let (tau_g1_2, tau_g1_3) = corrupted[2 * size..4 * size].split_at_mut(size);
+                tau_g1_2.swap_with_slice(tau_g1_3);
+                let (sender, receiver) = std::sync::mpsc::channel();
+                let res = Phase1::aggregate_verification_with_progress(
+                    (&corrupted, compressed_output, CheckForCorrectness::Full),
+                    &sender,
+                    &parameters,
+                );
+                assert!(res.is_err());
+                drop(sender);
+                // the verification stops at the first batch, which fails along with the chunk
+                let results = receiver.iter().collect::<Vec<_>>();
+                assert_eq!(results.len(), 2);
+                assert!(!results[0].success);
+                assert_eq!(results[0].start, chunk_start);
+                assert!(!results[1].success);
+
+                // bounding the number of sections verified at once does not change the outcome
+                for max_inflight in &[1, 2] {
+                    let mut parameters = parameters.clone();
+                    parameters.max_inflight = *max_inflight;
+                    let res = Phase1::aggregate_verification(
+                        (&output_2, compressed_output, CheckForCorrectness::Full),
+                        &parameters,
+                    );
+                    assert!(res.is_ok());
+                    let res = Phase1::aggregate_verification(
+                        (&corrupted, compressed_output, CheckForCorrectness::Full),
+                        &parameters,
+                    );
+                    assert!(res.is_err());
+                }
+
+                // reading the accumulator one window at a time gives the same result
+                if *proving_system == ProvingSystem::Groth16 {
+                    let res = Phase1::aggregate_verification_windowed(
+                        |range| Ok(output_2[range].to_vec()),
+                        (compressed_output, CheckForCorrectness::Full),
+                        SectionMask::all(),
+                        &parameters,
+                    );
+                    assert!(res.is_ok());
+                }
+
+                // each section can be verified on its own
+                for section in &["tau_g1", "tau_g2", "alpha_g1", "beta_g1"] {
+                    let res = Phase1::aggregate_verification_of_sections(
+                        (&output_2, compressed_output, CheckForCorrectness::Full),
+                        section_mask_from_str(section).unwrap(),
+                        &parameters,
+                    );
+                    assert!(res.is_ok());
+                    if *proving_system == ProvingSystem::Groth16 {
+                        let report = res.unwrap();
+                        assert_eq!(report.sections_verified, section_mask_from_str(section).unwrap());
+                        let expected = match *section {
+                            "tau_g1" => parameters.g1_chunk_size,
+                            _ => parameters.other_chunk_size,
+                        };
+                        assert_eq!(report.elements_checked, expected);
+                    }
+                }
+
                 // verification will fail if the old hash is used
                 let res = Phase1::verification(
                     &output,
@@ -1013,4 +2022,249 @@ mod tests {
         chunk_verification_test::<Bls12_377>(4, 3 + 3 * 4, UseCompression::No, UseCompression::No);
         chunk_verification_test::<Bls12_377>(4, 3 + 3 * 4, UseCompression::Yes, UseCompression::No);
     }
+
+    fn different_batch_size_verification_test<E: PairingEngine>(
+        total_size_in_log2: usize,
+        contribution_batch: usize,
+        verification_batch: usize,
+    ) {
+        let compressed_input = UseCompression::No;
+        let compressed_output = UseCompression::Yes;
+
+        for proving_system in &[ProvingSystem::Groth16, ProvingSystem::Marlin] {
+            let parameters = Phase1Parameters::<E>::new_full(*proving_system, total_size_in_log2, contribution_batch);
+
+            let (input, _) = generate_input(&parameters, compressed_input, CheckForCorrectness::No);
+            let mut output = generate_output(&parameters, compressed_output);
+
+            let current_accumulator_hash = blank_hash();
+            let mut rng = derive_rng_from_seed(b"test_different_batch_size");
+            let (pubkey, privkey) = Phase1::key_generation(&mut rng, current_accumulator_hash.as_ref())
+                .expect("could not generate keypair");
+            Phase1::computation(
+                &input,
+                &mut output,
+                compressed_input,
+                compressed_output,
+                CheckForCorrectness::No,
+                BatchExpMode::Auto,
+                &privkey,
+                &parameters,
+            )
+            .unwrap();
+
+            // the batch size only affects how the files are processed, not their layout
+            let verification_parameters =
+                Phase1Parameters::<E>::new_full(*proving_system, total_size_in_log2, verification_batch);
+            assert_eq!(parameters.accumulator_size, verification_parameters.accumulator_size);
+            assert_eq!(parameters.contribution_size, verification_parameters.contribution_size);
+
+            let mut new_challenge = generate_new_challenge(&verification_parameters, UseCompression::No);
+            let res = Phase1::verification(
+                &input,
+                &output,
+                &mut new_challenge,
+                &pubkey,
+                &current_accumulator_hash,
+                compressed_input,
+                compressed_output,
+                UseCompression::No,
+                CheckForCorrectness::No,
+                CheckForCorrectness::Full,
+                SubgroupCheckMode::Auto,
+                &verification_parameters,
+            );
+            assert!(res.is_ok());
+
+            let res = Phase1::aggregate_verification(
+                (&output, compressed_output, CheckForCorrectness::Full),
+                &verification_parameters,
+            );
+            assert!(res.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_verification_with_different_batch_size_bls12_377() {
+        different_batch_size_verification_test::<Bls12_377>(4, 3 + 3 * 4, 4);
+        different_batch_size_verification_test::<Bls12_377>(4, 3 + 3 * 4, 64);
+    }
+
+    #[test]
+    fn test_ratio_verification_checks_beta_g2() {
+        type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+        type G2 = <Bls12_377 as PairingEngine>::G2Affine;
+
+        for compressed in &[UseCompression::No, UseCompression::Yes] {
+            let compressed = *compressed;
+            let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 4, 3 + 3 * 4);
+            let (mut accumulator, _) = generate_input(&parameters, compressed, CheckForCorrectness::No);
+            let verify = |accumulator: &[u8]| {
+                let res =
+                    Phase1::aggregate_verification((accumulator, compressed, CheckForCorrectness::Full), &parameters);
+                let windowed = Phase1::aggregate_verification_windowed(
+                    |range| Ok(accumulator[range].to_vec()),
+                    (compressed, CheckForCorrectness::Full),
+                    SectionMask::all(),
+                    &parameters,
+                );
+                assert_eq!(res.is_ok(), windowed.is_ok());
+                res
+            };
+            verify(&accumulator).unwrap();
+
+            let g1_size = buffer_size::<G1>(compressed);
+            let g2_size = buffer_size::<G2>(compressed);
+            let beta_g2 = parameters.hash_size
+                + g1_size * (parameters.powers_g1_length + 2 * parameters.powers_length)
+                + g2_size * parameters.powers_length;
+            let beta_g2 = beta_g2..beta_g2 + g2_size;
+
+            // a zeroed beta_g2 is rejected
+            (&mut accumulator[beta_g2.clone()])
+                .write_element(&G2::zero(), compressed)
+                .unwrap();
+            assert!(verify(&accumulator).is_err());
+
+            // so is a beta_g2 which is not the G2 counterpart of beta_g1[0]
+            let point = G2::prime_subgroup_generator().into_projective().double().into_affine();
+            (&mut accumulator[beta_g2]).write_element(&point, compressed).unwrap();
+            match verify(&accumulator) {
+                Err(Error::VerificationError(VerificationError::InvalidRatio("BetaG1<>BetaG2"))) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_ratio_verification_reports_absolute_range() {
+        type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+
+        let compressed = UseCompression::No;
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 4, 4);
+        let (mut accumulator, _) = generate_input(&parameters, compressed, CheckForCorrectness::No);
+
+        // replace tau_g1[9], which is not in the first batch, by an element which is not a power of tau
+        let index = 9;
+        let g1_size = buffer_size::<G1>(compressed);
+        let position = parameters.hash_size + index * g1_size;
+        let point = G1::prime_subgroup_generator().into_projective().double().into_affine();
+        (&mut accumulator[position..position + g1_size])
+            .write_element(&point, compressed)
+            .unwrap();
+
+        let range = |res: Result<()>| match res {
+            Err(Error::VerificationError(VerificationError::InvalidRatioInRange {
+                section: ElementType::TauG1,
+                start,
+                end,
+                ..
+            })) => (start, end),
+            res => panic!("unexpected result: {:?}", res),
+        };
+        let (start, end) = range(
+            Phase1::aggregate_verification((&accumulator, compressed, CheckForCorrectness::Full), &parameters)
+                .map(|_| ()),
+        );
+        assert!(start <= index && index < end);
+        // the windowed verification reads each batch from its own window, and must still
+        // report the range within the section
+        let windowed = Phase1::aggregate_verification_windowed(
+            |range| Ok(accumulator[range].to_vec()),
+            (compressed, CheckForCorrectness::Full),
+            SectionMask::all(),
+            &parameters,
+        );
+        assert_eq!(range(windowed.map(|_| ())), (start, end));
+    }
+
+    #[test]
+    fn test_verification_with_validation_cache() {
+        let parameters = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, 2, 4);
+        let (input, output, pubkey, digest) = setup_verify(
+            UseCompression::No,
+            CheckForCorrectness::No,
+            UseCompression::Yes,
+            BatchExpMode::Auto,
+            &parameters,
+        );
+        let verify = |validation_cache: Option<&ValidationCache>| {
+            let mut new_challenge = generate_new_challenge(&parameters, UseCompression::No);
+            match validation_cache {
+                Some(validation_cache) => Phase1::verification_with_validation_cache(
+                    &input,
+                    &output,
+                    &mut new_challenge,
+                    &pubkey,
+                    &digest,
+                    UseCompression::No,
+                    UseCompression::Yes,
+                    UseCompression::No,
+                    CheckForCorrectness::No,
+                    CheckForCorrectness::Full,
+                    SubgroupCheckMode::Auto,
+                    validation_cache,
+                    &parameters,
+                ),
+                None => Phase1::verification(
+                    &input,
+                    &output,
+                    &mut new_challenge,
+                    &pubkey,
+                    &digest,
+                    UseCompression::No,
+                    UseCompression::Yes,
+                    UseCompression::No,
+                    CheckForCorrectness::No,
+                    CheckForCorrectness::Full,
+                    SubgroupCheckMode::Auto,
+                    &parameters,
+                ),
+            }
+            .unwrap();
+            new_challenge
+        };
+
+        let path = std::env::temp_dir().join(format!("verification_validation_cache_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let validation_cache = ValidationCache::open(&path, &CurveKind::Bls12_377, &parameters).unwrap();
+        let expected = verify(None);
+
+        // the first verification fills the cache
+        assert_eq!(verify(Some(&validation_cache)), expected);
+        let validated = validation_cache.len();
+        assert!(validated > 0);
+
+        // a repeated verification finds every range in the cache, and still writes the new challenge
+        validation_cache.save().unwrap();
+        let validation_cache = ValidationCache::open(&path, &CurveKind::Bls12_377, &parameters).unwrap();
+        assert_eq!(verify(Some(&validation_cache)), expected);
+        assert_eq!(validation_cache.len(), validated);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tolerate_subgroup_failure() {
+        // without collecting warnings, the error is passed through
+        let res = tolerate_subgroup_failure(Error::IncorrectSubgroup, None, ElementType::TauG1, (0, 2));
+        assert!(matches!(res, Err(Error::IncorrectSubgroup)));
+
+        // otherwise it is recorded as a warning
+        let warnings = Mutex::new(vec![]);
+        tolerate_subgroup_failure(Error::IncorrectSubgroup, Some(&warnings), ElementType::TauG2, (2, 4)).unwrap();
+        assert_eq!(
+            warnings.into_inner().unwrap(),
+            vec![SubgroupWarning {
+                element_type: ElementType::TauG2,
+                start: 2,
+                end: 4,
+            }]
+        );
+
+        // other errors are never tolerated
+        let warnings = Mutex::new(vec![]);
+        let res = tolerate_subgroup_failure(Error::PointAtInfinity, Some(&warnings), ElementType::TauG1, (0, 2));
+        assert!(matches!(res, Err(Error::PointAtInfinity)));
+        assert!(warnings.into_inner().unwrap().is_empty());
+    }
 }
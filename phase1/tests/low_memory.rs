@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod test {
+    use phase1::{helpers::testing::setup_verify, ContributionMode, Phase1, Phase1Parameters, ProvingSystem};
+    use setup_utils::{
+        buffer_size, BatchExpMode, CheckForCorrectness, Error, Result, SubgroupCheckMode, UseCompression,
+    };
+
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        fs::{self, File, OpenOptions},
+        io::{Read, Seek, SeekFrom, Write},
+        ops::Range,
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use zexe_algebra::{Bls12_377, PairingEngine};
+
+    /// Tracks the bytes allocated on the heap and their peak. Unlike the resident memory,
+    /// which is counted in pages and includes the memory the allocator keeps around, this
+    /// is exact, so that the bound on the memory of the verification can be tight.
+    struct CountingAllocator;
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+                PEAK.fetch_max(allocated, Ordering::SeqCst);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn read_window(mut file: &File, range: Range<usize>) -> Result<Vec<u8>> {
+        let mut window = vec![0; range.end - range.start];
+        file.seek(SeekFrom::Start(range.start as u64))?;
+        file.read_exact(&mut window)?;
+        Ok(window)
+    }
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("low_memory_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Verifies a contribution with `verify_streaming`, reading the accumulators from disk,
+    /// and returns the result with how much the heap grew while verifying
+    fn verify_from_disk<E: PairingEngine + Sync>(parameters: &Phase1Parameters<E>) -> (Result<()>, usize) {
+        let (compressed_input, compressed_output) = (UseCompression::No, UseCompression::Yes);
+
+        // The accumulators are only kept on disk, so that they do not count towards the memory
+        let (challenge_path, response_path, public_key, digest) = {
+            let (input, output, public_key, digest) = setup_verify(
+                compressed_input,
+                CheckForCorrectness::No,
+                compressed_output,
+                BatchExpMode::Auto,
+                parameters,
+            );
+            (
+                temp_file("challenge", &input),
+                temp_file("response", &output),
+                public_key,
+                digest,
+            )
+        };
+        let new_challenge_path = temp_file("new_challenge", &[]);
+        let challenge = File::open(&challenge_path).unwrap();
+        let response = File::open(&response_path).unwrap();
+        let mut new_challenge = OpenOptions::new().write(true).open(&new_challenge_path).unwrap();
+
+        let baseline = ALLOCATED.load(Ordering::SeqCst);
+        PEAK.store(baseline, Ordering::SeqCst);
+        let res = Phase1::verify_streaming(
+            |range| read_window(&challenge, range),
+            |range| read_window(&response, range),
+            |offset, bytes| {
+                new_challenge.seek(SeekFrom::Start(offset as u64))?;
+                new_challenge.write_all(bytes)?;
+                Ok(())
+            },
+            &public_key,
+            &digest,
+            (compressed_input, CheckForCorrectness::No),
+            (compressed_output, CheckForCorrectness::Full),
+            UseCompression::No,
+            SubgroupCheckMode::Auto,
+            parameters,
+        );
+        let growth = PEAK.load(Ordering::SeqCst) - baseline;
+
+        for path in &[challenge_path, response_path, new_challenge_path] {
+            fs::remove_file(path).unwrap();
+        }
+        (res.map(|_| ()), growth)
+    }
+
+    #[test]
+    fn test_low_memory_verification() {
+        type G2 = <Bls12_377 as PairingEngine>::G2Affine;
+
+        let powers = 10;
+        let batch = 4;
+        let full = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Groth16, powers, batch);
+        // The second chunk holds elements of every section
+        let chunk = full.into_chunk_parameters(ContributionMode::Chunked, 1, 1 << (powers - 1));
+
+        // The buffers only ever hold a few batches, while the accumulator is many times larger
+        let bound = 64 * batch * buffer_size::<G2>(UseCompression::No);
+        assert!(bound * 10 < full.get_length(UseCompression::No));
+        for parameters in &[full, chunk] {
+            let (res, growth) = verify_from_disk(parameters);
+            assert!(res.is_ok());
+            assert!(
+                growth < bound,
+                "the heap grew by {} bytes while verifying, more than {} bytes",
+                growth,
+                bound
+            );
+        }
+
+        // Marlin accumulators can't be verified in bounded memory
+        let marlin = Phase1Parameters::<Bls12_377>::new_full(ProvingSystem::Marlin, 2, batch);
+        let (res, _) = verify_from_disk(&marlin);
+        assert!(matches!(res, Err(Error::Unsupported(_))));
+    }
+}
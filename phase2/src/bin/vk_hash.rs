@@ -0,0 +1,297 @@
+use phase2::{
+    chunked_groth16::{query_lengths, QueryLengths},
+    cli_common::{
+        CheckQueryOpts, Command, ContributionDeltaOpts, CurveKind, Phase2Opts, VerifyContributorsOpts, VerifyFinalOpts,
+        VerifyLinkageOpts, VerifyNewChallengeOpts, VerifyOpts, VkHashOpts,
+    },
+    keypair::PublicKey,
+    parameters::{MPCParameters, VerificationFailure},
+};
+use setup_utils::{
+    calculate_hash, print_hash, CheckForCorrectness, Groth16Params, InvariantKind, Result, UseCompression,
+};
+
+use zexe_algebra::{Bls12_377, PairingEngine, BW6_761};
+
+use gumdrop::Options;
+use std::{fs::File, process, time::Instant};
+use tracing::{error, info};
+use tracing_subscriber::{
+    filter::EnvFilter,
+    fmt::{time::ChronoUtc, Subscriber},
+};
+
+/// Prints the hash of the verifying key of the parameters, with both compressed and
+/// uncompressed serialization, so that deployers commit to the value computed by the
+/// same code which produced the keys
+fn vk_hash<E: PairingEngine>(opt: &VkHashOpts) -> Result<()> {
+    let reader = File::open(&opt.params_fname).expect("unable to open the parameters file");
+    let parameters = MPCParameters::<E>::read(reader)?;
+
+    info!("Hash of the verifying key, serialized with compression:");
+    print_hash(&parameters.verifying_key_hash(UseCompression::Yes)?);
+    info!("Hash of the verifying key, serialized without compression:");
+    print_hash(&parameters.verifying_key_hash(UseCompression::No)?);
+
+    Ok(())
+}
+
+/// Reads the prepared phase 1 transcript, and returns the elements which the parameters
+/// take as is from it and which differ from it
+fn phase1_divergences<E: PairingEngine>(
+    parameters: &MPCParameters<E>,
+    phase1_fname: &str,
+    phase1_size: usize,
+    compressed: bool,
+) -> Result<Vec<InvariantKind>> {
+    // the H query of the parameters has one element less than the phase 2 size
+    let phase2_size = parameters.params.h_query.len() + 1;
+    let compressed = if compressed {
+        UseCompression::Yes
+    } else {
+        UseCompression::No
+    };
+    let mut transcript = std::fs::read(phase1_fname).expect("unable to read the phase 1 transcript");
+    let phase1 = Groth16Params::<E>::read(
+        &mut transcript,
+        compressed,
+        CheckForCorrectness::Full,
+        phase1_size,
+        phase2_size,
+    )?;
+
+    Ok(parameters.phase1_divergences(&phase1))
+}
+
+/// Checks that the parameters take the elements which come as is from phase 1 from the
+/// prepared phase 1 transcript. The queries also depend on the circuit, which this binary
+/// doesn't know of, so they are checked by `MPCParameters::verify_linkage` instead.
+fn verify_linkage<E: PairingEngine>(opt: &VerifyLinkageOpts) -> Result<()> {
+    let reader = File::open(&opt.params_fname).expect("unable to open the parameters file");
+    let parameters = MPCParameters::<E>::read(reader)?;
+
+    let divergences = phase1_divergences(&parameters, &opt.phase1_fname, opt.phase1_size, opt.compressed)?;
+    if !divergences.is_empty() {
+        for kind in divergences.iter() {
+            error!("{} differs from the phase 1 transcript", kind);
+        }
+        process::exit(1);
+    }
+
+    info!("The parameters were derived from the phase 1 transcript");
+    Ok(())
+}
+
+/// Checks that a challenge was derived from the prepared phase 1 transcript, like
+/// `verify-linkage`, and that no one contributed to it yet. The queries also depend on the
+/// circuit, which this binary doesn't know of, so they are checked by
+/// `MPCParameters::verify_new_challenge` instead.
+fn verify_new_challenge<E: PairingEngine>(opt: &VerifyNewChallengeOpts) -> Result<()> {
+    let reader = File::open(&opt.challenge_fname).expect("unable to open the challenge file");
+    let parameters = MPCParameters::<E>::read(reader)?;
+
+    let mut divergences = phase1_divergences(&parameters, &opt.phase1_fname, opt.phase1_size, opt.compressed)?;
+    divergences.extend(parameters.new_challenge_divergences());
+    if !divergences.is_empty() {
+        for kind in divergences.iter() {
+            error!(
+                "{} differs from a new challenge derived from the phase 1 transcript",
+                kind
+            );
+        }
+        process::exit(1);
+    }
+
+    info!("The challenge is a new one derived from the phase 1 transcript");
+    Ok(())
+}
+
+/// Checks that the contributions to the parameters are exactly the published public keys,
+/// which confirms the set of contributors rather than only that some valid chain exists
+fn verify_contributors<E: PairingEngine>(opt: &VerifyContributorsOpts) -> Result<()> {
+    let reader = File::open(&opt.params_fname).expect("unable to open the parameters file");
+    let parameters = MPCParameters::<E>::read(reader)?;
+
+    let public_keys = std::fs::read_to_string(&opt.public_keys_fname)
+        .expect("unable to read the public keys file")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let bytes = hex::decode(line).expect("the public key is not valid hex");
+            PublicKey::<E>::read(&mut &bytes[..])
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let hashes = parameters.verify_contributors(&public_keys)?;
+    info!(
+        "The parameters were contributed to by exactly the {} published public keys",
+        hashes.len()
+    );
+    for (i, hash) in hashes.iter().enumerate() {
+        info!("Hash of contribution {}:", i);
+        print_hash(hash);
+    }
+
+    Ok(())
+}
+
+/// Verifies the combined parameters before and after a combination against the initial
+/// parameters, and checks that the combination added exactly the expected number of
+/// contributions. The hashes of the contributions only one of them has are printed.
+fn contribution_delta<E: PairingEngine>(opt: &ContributionDeltaOpts) -> Result<()> {
+    let read = |fname: &str| -> Result<MPCParameters<E>> {
+        let reader = File::open(fname).expect("unable to open the parameters file");
+        MPCParameters::<E>::read(reader)
+    };
+    let initial = read(&opt.initial_fname)?;
+    let previous = read(&opt.previous_fname)?;
+    let next = read(&opt.next_fname)?;
+
+    let delta = initial.contribution_delta(&previous, &next)?;
+    info!(
+        "{} has {} verified contributions and {} has {}",
+        opt.previous_fname,
+        delta.previous.len(),
+        opt.next_fname,
+        delta.next.len()
+    );
+    for hash in delta.only_in_previous().iter() {
+        info!("Contribution only in {}:", opt.previous_fname);
+        print_hash(hash);
+    }
+    for hash in delta.only_in_next().iter() {
+        info!("Contribution only in {}:", opt.next_fname);
+        print_hash(hash);
+    }
+
+    if delta.count_difference() != opt.expected_difference {
+        error!(
+            "The combination added {} contributions, but {} were expected",
+            delta.count_difference(),
+            opt.expected_difference
+        );
+        process::exit(1);
+    }
+
+    info!(
+        "The combination added exactly {} contributions",
+        opt.expected_difference
+    );
+    Ok(())
+}
+
+/// Recomputes the final hash of published combined parameters, which is the hash of the
+/// whole file, and checks that it matches the hash announced for the ceremony
+fn verify_final(opt: &VerifyFinalOpts) -> Result<()> {
+    let expected_hash = hex::decode(&opt.expected_hash).expect("could not hex decode the expected final hash");
+    let contents = std::fs::read(&opt.params_fname).expect("unable to read the parameters file");
+
+    let final_hash = calculate_hash(&contents);
+    info!("FINAL HASH of the combined parameters: {}", hex::encode(&final_hash));
+    print_hash(&final_hash);
+
+    if final_hash.as_slice() != expected_hash.as_slice() {
+        error!(
+            "The final hash does not match the expected hash {}, this is NOT the authentic combined file!",
+            opt.expected_hash
+        );
+        process::exit(1);
+    }
+
+    info!("The combined parameters match the expected final hash");
+    Ok(())
+}
+
+/// Verifies the contributions made between two versions of the parameters, and that there
+/// are exactly as many as expected if a count was provided, which catches a combination
+/// which silently dropped or duplicated a contribution. A failure exits with the code of
+/// its category, see `VerificationFailure::exit_code`.
+fn verify<E: PairingEngine>(opt: &VerifyOpts) -> Result<()> {
+    let read = |fname: &str| -> Result<MPCParameters<E>> {
+        let reader = File::open(fname).expect("unable to open the parameters file");
+        MPCParameters::<E>::read(reader)
+    };
+    let before = read(&opt.before_fname)?;
+    let after = read(&opt.after_fname)?;
+
+    let result = match opt.expected_count {
+        Some(expected_count) => before.verify_with_expected_count(&after, expected_count),
+        None => before.verify(&after),
+    };
+    // exit with a code telling which check failed, so that a coordinator can triage the rejection
+    let hashes = result.unwrap_or_else(|e| {
+        let failure = VerificationFailure::from_error(&e);
+        error!("Verification failed ({}): {}", failure, e);
+        process::exit(failure.exit_code())
+    });
+    info!("Verified {} contributions", hashes.len());
+    for (i, hash) in hashes.iter().enumerate() {
+        info!("Hash of contribution {}:", i);
+        print_hash(hash);
+    }
+
+    Ok(())
+}
+
+/// Checks that the queries of the parameters hold as many elements as a circuit with the
+/// given number of instance and witness variables needs, without reading the elements, which
+/// catches a truncated file or parameters built for another circuit early
+fn check_query<E: PairingEngine>(opt: &CheckQueryOpts) -> Result<()> {
+    let reader = File::open(&opt.params_fname).expect("unable to open the parameters file");
+    let actual = query_lengths::<E, _>(reader)?;
+    let expected = QueryLengths::expected(opt.num_instance_variables, opt.num_witness_variables, opt.phase2_size);
+
+    if actual != expected {
+        error!(
+            "The queries of the parameters hold {:?} elements, but the circuit needs {:?}",
+            actual, expected
+        );
+        process::exit(1);
+    }
+
+    info!(
+        "The queries of the parameters hold the {:?} elements the circuit needs",
+        expected
+    );
+    Ok(())
+}
+
+fn execute_cmd<E: PairingEngine>(opts: Phase2Opts) -> Result<()> {
+    let command = opts.command.unwrap_or_else(|| {
+        error!("No command was provided.");
+        error!("{}", Phase2Opts::usage());
+        process::exit(2)
+    });
+
+    let now = Instant::now();
+    match &command {
+        Command::VkHash(opt) => vk_hash::<E>(opt)?,
+        Command::VerifyLinkage(opt) => verify_linkage::<E>(opt)?,
+        Command::VerifyNewChallenge(opt) => verify_new_challenge::<E>(opt)?,
+        Command::VerifyContributors(opt) => verify_contributors::<E>(opt)?,
+        Command::ContributionDelta(opt) => contribution_delta::<E>(opt)?,
+        Command::VerifyFinal(opt) => verify_final(opt)?,
+        Command::Verify(opt) => verify::<E>(opt)?,
+        Command::CheckQuery(opt) => check_query::<E>(opt)?,
+    }
+
+    let new_now = Instant::now();
+    info!("Executing {:?} took: {:?}", command, new_now.duration_since(now));
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    Subscriber::builder()
+        .with_timer(ChronoUtc::rfc3339())
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let opts = Phase2Opts::parse_args_default_or_exit();
+
+    match opts.curve_kind {
+        CurveKind::Bls12_377 => execute_cmd::<Bls12_377>(opts),
+        CurveKind::BW6 => execute_cmd::<BW6_761>(opts),
+    }
+}
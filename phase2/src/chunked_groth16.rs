@@ -7,7 +7,7 @@ use crate::{
     keypair::{Keypair, PublicKey},
     parameters::*,
 };
-use setup_utils::{batch_mul, check_same_ratio, merge_pairs, InvariantKind, Phase2Error, Result};
+use setup_utils::{batch_mul, check_same_ratio, merge_pairs, Error, InvariantKind, Phase2Error, Result};
 
 use zexe_algebra::{
     AffineCurve, CanonicalDeserialize, CanonicalSerialize, ConstantSerializedSize, Field, PairingEngine,
@@ -325,12 +325,71 @@ pub fn contribute<E: PairingEngine, R: Rng>(buffer: &mut [u8], rng: &mut R, batc
     Ok(hash)
 }
 
-/// Skips the vector ahead of the cursor.
-fn skip_vec<C: AffineCurve, B: Read + Seek>(mut buffer: B) -> Result<()> {
+/// The number of elements of each query of serialized `MPCParameters`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryLengths {
+    pub gamma_abc_g1: usize,
+    pub a_query: usize,
+    pub b_g1_query: usize,
+    pub b_g2_query: usize,
+    pub h_query: usize,
+    pub l_query: usize,
+}
+
+impl QueryLengths {
+    /// Returns the lengths of the queries of the parameters of a circuit with
+    /// `num_instance_variables` instance and `num_witness_variables` witness variables,
+    /// on a phase 2 of size `phase2_size`
+    pub fn expected(num_instance_variables: usize, num_witness_variables: usize, phase2_size: usize) -> Self {
+        let num_variables = num_instance_variables + num_witness_variables;
+        Self {
+            gamma_abc_g1: num_instance_variables,
+            a_query: num_variables,
+            b_g1_query: num_variables,
+            b_g2_query: num_variables,
+            // the H query has one element less than the phase 2 size
+            h_query: phase2_size - 1,
+            l_query: num_witness_variables,
+        }
+    }
+}
+
+/// Given a buffer which corresponds to the format of `MPCParameters`, this will read the
+/// number of elements of each query without reading the elements themselves. A buffer
+/// which is too short to hold the elements of its queries is rejected.
+pub fn query_lengths<E: PairingEngine, B: Read + Seek>(mut buffer: B) -> Result<QueryLengths> {
+    let vk = VerifyingKey::<E>::deserialize(&mut buffer)?;
+    // skip beta_g1 and delta_g1
+    buffer.seek(SeekFrom::Current(2 * E::G1Affine::SERIALIZED_SIZE as i64))?;
+
+    let lengths = QueryLengths {
+        gamma_abc_g1: vk.gamma_abc_g1.len(),
+        a_query: skip_vec::<E::G1Affine, _>(&mut buffer)?,
+        b_g1_query: skip_vec::<E::G1Affine, _>(&mut buffer)?,
+        b_g2_query: skip_vec::<E::G2Affine, _>(&mut buffer)?,
+        h_query: skip_vec::<E::G1Affine, _>(&mut buffer)?,
+        l_query: skip_vec::<E::G1Affine, _>(&mut buffer)?,
+    };
+
+    // seeking past the end succeeds, so check that the queries were all there
+    let end_of_queries = buffer.seek(SeekFrom::Current(0))? as usize;
+    let len = buffer.seek(SeekFrom::End(0))? as usize;
+    if len < end_of_queries {
+        return Err(Error::InvalidLength {
+            expected: end_of_queries,
+            got: len,
+        });
+    }
+
+    Ok(lengths)
+}
+
+/// Skips the vector ahead of the cursor, and returns its length.
+fn skip_vec<C: AffineCurve, B: Read + Seek>(mut buffer: B) -> Result<usize> {
     let len = u64::deserialize(&mut buffer)? as usize;
     let skip_len = len * C::SERIALIZED_SIZE;
     buffer.seek(SeekFrom::Current(skip_len as i64))?;
-    Ok(())
+    Ok(len)
 }
 
 /// Multiplies a vector of affine elements by `element` in `batch_size` batches
@@ -0,0 +1,169 @@
+//! The options and commands shared by the phase 2 binaries, so that every command is
+//! parsed and dispatched in one place.
+
+use gumdrop::Options;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveKind {
+    Bls12_377,
+    BW6,
+}
+
+pub fn curve_from_str(src: &str) -> Result<CurveKind, String> {
+    let curve = match src.to_lowercase().as_str() {
+        "bls12_377" => CurveKind::Bls12_377,
+        "bw6" => CurveKind::BW6,
+        _ => return Err("unsupported curve".to_string()),
+    };
+    Ok(curve)
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct Phase2Opts {
+    help: bool,
+    #[options(
+        help = "the elliptic curve to use",
+        default = "bls12_377",
+        parse(try_from_str = "curve_from_str")
+    )]
+    pub curve_kind: CurveKind,
+    #[options(command)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Options, Clone)]
+pub enum Command {
+    // this prints the hash of the verifying key, for deployments to commit to.
+    #[options(help = "print the hash of the verifying key of the parameters, compressed and uncompressed")]
+    VkHash(VkHashOpts),
+    // this checks that parameters were built on a phase 1 transcript, which the circuit isn't needed for.
+    #[options(help = "check that the parameters were derived from the prepared phase 1 transcript")]
+    VerifyLinkage(VerifyLinkageOpts),
+    // this checks that a challenge is a new one, before contributing to it.
+    #[options(help = "check that a challenge was derived from the phase 1 transcript and not contributed to yet")]
+    VerifyNewChallenge(VerifyNewChallengeOpts),
+    // this checks that the contributions to the parameters are exactly the published ones.
+    #[options(help = "check that the contributions to the parameters are exactly the published public keys, in order")]
+    VerifyContributors(VerifyContributorsOpts),
+    // this checks that an incremental combination added exactly the expected contributions.
+    #[options(help = "compare the verified contributions of the combined parameters before and after a combination")]
+    ContributionDelta(ContributionDeltaOpts),
+    // this checks that a published combined file is the authentic output of the ceremony.
+    #[options(help = "recompute the final hash of the combined parameters and check it against the announced one")]
+    VerifyFinal(VerifyFinalOpts),
+    // this verifies the contributions made between two versions of the parameters.
+    #[options(help = "verify the contributions made to the parameters, optionally checking how many there are")]
+    Verify(VerifyOpts),
+    // this checks that the queries of the parameters hold as many elements as the circuit needs.
+    #[options(help = "check that the queries of the parameters hold as many elements as the circuit needs")]
+    CheckQuery(CheckQueryOpts),
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VkHashOpts {
+    help: bool,
+    #[options(
+        help = "the phase 2 parameters whose verifying key will be hashed",
+        default = "params"
+    )]
+    pub params_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyLinkageOpts {
+    help: bool,
+    #[options(
+        help = "the prepared phase 1 transcript the parameters should be derived from",
+        default = "phase1"
+    )]
+    pub phase1_fname: String,
+    #[options(help = "the number of powers of tau in the phase 1 transcript")]
+    pub phase1_size: usize,
+    #[options(help = "whether the phase 1 transcript is compressed")]
+    pub compressed: bool,
+    #[options(
+        help = "the phase 2 parameters to check, before or after contributions",
+        default = "params"
+    )]
+    pub params_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyNewChallengeOpts {
+    help: bool,
+    #[options(
+        help = "the prepared phase 1 transcript the challenge should be derived from",
+        default = "phase1"
+    )]
+    pub phase1_fname: String,
+    #[options(help = "the number of powers of tau in the phase 1 transcript")]
+    pub phase1_size: usize,
+    #[options(help = "whether the phase 1 transcript is compressed")]
+    pub compressed: bool,
+    #[options(help = "the new phase 2 challenge to check", default = "challenge")]
+    pub challenge_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyContributorsOpts {
+    help: bool,
+    #[options(help = "the combined phase 2 parameters to check", default = "params")]
+    pub params_fname: String,
+    #[options(
+        help = "the file with the hex encoded public key of each contributor, one per line and in order",
+        default = "public_keys"
+    )]
+    pub public_keys_fname: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct ContributionDeltaOpts {
+    help: bool,
+    #[options(
+        help = "the initial phase 2 parameters, which both are verified against",
+        default = "initial_params"
+    )]
+    pub initial_fname: String,
+    #[options(help = "the combined parameters before the combination")]
+    pub previous_fname: String,
+    #[options(help = "the combined parameters after the combination")]
+    pub next_fname: String,
+    #[options(
+        help = "the number of contributions the combination should have added",
+        default = "1"
+    )]
+    pub expected_difference: isize,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyFinalOpts {
+    help: bool,
+    #[options(help = "the published combined phase 2 parameters", default = "params")]
+    pub params_fname: String,
+    #[options(help = "the hex encoded final hash which was announced for the combined parameters")]
+    pub expected_hash: String,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct VerifyOpts {
+    help: bool,
+    #[options(help = "the phase 2 parameters before the contributions", default = "initial_params")]
+    pub before_fname: String,
+    #[options(help = "the phase 2 parameters after the contributions", default = "params")]
+    pub after_fname: String,
+    #[options(help = "the number of contributions the parameters after the contributions should have")]
+    pub expected_count: Option<usize>,
+}
+
+#[derive(Debug, Options, Clone)]
+pub struct CheckQueryOpts {
+    help: bool,
+    #[options(help = "the phase 2 parameters whose queries will be checked", default = "params")]
+    pub params_fname: String,
+    #[options(help = "the number of instance variables of the circuit")]
+    pub num_instance_variables: usize,
+    #[options(help = "the number of witness variables of the circuit")]
+    pub num_witness_variables: usize,
+    #[options(help = "the size of the phase 2, which the phase 1 transcript was prepared for")]
+    pub phase2_size: usize,
+}
@@ -22,6 +22,7 @@ use std::{
     fmt,
     io::{self, Read, Write},
 };
+use tracing::error;
 
 /// MPC parameters are just like Zexe's `Parameters` except, when serialized,
 /// they contain a transcript of contributions at the end, which can be verified.
@@ -273,6 +274,244 @@ impl<E: PairingEngine> MPCParameters<E> {
         verify_transcript(before.cs_hash, &after.contributions)
     }
 
+    /// Same as `verify`, but additionally fails if the number of verified
+    /// contributions is not `expected_count`. This catches combined parameters
+    /// which silently dropped or duplicated a contribution.
+    pub fn verify_with_expected_count(&self, after: &Self, expected_count: usize) -> Result<Vec<[u8; 64]>> {
+        let contributions = self.verify(after)?;
+        ensure_contribution_count(&contributions, expected_count)?;
+        Ok(contributions)
+    }
+
+    /// Checks that the contributions to these parameters are exactly `public_keys`, in order,
+    /// e.g. the keys published by each contributor, and that they form a valid chain of deltas
+    /// ending at the delta of the parameters. Every key which does not fit is logged, and the
+    /// hashes of the contributions are returned, like with `verify`.
+    pub fn verify_contributors(&self, public_keys: &[PublicKey<E>]) -> Result<Vec<[u8; 64]>> {
+        let mut mismatches = 0;
+        for (i, public_key) in public_keys.iter().enumerate() {
+            match self.contributions.get(i) {
+                Some(contribution) if contribution == public_key => {}
+                Some(_) => {
+                    error!("Public key {} does not match contribution {} of the parameters", i, i);
+                    mismatches += 1;
+                }
+                None => {
+                    error!("Public key {} has no matching contribution in the parameters", i);
+                    mismatches += 1;
+                }
+            }
+        }
+        if mismatches > 0 {
+            return Err(Phase2Error::BrokenInvariant(InvariantKind::Contributions).into());
+        }
+        if self.contributions.len() != public_keys.len() {
+            error!(
+                "The parameters have {} contributions, but only {} public keys were provided",
+                self.contributions.len(),
+                public_keys.len()
+            );
+            return Err(Phase2Error::UnexpectedContributionCount {
+                expected: public_keys.len(),
+                got: self.contributions.len(),
+            }
+            .into());
+        }
+
+        // The last delta of the chain must be the one of the parameters
+        if let Some(last) = public_keys.last() {
+            ensure_unchanged(last.delta_after, self.params.delta_g1, InvariantKind::DeltaG1)?;
+            check_same_ratio::<E>(
+                &(E::G1Affine::prime_subgroup_generator(), last.delta_after),
+                &(E::G2Affine::prime_subgroup_generator(), self.params.vk.delta_g2),
+                "Inconsistent G2 Delta",
+            )?;
+        }
+
+        verify_transcript(self.cs_hash, public_keys)
+    }
+
+    /// Verifies `previous` and `next`, e.g. the combined parameters before and after new
+    /// contributions were combined, against these initial parameters, and returns how their
+    /// contributions differ. This catches contributions which were dropped or counted twice
+    /// while combining incrementally.
+    pub fn contribution_delta(&self, previous: &Self, next: &Self) -> Result<ContributionDelta> {
+        Ok(ContributionDelta {
+            previous: self.verify(previous)?,
+            next: self.verify(next)?,
+        })
+    }
+
+    /// Returns the hash of the verifying key, e.g. for deployments to commit to it on-chain.
+    /// The compression of the serialized key changes the hash, so it must match the one the
+    /// commitment is checked with.
+    pub fn verifying_key_hash(&self, compressed: UseCompression) -> Result<[u8; 64]> {
+        let mut sink = HashWriter::new(io::sink());
+        match compressed {
+            UseCompression::Yes => self.params.vk.serialize(&mut sink)?,
+            UseCompression::No => self.params.vk.serialize_uncompressed(&mut sink)?,
+        }
+        let h = sink.into_hash();
+        let mut vk_hash = [0; 64];
+        vk_hash.copy_from_slice(h.as_ref());
+        Ok(vk_hash)
+    }
+
+    /// Returns the elements which these parameters take as is from the phase 1 transcript
+    /// and which differ from it. They are left untouched by contributions, so the check holds
+    /// at any point of the phase 2 ceremony, without the circuit.
+    pub fn phase1_divergences(&self, phase1: &Groth16Params<E>) -> Vec<InvariantKind> {
+        let mut divergences = vec![];
+        if self.params.vk.alpha_g1 != phase1.alpha_g1 {
+            divergences.push(InvariantKind::AlphaG1);
+        }
+        if self.params.beta_g1 != phase1.beta_g1 {
+            divergences.push(InvariantKind::BetaG1);
+        }
+        if self.params.vk.beta_g2 != phase1.beta_g2 {
+            divergences.push(InvariantKind::BetaG2);
+        }
+        divergences
+    }
+
+    /// Returns the elements of these parameters which differ from `initial`, the parameters
+    /// derived from the phase 1 transcript and the circuit before any contribution. These
+    /// are all the elements contributions leave untouched, so an empty result proves these
+    /// parameters were built on the same phase 1 transcript and circuit, while the
+    /// contributions themselves are checked by `verify`.
+    pub fn linkage_divergences(&self, initial: &Self) -> Vec<InvariantKind> {
+        let mut divergences = vec![];
+        if self.cs_hash[..] != initial.cs_hash[..] {
+            divergences.push(InvariantKind::CsHash);
+        }
+        if self.params.vk.alpha_g1 != initial.params.vk.alpha_g1 {
+            divergences.push(InvariantKind::AlphaG1);
+        }
+        if self.params.beta_g1 != initial.params.beta_g1 {
+            divergences.push(InvariantKind::BetaG1);
+        }
+        if self.params.vk.beta_g2 != initial.params.vk.beta_g2 {
+            divergences.push(InvariantKind::BetaG2);
+        }
+        if self.params.vk.gamma_g2 != initial.params.vk.gamma_g2 {
+            divergences.push(InvariantKind::GammaG2);
+        }
+        if self.params.vk.gamma_abc_g1 != initial.params.vk.gamma_abc_g1 {
+            divergences.push(InvariantKind::GammaAbcG1);
+        }
+        if self.params.a_query != initial.params.a_query {
+            divergences.push(InvariantKind::AlphaG1Query);
+        }
+        if self.params.b_g1_query != initial.params.b_g1_query {
+            divergences.push(InvariantKind::BetaG1Query);
+        }
+        if self.params.b_g2_query != initial.params.b_g2_query {
+            divergences.push(InvariantKind::BetaG2Query);
+        }
+        divergences
+    }
+
+    /// Returns the elements which show that these parameters were contributed to, so that a
+    /// new challenge can be checked without the circuit, together with `phase1_divergences`.
+    /// The queries contributions rescale also depend on the circuit, so they are only
+    /// checked by `verify_new_challenge`.
+    pub fn new_challenge_divergences(&self) -> Vec<InvariantKind> {
+        let mut divergences = vec![];
+        if !self.contributions.is_empty() {
+            divergences.push(InvariantKind::Contributions);
+        }
+        if self.params.delta_g1 != E::G1Affine::prime_subgroup_generator()
+            || self.params.vk.delta_g2 != E::G2Affine::prime_subgroup_generator()
+        {
+            divergences.push(InvariantKind::DeltaG1);
+        }
+        divergences
+    }
+
+    /// Re-derives the initial parameters from the phase 1 transcript and the circuit, like
+    /// `new_from_buffer`, and fails with the first diverging element if these parameters
+    /// were not built on them. Every divergence is logged. Returns the derived parameters.
+    #[cfg(not(feature = "wasm"))]
+    pub fn verify_linkage<C>(
+        &self,
+        circuit: C,
+        transcript: &mut [u8],
+        compressed: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        phase1_size: usize,
+        phase2_size: usize,
+    ) -> Result<Self>
+    where
+        C: ConstraintSynthesizer<E::Fr>,
+    {
+        let initial = Self::new_from_buffer(
+            circuit,
+            transcript,
+            compressed,
+            check_input_for_correctness,
+            phase1_size,
+            phase2_size,
+        )?;
+        let divergences = self.linkage_divergences(&initial);
+        for kind in divergences.iter() {
+            error!("{} differs from the parameters derived from phase 1", kind);
+        }
+        match divergences.into_iter().next() {
+            Some(kind) => Err(Phase2Error::BrokenInvariant(kind).into()),
+            None => Ok(initial),
+        }
+    }
+
+    /// Same as `verify_linkage`, except that no contribution may have been made yet, so these
+    /// parameters must serialize to exactly the same bytes as the derived ones. This checks
+    /// that a challenge was generated honestly before contributing to it. Fails with the
+    /// offset of the first differing byte.
+    #[cfg(not(feature = "wasm"))]
+    pub fn verify_new_challenge<C>(
+        &self,
+        circuit: C,
+        transcript: &mut [u8],
+        compressed: UseCompression,
+        check_input_for_correctness: CheckForCorrectness,
+        phase1_size: usize,
+        phase2_size: usize,
+    ) -> Result<()>
+    where
+        C: ConstraintSynthesizer<E::Fr>,
+    {
+        let initial = self.verify_linkage(
+            circuit,
+            transcript,
+            compressed,
+            check_input_for_correctness,
+            phase1_size,
+            phase2_size,
+        )?;
+        let mut challenge = vec![];
+        self.write(&mut challenge)?;
+        let mut expected = vec![];
+        initial.write(&mut expected)?;
+
+        match challenge.iter().zip(&expected).position(|(a, b)| a != b) {
+            Some(offset) => {
+                error!(
+                    "The challenge has {:02x} at byte {}, but the parameters derived from phase 1 have {:02x}",
+                    challenge[offset], offset, expected[offset]
+                );
+                Err(Phase2Error::ChallengeDivergence(offset).into())
+            }
+            None if challenge.len() != expected.len() => {
+                error!(
+                    "The challenge is {} bytes long, but the parameters derived from phase 1 are {} bytes long",
+                    challenge.len(),
+                    expected.len()
+                );
+                Err(Phase2Error::ChallengeDivergence(std::cmp::min(challenge.len(), expected.len())).into())
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Serialize these parameters. The serialized parameters
     /// can be read by Zexe's Groth16 `Parameters`.
     pub fn write<W: Write>(&self, mut writer: W) -> Result<()> {
@@ -300,6 +539,117 @@ impl<E: PairingEngine> MPCParameters<E> {
     }
 }
 
+/// The hashes of the verified contributions of two parameters, as returned by
+/// `MPCParameters::contribution_delta`
+#[derive(Clone)]
+pub struct ContributionDelta {
+    /// The hashes of the contributions to the previous parameters
+    pub previous: Vec<[u8; 64]>,
+    /// The hashes of the contributions to the next parameters
+    pub next: Vec<[u8; 64]>,
+}
+
+impl ContributionDelta {
+    /// The number of contributions the next parameters have more than the previous ones
+    pub fn count_difference(&self) -> isize {
+        self.next.len() as isize - self.previous.len() as isize
+    }
+
+    /// The hashes of the contributions to the previous parameters which the next ones lack
+    pub fn only_in_previous(&self) -> Vec<[u8; 64]> {
+        self.previous
+            .iter()
+            .filter(|hash| !contains_contribution(&self.next, hash))
+            .cloned()
+            .collect()
+    }
+
+    /// The hashes of the contributions to the next parameters which the previous ones lack
+    pub fn only_in_next(&self) -> Vec<[u8; 64]> {
+        self.next
+            .iter()
+            .filter(|hash| !contains_contribution(&self.previous, hash))
+            .cloned()
+            .collect()
+    }
+}
+
+/// The category of a failed verification, so that a coordinator can triage
+/// rejected contributions without parsing error messages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerificationFailure {
+    /// There were no contributions to verify
+    NoContributions,
+    /// Delta in G1 or G2 is not consistent with the latest contribution
+    Delta,
+    /// The H or L queries were not updated correctly, or the A/B queries changed
+    Query,
+    /// A parameter which must never change was modified
+    Invariant,
+    /// The transcript of contributions or a signature of knowledge is invalid
+    Transcript,
+    /// The number of verified contributions was not the expected one
+    ContributionCount,
+    /// Any other error, e.g. IO or deserialization
+    Other,
+}
+
+impl VerificationFailure {
+    /// Categorizes an error returned by `MPCParameters::verify`
+    pub fn from_error(err: &Error) -> Self {
+        match err {
+            Error::Phase2Error(Phase2Error::NoContributions) => VerificationFailure::NoContributions,
+            Error::Phase2Error(Phase2Error::InvalidLength) => VerificationFailure::Query,
+            Error::Phase2Error(Phase2Error::InvalidTranscript) => VerificationFailure::Transcript,
+            Error::Phase2Error(Phase2Error::UnexpectedContributionCount { .. }) => {
+                VerificationFailure::ContributionCount
+            }
+            Error::Phase2Error(Phase2Error::BrokenInvariant(kind)) => match kind {
+                InvariantKind::DeltaG1 => VerificationFailure::Delta,
+                InvariantKind::AlphaG1Query | InvariantKind::BetaG1Query | InvariantKind::BetaG2Query => {
+                    VerificationFailure::Query
+                }
+                InvariantKind::Transcript | InvariantKind::Contributions => VerificationFailure::Transcript,
+                _ => VerificationFailure::Invariant,
+            },
+            Error::VerificationError(VerificationError::InvalidRatio(context)) => match *context {
+                "Inconsistent G2 Delta" | "Inconsistent G1 Delta" => VerificationFailure::Delta,
+                "H_query ratio check failed" | "L_query ratio check failed" => VerificationFailure::Query,
+                "Incorrect signature of knowledge" => VerificationFailure::Transcript,
+                _ => VerificationFailure::Other,
+            },
+            _ => VerificationFailure::Other,
+        }
+    }
+
+    /// A distinct process exit code for each category
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            VerificationFailure::NoContributions => 10,
+            VerificationFailure::Delta => 11,
+            VerificationFailure::Query => 12,
+            VerificationFailure::Invariant => 13,
+            VerificationFailure::Transcript => 14,
+            VerificationFailure::ContributionCount => 15,
+            VerificationFailure::Other => 1,
+        }
+    }
+}
+
+impl fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerificationFailure::NoContributions => write!(f, "no contributions"),
+            VerificationFailure::Delta => write!(f, "inconsistent delta"),
+            VerificationFailure::Query => write!(f, "invalid query update"),
+            VerificationFailure::Invariant => write!(f, "broken invariant"),
+            VerificationFailure::Transcript => write!(f, "invalid transcript"),
+            VerificationFailure::ContributionCount => write!(f, "unexpected contribution count"),
+            VerificationFailure::Other => write!(f, "other error"),
+        }
+    }
+}
+
 /// This is a cheap helper utility that exists purely
 /// because Rust still doesn't have type-level integers
 /// and so doesn't implement `PartialEq` for `[T; 64]`
@@ -313,6 +663,18 @@ pub fn contains_contribution(contributions: &[[u8; 64]], my_contribution: &[u8;
     false
 }
 
+/// Checks that exactly `expected` contributions were verified
+pub fn ensure_contribution_count(contributions: &[[u8; 64]], expected: usize) -> Result<()> {
+    if contributions.len() != expected {
+        return Err(Phase2Error::UnexpectedContributionCount {
+            expected,
+            got: contributions.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 // Helpers for invariant checking
 pub fn ensure_same_length<T, U>(a: &[T], b: &[U]) -> Result<()> {
     if a.len() != b.len() {
@@ -406,7 +768,7 @@ pub fn circuit_to_qap<Zexe: PairingEngine, C: ConstraintSynthesizer<Zexe::Fr>>(
 mod tests {
     use super::*;
     use crate::{
-        chunked_groth16::{contribute, verify},
+        chunked_groth16::{contribute, query_lengths, verify, QueryLengths},
         helpers::testing::TestCircuit,
     };
     use phase1::{helpers::testing::setup_verify, Phase1, Phase1Parameters, ProvingSystem};
@@ -433,6 +795,126 @@ mod tests {
         assert_eq!(deserialized, mpc)
     }
 
+    #[test]
+    fn verifying_key_hash() {
+        verifying_key_hash_curve::<Bls12_377>()
+    }
+
+    fn verifying_key_hash_curve<E: PairingEngine>() {
+        let rng = &mut thread_rng();
+        let mpc = generate_ceremony::<E>();
+
+        let mut serialized = vec![];
+        mpc.params.vk.serialize(&mut serialized).unwrap();
+        let hash = mpc.verifying_key_hash(UseCompression::Yes).unwrap();
+        assert_eq!(&hash[..], calculate_hash(&serialized).as_slice());
+
+        let mut serialized = vec![];
+        mpc.params.vk.serialize_uncompressed(&mut serialized).unwrap();
+        let uncompressed_hash = mpc.verifying_key_hash(UseCompression::No).unwrap();
+        assert_eq!(&uncompressed_hash[..], calculate_hash(&serialized).as_slice());
+        assert_ne!(&hash[..], &uncompressed_hash[..]);
+
+        // contributing changes delta, and so the verifying key
+        let mut contribution = mpc.clone();
+        contribution.contribute(rng).unwrap();
+        assert_ne!(
+            &contribution.verifying_key_hash(UseCompression::Yes).unwrap()[..],
+            &hash[..]
+        );
+    }
+
+    #[test]
+    fn linkage_divergences() {
+        linkage_divergences_curve::<Bls12_377>()
+    }
+
+    fn linkage_divergences_curve<E: PairingEngine>() {
+        let rng = &mut thread_rng();
+        let mpc = generate_ceremony::<E>();
+        assert!(mpc.linkage_divergences(&mpc).is_empty());
+
+        // contributions leave the linkage untouched
+        let mut contribution = mpc.clone();
+        contribution.contribute(rng).unwrap();
+        contribution.contribute(rng).unwrap();
+        assert!(contribution.linkage_divergences(&mpc).is_empty());
+
+        // parameters built on another phase 1 transcript diverge
+        let other = generate_ceremony::<E>();
+        let divergences = contribution.linkage_divergences(&other);
+        assert!(divergences.contains(&InvariantKind::CsHash));
+        assert!(divergences.contains(&InvariantKind::AlphaG1));
+        assert!(divergences.contains(&InvariantKind::BetaG2));
+        assert!(divergences.contains(&InvariantKind::AlphaG1Query));
+        assert!(!divergences.contains(&InvariantKind::GammaG2));
+    }
+
+    #[test]
+    fn verify_contributors() {
+        verify_contributors_curve::<Bls12_377>()
+    }
+
+    fn verify_contributors_curve<E: PairingEngine>() {
+        let rng = &mut thread_rng();
+        let mpc = generate_ceremony::<E>();
+        let mut contribution = mpc.clone();
+        let hashes = vec![
+            contribution.contribute(rng).unwrap(),
+            contribution.contribute(rng).unwrap(),
+        ];
+        let public_keys = contribution.contributions.clone();
+
+        // [u8; 64] does not implement debug, so we can't use `assert_eq`
+        assert!(contribution.verify_contributors(&public_keys).unwrap() == hashes);
+
+        // a missing, reordered or foreign key does not fit the chain
+        assert!(contribution.verify_contributors(&public_keys[..1]).is_err());
+        let reordered = vec![public_keys[1].clone(), public_keys[0].clone()];
+        assert!(contribution.verify_contributors(&reordered).is_err());
+        let mut other = mpc.clone();
+        other.contribute(rng).unwrap();
+        let foreign = vec![public_keys[0].clone(), other.contributions[0].clone()];
+        assert!(contribution.verify_contributors(&foreign).is_err());
+    }
+
+    #[test]
+    fn contribution_delta() {
+        contribution_delta_curve::<Bls12_377>()
+    }
+
+    fn contribution_delta_curve<E: PairingEngine>() {
+        let rng = &mut thread_rng();
+        let mpc = generate_ceremony::<E>();
+        let mut previous = mpc.clone();
+        let first = previous.contribute(rng).unwrap();
+        let mut next = previous.clone();
+        let second = next.contribute(rng).unwrap();
+
+        // [u8; 64] does not implement debug, so we can't use `assert_eq`
+        let delta = mpc.contribution_delta(&previous, &next).unwrap();
+        assert_eq!(delta.count_difference(), 1);
+        assert!(delta.only_in_previous().is_empty());
+        assert!(delta.only_in_next() == vec![second]);
+
+        // a combination which dropped a contribution
+        let delta = mpc.contribution_delta(&next, &previous).unwrap();
+        assert_eq!(delta.count_difference(), -1);
+        assert!(delta.only_in_previous() == vec![second]);
+        assert!(delta.only_in_next().is_empty());
+
+        // a combination which replaced a contribution
+        let mut other = mpc.clone();
+        let foreign = other.contribute(rng).unwrap();
+        let delta = mpc.contribution_delta(&previous, &other).unwrap();
+        assert_eq!(delta.count_difference(), 0);
+        assert!(delta.only_in_previous() == vec![first]);
+        assert!(delta.only_in_next() == vec![foreign]);
+
+        // both parameters must be verified contributions to the initial ones
+        assert!(mpc.contribution_delta(&mpc, &next).is_err());
+    }
+
     #[test]
     fn verify_with_self_fails() {
         verify_with_self_fails_curve::<Bls12_377>()
@@ -447,6 +929,10 @@ mod tests {
         // debug, meaning we cannot call `assert` on it
         if let Err(e) = err {
             assert_eq!(e.to_string(), "Phase 2 Error: There were no contributions found");
+            assert_eq!(
+                VerificationFailure::from_error(&e),
+                VerificationFailure::NoContributions
+            );
         } else {
             panic!("Verifying with self must fail")
         }
@@ -516,6 +1002,72 @@ mod tests {
         mpc.verify(&contribution3).unwrap();
         contribution1.verify(&contribution3).unwrap();
         contribution2.verify(&contribution3).unwrap();
+
+        // the number of contributions can be enforced
+        mpc.verify_with_expected_count(&contribution3, 3).unwrap();
+        if let Err(e) = mpc.verify_with_expected_count(&contribution3, 2) {
+            assert_eq!(
+                e.to_string(),
+                "Phase 2 Error: Expected 2 verified contributions, but found 3"
+            );
+            assert_eq!(
+                VerificationFailure::from_error(&e),
+                VerificationFailure::ContributionCount
+            );
+        } else {
+            panic!("Verifying with the wrong count must fail")
+        }
+    }
+
+    #[test]
+    fn categorize_verification_failures() {
+        let delta: Error = VerificationError::InvalidRatio("Inconsistent G2 Delta").into();
+        assert_eq!(VerificationFailure::from_error(&delta), VerificationFailure::Delta);
+        let query: Error = VerificationError::InvalidRatio("L_query ratio check failed").into();
+        assert_eq!(VerificationFailure::from_error(&query), VerificationFailure::Query);
+        let invariant: Error = Phase2Error::BrokenInvariant(InvariantKind::CsHash).into();
+        assert_eq!(
+            VerificationFailure::from_error(&invariant),
+            VerificationFailure::Invariant
+        );
+        let transcript: Error = Phase2Error::BrokenInvariant(InvariantKind::Transcript).into();
+        assert_eq!(
+            VerificationFailure::from_error(&transcript),
+            VerificationFailure::Transcript
+        );
+        assert_ne!(
+            VerificationFailure::Delta.exit_code(),
+            VerificationFailure::Query.exit_code()
+        );
+    }
+
+    #[test]
+    fn query_lengths_match_the_circuit() {
+        query_lengths_match_the_circuit_curve::<Bls12_377>()
+    }
+
+    fn query_lengths_match_the_circuit_curve<E: PairingEngine>() {
+        let mpc = generate_ceremony::<E>();
+        let mut serialized = vec![];
+        mpc.write(&mut serialized).unwrap();
+
+        let counter = ConstraintSystem::new_ref();
+        counter.set_mode(SynthesisMode::Setup);
+        TestCircuit::<E>(None).generate_constraints(counter.clone()).unwrap();
+        let expected = QueryLengths::expected(counter.num_instance_variables(), counter.num_witness_variables(), 7);
+        assert_eq!(
+            query_lengths::<E, _>(std::io::Cursor::new(&serialized)).unwrap(),
+            expected
+        );
+        assert_eq!(expected.l_query, mpc.params.l_query.len());
+
+        // another circuit needs other lengths
+        let other = QueryLengths::expected(counter.num_instance_variables(), counter.num_witness_variables() + 1, 7);
+        assert_ne!(query_lengths::<E, _>(std::io::Cursor::new(&serialized)).unwrap(), other);
+
+        // a truncated file is rejected
+        let truncated = &serialized[..serialized.len() / 2];
+        assert!(query_lengths::<E, _>(std::io::Cursor::new(truncated)).is_err());
     }
 
     // helper which generates the initial phase 2 params
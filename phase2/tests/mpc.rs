@@ -5,7 +5,7 @@ use phase1::{
 };
 use phase2::{helpers::testing::TestCircuit, parameters::MPCParameters};
 use rand::{thread_rng, Rng};
-use setup_utils::{BatchExpMode, Groth16Params, UseCompression};
+use setup_utils::{BatchExpMode, Groth16Params, InvariantKind, UseCompression};
 use zexe_algebra::{Bls12_377, Bls12_381, PairingEngine, PrimeField, BW6_761};
 use zexe_groth16::{create_random_proof, prepare_verifying_key, verify_proof, Parameters};
 use zexe_r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
@@ -107,3 +107,126 @@ fn groth_test_curve<E: PairingEngine>() {
     let res = verify_proof(&pvk, &proof, &[<E::Fr as PrimeField>::BigInt::from(25).into()]);
     assert!(res.is_ok());
 }
+
+#[test]
+fn test_prepared_phase1_round_trip() {
+    type E = Bls12_377;
+    let powers = 4;
+    let params = Phase1Parameters::<E>::new_full(ProvingSystem::Groth16, powers, 4);
+    let compressed = UseCompression::Yes;
+    let (_, output, _, _) = setup_verify(
+        compressed,
+        CheckForCorrectness::Full,
+        compressed,
+        BatchExpMode::Auto,
+        &params,
+    );
+    let accumulator = Phase1::deserialize(&output, compressed, CheckForCorrectness::Full, &params).unwrap();
+
+    // the phase 1 input of phase 2 may be written either way by the prepare-phase1 command
+    let phase1_size = 1 << powers;
+    let groth_params = Groth16Params::<E>::new(
+        phase1_size,
+        accumulator.tau_powers_g1,
+        accumulator.tau_powers_g2,
+        accumulator.alpha_tau_powers_g1,
+        accumulator.beta_tau_powers_g1,
+        accumulator.beta_g2,
+    )
+    .unwrap();
+    for &phase2_compressed in &[UseCompression::No, UseCompression::Yes] {
+        let mut transcript = vec![];
+        groth_params.write(&mut transcript, phase2_compressed).unwrap();
+        assert_eq!(
+            transcript.len(),
+            Groth16Params::<E>::serialized_size(phase1_size, phase2_compressed)
+        );
+
+        let read = Groth16Params::<E>::read(
+            &mut transcript.clone(),
+            phase2_compressed,
+            CheckForCorrectness::Full,
+            phase1_size,
+            phase1_size,
+        )
+        .unwrap();
+        assert!(read == groth_params);
+
+        let mpc = MPCParameters::<E>::new_from_buffer(
+            TestCircuit::<E>(None),
+            transcript.as_mut(),
+            phase2_compressed,
+            CheckForCorrectness::Full,
+            phase1_size,
+            8,
+        )
+        .unwrap();
+        assert!(mpc.phase1_divergences(&groth_params).is_empty());
+    }
+}
+
+#[test]
+fn test_verify_new_challenge() {
+    type E = Bls12_377;
+    let powers = 4;
+    let params = Phase1Parameters::<E>::new_full(ProvingSystem::Groth16, powers, 4);
+    let compressed = UseCompression::Yes;
+    let (_, output, _, _) = setup_verify(
+        compressed,
+        CheckForCorrectness::Full,
+        compressed,
+        BatchExpMode::Auto,
+        &params,
+    );
+    let accumulator = Phase1::deserialize(&output, compressed, CheckForCorrectness::Full, &params).unwrap();
+
+    let phase1_size = 1 << powers;
+    let groth_params = Groth16Params::<E>::new(
+        phase1_size,
+        accumulator.tau_powers_g1,
+        accumulator.tau_powers_g2,
+        accumulator.alpha_tau_powers_g1,
+        accumulator.beta_tau_powers_g1,
+        accumulator.beta_g2,
+    )
+    .unwrap();
+    let mut transcript = vec![];
+    groth_params.write(&mut transcript, compressed).unwrap();
+
+    let mut mpc = MPCParameters::<E>::new_from_buffer(
+        TestCircuit::<E>(None),
+        transcript.clone().as_mut(),
+        compressed,
+        CheckForCorrectness::Full,
+        phase1_size,
+        8,
+    )
+    .unwrap();
+    assert!(mpc.new_challenge_divergences().is_empty());
+    mpc.verify_new_challenge(
+        TestCircuit::<E>(None),
+        transcript.clone().as_mut(),
+        compressed,
+        CheckForCorrectness::Full,
+        phase1_size,
+        8,
+    )
+    .unwrap();
+
+    // a challenge which was already contributed to is not the initial one
+    mpc.contribute(&mut thread_rng()).unwrap();
+    assert_eq!(
+        mpc.new_challenge_divergences(),
+        vec![InvariantKind::Contributions, InvariantKind::DeltaG1]
+    );
+    assert!(mpc
+        .verify_new_challenge(
+            TestCircuit::<E>(None),
+            transcript.as_mut(),
+            compressed,
+            CheckForCorrectness::Full,
+            phase1_size,
+            8,
+        )
+        .is_err());
+}
@@ -58,7 +58,7 @@ fn benchmark_batchexp(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
     let tau = <Bls12_377 as PairingEngine>::Fr::rand(&mut rng);
 
-    for len in (5..12).map(|i| 2u32.pow(i)) {
+    for len in (5..14).map(|i| 2u32.pow(i)) {
         group.throughput(Throughput::Elements(len as u64));
         // generate a vector of bases and exponents
         let mut elements: Vec<G1Affine> = random_point_vec(len as usize, &mut rng);
@@ -67,6 +67,10 @@ fn benchmark_batchexp(c: &mut Criterion) {
         group.bench_with_input("batch_exp", &len, |b, _len| {
             b.iter(|| batch_exp(&mut elements, &powers, None, BatchExpMode::Direct).unwrap())
         });
+        // batches are split across threads by the batch inversion, compare against the direct version
+        group.bench_with_input("batch_exp_batch_inversion", &len, |b, _len| {
+            b.iter(|| batch_exp(&mut elements, &powers, None, BatchExpMode::BatchInversion).unwrap())
+        });
     }
 }
 
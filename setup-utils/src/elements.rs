@@ -16,6 +16,23 @@ impl fmt::Display for UseCompression {
     }
 }
 
+/// The byte order of the coordinates of serialized elements. The canonical format
+/// is little endian, but other ceremony tools may use big endian.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Endianness::Little => write!(f, "Little"),
+            Endianness::Big => write!(f, "Big"),
+        }
+    }
+}
+
 /// Determines if points should be checked to be infinity.
 #[derive(Copy, Clone, PartialEq)]
 pub enum CheckForCorrectness {
@@ -92,3 +109,68 @@ impl fmt::Display for SubgroupCheckMode {
         }
     }
 }
+
+/// Selects which sections of an accumulator have their ratios verified
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SectionMask {
+    pub tau_g1: bool,
+    pub tau_g2: bool,
+    pub alpha_g1: bool,
+    pub beta_g1: bool,
+}
+
+impl SectionMask {
+    /// Selects every section
+    pub fn all() -> Self {
+        Self {
+            tau_g1: true,
+            tau_g2: true,
+            alpha_g1: true,
+            beta_g1: true,
+        }
+    }
+
+    /// Selects no section
+    pub fn none() -> Self {
+        Self {
+            tau_g1: false,
+            tau_g2: false,
+            alpha_g1: false,
+            beta_g1: false,
+        }
+    }
+
+    /// Returns whether the section of the provided element type is selected.
+    /// BetaG2 consists of a single element and is never selected.
+    pub fn contains(&self, element_type: ElementType) -> bool {
+        match element_type {
+            ElementType::TauG1 => self.tau_g1,
+            ElementType::TauG2 => self.tau_g2,
+            ElementType::AlphaG1 => self.alpha_g1,
+            ElementType::BetaG1 => self.beta_g1,
+            ElementType::BetaG2 => false,
+        }
+    }
+}
+
+impl Default for SectionMask {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl fmt::Display for SectionMask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sections = [
+            ElementType::TauG1,
+            ElementType::TauG2,
+            ElementType::AlphaG1,
+            ElementType::BetaG1,
+        ]
+        .iter()
+        .filter(|section| self.contains(**section))
+        .map(|section| section.to_string())
+        .collect::<Vec<_>>();
+        write!(f, "{}", sections.join(","))
+    }
+}
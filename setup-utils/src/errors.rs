@@ -33,6 +33,20 @@ pub enum Error {
     IncorrectSubgroup,
     #[error("Got invalid decompression parameters")]
     InvalidDecompressionParametersError,
+    #[error("Compressed and uncompressed {0} elements differ at index {1}")]
+    CompressionMismatch(ElementType, usize),
+    #[error("Combined {0} elements of chunk {1} do not match the chunk's contribution")]
+    AggregationMismatch(ElementType, usize),
+    #[error("Invalid {field} in file header: expected {expected}, got {got}")]
+    InvalidHeader {
+        field: &'static str,
+        expected: String,
+        got: String,
+    },
+    #[error("Unsupported operation: {0}")]
+    Unsupported(&'static str),
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(&'static str),
 }
 
 impl From<Box<dyn std::any::Any + Send>> for Error {
@@ -51,6 +65,21 @@ pub enum Phase2Error {
     NoContributions,
     #[error("The Transcript was not consistent")]
     InvalidTranscript,
+    #[error("The challenge differs from the parameters derived from phase 1 at byte {0}")]
+    ChallengeDivergence(usize),
+    #[error("Expected {expected} verified contributions, but found {got}")]
+    UnexpectedContributionCount { expected: usize, got: usize },
+    #[error(
+        "The prepared phase 1 transcript is too small: a phase 2 of size {phase2_size} needs a phase 1 \
+         with at least that many powers (got {phase1_size}), which takes {needed} bytes, but only {available} \
+         bytes were provided. Use a phase 1 with more powers or reduce the phase 2 size"
+    )]
+    Phase1TooSmall {
+        phase1_size: usize,
+        phase2_size: usize,
+        needed: usize,
+        available: usize,
+    },
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -89,14 +118,23 @@ impl fmt::Display for InvariantKind {
     }
 }
 
-// todo: make this more detailed so that we can know which
-// exact pairing ratio check failed
 #[derive(Debug, Error)]
 pub enum VerificationError {
     #[error("Invalid ratio! Context: {0}")]
     /// The ratio check via the pairing of the provided elements failed
     InvalidRatio(&'static str),
+    #[error("Invalid ratio for {section} elements in range [{start}, {end})! Context: {context}")]
+    /// The ratio check via the pairing of a batch of elements failed
+    InvalidRatioInRange {
+        context: &'static str,
+        section: ElementType,
+        start: usize,
+        end: usize,
+    },
     #[error("Invalid generator for {0} powers")]
     /// The first power of Tau was not the generator of that group
     InvalidGenerator(ElementType),
+    #[error("Invalid public key: {0} is zero or not in the prime order subgroup")]
+    /// A point of the contribution's public key was malformed
+    InvalidPublicKey(&'static str),
 }
@@ -1,6 +1,6 @@
 /// Utilities to read/write and convert the Powers of Tau from Phase 1
 /// to Phase 2-compatible Lagrange Coefficients.
-use crate::{buffer_size, CheckForCorrectness, Deserializer, Result, Serializer, UseCompression};
+use crate::{buffer_size, CheckForCorrectness, Deserializer, Phase2Error, Result, Serializer, UseCompression};
 
 use zexe_algebra::{AffineCurve, PairingEngine, PrimeField, ProjectiveCurve};
 use zexe_fft::{
@@ -121,6 +121,11 @@ impl<E: PairingEngine> Groth16Params<E> {
         })??)
     }
 
+    /// The number of bytes `write` produces for the parameters of a Phase 2 of `phase2_size` constraints
+    pub fn serialized_size(phase2_size: usize, compressed: UseCompression) -> usize {
+        transcript_size::<E>(phase2_size, phase2_size, compressed)
+    }
+
     /// Writes the data structure to the provided writer, in compressed or uncompressed form.
     pub fn write<W: Write>(&self, writer: &mut W, compression: UseCompression) -> Result<()> {
         // Write alpha (in g1)
@@ -161,6 +166,9 @@ impl<E: PairingEngine> Groth16Params<E> {
 
     /// Reads the first `num_constraints` coefficients from the provided processed
     /// Phase 1 transcript with size `phase1_size`.
+    ///
+    /// Fails with `Phase2Error::Phase1TooSmall` if `num_constraints` exceeds `phase1_size`
+    /// or if the transcript is shorter than a prepared Phase 1 of `phase1_size` powers.
     pub fn read(
         reader: &mut [u8],
         compressed: UseCompression,
@@ -171,6 +179,17 @@ impl<E: PairingEngine> Groth16Params<E> {
         let span = info_span!("Groth16Utils_read");
         let _enter = span.enter();
 
+        let needed = transcript_size::<E>(std::cmp::max(phase1_size, num_constraints), num_constraints, compressed);
+        if num_constraints > phase1_size || reader.len() < needed {
+            return Err(Phase2Error::Phase1TooSmall {
+                phase1_size,
+                phase2_size: num_constraints,
+                needed,
+                available: reader.len(),
+            }
+            .into());
+        }
+
         let mut reader = std::io::Cursor::new(reader);
         let alpha_g1 = reader.read_element(compressed, check_input_for_correctness)?;
         let beta_g1 = reader.read_element(compressed, check_input_for_correctness)?;
@@ -230,6 +249,17 @@ type SplitBuf<'a> = (&'a [u8], &'a [u8], &'a [u8], &'a [u8], &'a [u8]);
 
 use crate::BatchDeserializer;
 
+/// The number of bytes which must be read from a prepared Phase 1 transcript with
+/// `phase1_size` powers in order to extract the coefficients for a Phase 2 of `size`
+fn transcript_size<E: PairingEngine>(phase1_size: usize, size: usize, compressed: UseCompression) -> usize {
+    let g1_size = buffer_size::<E::G1Affine>(compressed);
+    let g2_size = buffer_size::<E::G2Affine>(compressed);
+
+    // alpha, beta in G1 and beta in G2, followed by 3 G1 and 1 G2 vectors of phase1_size
+    // elements, and the first size - 1 elements of the H query
+    2 * g1_size + g2_size + (3 * g1_size + g2_size) * phase1_size + g1_size * size.saturating_sub(1)
+}
+
 /// splits the transcript from phase 1 after it's been prepared and converted to coefficient form
 fn split_transcript<E: PairingEngine>(
     input: &[u8],
@@ -262,7 +292,7 @@ fn split_transcript<E: PairingEngine>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::UseCompression;
+    use crate::{Error, UseCompression};
     use phase1::{
         helpers::testing::{
             setup_verify, BatchExpMode as BatchExpModePhase1, CheckForCorrectness as CheckForCorrectnessPhase1,
@@ -319,6 +349,10 @@ mod tests {
 
         let mut writer = vec![];
         groth_params.write(&mut writer, compressed).unwrap();
+        assert_eq!(
+            writer.len(),
+            Groth16Params::<E>::serialized_size(prepared_phase1_size, compressed)
+        );
         let mut reader = std::io::Cursor::new(writer);
         let deserialized = Groth16Params::<E>::read(
             &mut reader.get_mut(),
@@ -370,6 +404,43 @@ mod tests {
         read_write_curve::<Bls12_377>(power, prepared_phase1_size, UseCompression::No);
     }
 
+    #[test]
+    fn phase1_too_small() {
+        let compressed = UseCompression::Yes;
+        let phase1_size = 8;
+        let mut transcript = vec![0; transcript_size::<Bls12_377>(phase1_size, phase1_size, compressed)];
+
+        // phase 2 larger than the prepared phase 1
+        match Groth16Params::<Bls12_377>::read(
+            &mut transcript,
+            compressed,
+            CheckForCorrectness::No,
+            phase1_size,
+            phase1_size * 2,
+        ) {
+            Err(Error::Phase2Error(Phase2Error::Phase1TooSmall { phase2_size, .. })) => {
+                assert_eq!(phase2_size, phase1_size * 2)
+            }
+            res => panic!("unexpected result: {:?}", res.err()),
+        }
+
+        // truncated transcript
+        let len = transcript.len() - 1;
+        match Groth16Params::<Bls12_377>::read(
+            &mut transcript[..len],
+            compressed,
+            CheckForCorrectness::No,
+            phase1_size,
+            phase1_size,
+        ) {
+            Err(Error::Phase2Error(Phase2Error::Phase1TooSmall { needed, available, .. })) => {
+                assert_eq!(needed, len + 1);
+                assert_eq!(available, len);
+            }
+            res => panic!("unexpected result: {:?}", res.err()),
+        }
+    }
+
     #[test]
     #[should_panic]
     fn large_phase2_fails() {
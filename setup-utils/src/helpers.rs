@@ -114,8 +114,9 @@ pub fn batch_exp<C: AffineCurve>(
                     s.into_repr()
                 })
                 .collect();
-            let chunked_powers_vec = cfg_chunks_mut!(powers_vec, CPU_CHUNK_SIZE).collect::<Vec<_>>();
-            cfg_chunks_mut!(bases, CPU_CHUNK_SIZE)
+            let chunk_size = batch_inversion_chunk_size(bases.len(), CPU_CHUNK_SIZE);
+            let chunked_powers_vec = cfg_chunks_mut!(powers_vec, chunk_size).collect::<Vec<_>>();
+            cfg_chunks_mut!(bases, chunk_size)
                 .zip(chunked_powers_vec)
                 .for_each(|(chunk_bases, chunk_exps)| {
                     // &mut bases[..].cpu_gpu_scalar_mul(&powers_vec[..], 1 << 5, CPU_CHUNK_SIZE);
@@ -127,6 +128,22 @@ pub fn batch_exp<C: AffineCurve>(
     Ok(())
 }
 
+/// The smallest number of elements given to a parallel task of the batch inversion exponentiation.
+/// Below this, the shared inversion is not amortized well enough to be worth splitting a batch.
+const MIN_PARALLEL_CHUNK_SIZE: usize = 1 << 8;
+
+/// Splits batches smaller than a few times the optimal chunk size across the available threads,
+/// since a single chunk is exponentiated serially. Small batches are never split below the threshold.
+fn batch_inversion_chunk_size(len: usize, cpu_chunk_size: usize) -> usize {
+    #[cfg(feature = "parallel")]
+    let num_threads = rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    let num_threads = 1;
+
+    let per_thread = (len + num_threads - 1) / num_threads;
+    std::cmp::min(cpu_chunk_size, std::cmp::max(MIN_PARALLEL_CHUNK_SIZE, per_thread))
+}
+
 // Create an RNG based on a mixture of system randomness and user provided randomness
 pub fn user_system_randomness() -> Vec<u8> {
     let mut system_rng = OsRng;
@@ -318,6 +335,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_batch_exp_modes_agree() {
+        let rng = &mut thread_rng();
+        // large enough to be split across threads by the batch inversion
+        let len = 3 * MIN_PARALLEL_CHUNK_SIZE + 1;
+        let bases = (0..len).map(|_| G1Affine::prime_subgroup_generator().mul(Fr::rand(rng)).into_affine());
+        let bases = bases.collect::<Vec<_>>();
+        let exps = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let coeff = Fr::rand(rng);
+
+        let mut direct = bases.clone();
+        batch_exp(&mut direct, &exps, Some(&coeff), BatchExpMode::Direct).unwrap();
+        let mut batch_inversion = bases;
+        batch_exp(&mut batch_inversion, &exps, Some(&coeff), BatchExpMode::BatchInversion).unwrap();
+        assert_eq!(direct, batch_inversion);
+    }
+
+    #[test]
+    fn test_batch_inversion_chunk_size() {
+        assert_eq!(batch_inversion_chunk_size(10, 1 << 12), MIN_PARALLEL_CHUNK_SIZE);
+        assert_eq!(batch_inversion_chunk_size(1 << 20, 1 << 12), 1 << 12);
+        let chunk_size = batch_inversion_chunk_size(1 << 12, 1 << 12);
+        assert!(MIN_PARALLEL_CHUNK_SIZE <= chunk_size && chunk_size <= 1 << 12);
+    }
+
     #[test]
     fn test_same_ratio() {
         let rng = &mut thread_rng();
@@ -359,11 +401,41 @@ mod tests {
             &(G2Affine::prime_subgroup_generator(), gx)
         ));
     }
+
+    #[test]
+    fn test_strict_mode_rejects_points_outside_the_subgroup() {
+        use crate::{Deserializer, Serializer, UseCompression};
+        use zexe_algebra::FpParameters;
+
+        // Points on the curve are almost never in the prime order subgroup
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let point = loop {
+            let bytes: Vec<u8> = (0..G1Affine::SERIALIZED_SIZE).map(|_| rng.gen()).collect();
+            if let Some(p) = G1Affine::from_random_bytes(&bytes) {
+                if !p.mul(<<Fr as PrimeField>::Params as FpParameters>::MODULUS).is_zero() {
+                    break p;
+                }
+            }
+        };
+        let mut buffer = vec![];
+        buffer.write_element(&point, UseCompression::No).unwrap();
+
+        let read = |check| (&buffer[..]).read_element::<G1Affine>(UseCompression::No, check);
+        for default in &[CheckForCorrectness::No, CheckForCorrectness::OnlyNonZero] {
+            assert!(read(strict_correctness_check_config(*default, false)).is_ok());
+            assert!(read(strict_correctness_check_config(*default, true)).is_err());
+        }
+    }
 }
 
 pub fn merge_pairs<G: AffineCurve>(v1: &[G], v2: &[G]) -> (G, G) {
+    merge_pairs_with_rng(v1, v2, &mut thread_rng())
+}
+
+/// Same as `merge_pairs`, except that the random linear combination is drawn from `rng`,
+/// so that it can be reproduced
+pub fn merge_pairs_with_rng<G: AffineCurve, R: Rng>(v1: &[G], v2: &[G], rng: &mut R) -> (G, G) {
     assert_eq!(v1.len(), v2.len());
-    let rng = &mut thread_rng();
 
     let randomness: Vec<<G::ScalarField as PrimeField>::BigInt> =
         (0..v1.len()).map(|_| G::ScalarField::rand(rng).into_repr()).collect();
@@ -380,6 +452,11 @@ pub fn power_pairs<G: AffineCurve>(v: &[G]) -> (G, G) {
     merge_pairs(&v[0..(v.len() - 1)], &v[1..])
 }
 
+/// Same as `power_pairs`, except that the random linear combination is drawn from `rng`
+pub fn power_pairs_with_rng<G: AffineCurve, R: Rng>(v: &[G], rng: &mut R) -> (G, G) {
+    merge_pairs_with_rng(&v[0..(v.len() - 1)], &v[1..], rng)
+}
+
 /// Compute BLAKE2b("")
 pub fn blank_hash() -> GenericArray<u8, U64> {
     Blake2b::new().result()
@@ -553,3 +630,15 @@ pub fn upgrade_correctness_check_config(
         (_, _) => check_correctness,
     }
 }
+
+/// Fully checks every element in strict mode, regardless of `check_correctness`, so that
+/// no untrusted bytes skip validation. The subgroup check dominates the cost of reading
+/// elements, so this makes reading several times slower, but it should be used whenever
+/// the inputs were not produced or verified locally.
+pub fn strict_correctness_check_config(check_correctness: CheckForCorrectness, strict: bool) -> CheckForCorrectness {
+    if strict {
+        CheckForCorrectness::Full
+    } else {
+        check_correctness
+    }
+}
@@ -5,7 +5,7 @@ pub use read::{BatchDeserializer, Deserializer};
 mod write;
 pub use write::{BatchSerializer, Serializer};
 
-use crate::UseCompression;
+use crate::{Endianness, Error, Result, UseCompression};
 use zexe_algebra::AffineCurve;
 
 pub fn buffer_size<C: AffineCurve>(compression: UseCompression) -> usize {
@@ -16,6 +16,25 @@ pub fn buffer_size<C: AffineCurve>(compression: UseCompression) -> usize {
     }
 }
 
+/// Converts serialized elements in place between the canonical little endian byte order and
+/// `endianness`, by reversing the bytes of each coordinate of `coordinate_size` bytes. This also
+/// moves the flags from the most significant bits of the last byte of a coordinate to those of
+/// its first byte. The conversion is its own inverse.
+pub fn convert_endianness(buffer: &mut [u8], coordinate_size: usize, endianness: Endianness) -> Result<()> {
+    if buffer.len() % coordinate_size != 0 {
+        return Err(Error::InvalidLength {
+            expected: buffer.len() - buffer.len() % coordinate_size,
+            got: buffer.len(),
+        });
+    }
+    if endianness == Endianness::Big {
+        buffer
+            .chunks_exact_mut(coordinate_size)
+            .for_each(|coordinate| coordinate.reverse());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,6 +77,40 @@ mod tests {
         read_write_batch_element_preallocated::<G2Affine>(UseCompression::Yes);
     }
 
+    #[test]
+    fn convert_endianness_roundtrip() {
+        // a compressed G1 element consists of a single coordinate
+        let coordinate_size = buffer_size::<G1Affine>(UseCompression::Yes);
+        convert_endianness_roundtrip_element::<G1Affine>(UseCompression::No, coordinate_size);
+        convert_endianness_roundtrip_element::<G1Affine>(UseCompression::Yes, coordinate_size);
+        convert_endianness_roundtrip_element::<G2Affine>(UseCompression::No, coordinate_size);
+        convert_endianness_roundtrip_element::<G2Affine>(UseCompression::Yes, coordinate_size);
+
+        let mut buf = vec![0; coordinate_size + 1];
+        assert!(convert_endianness(&mut buf, coordinate_size, Endianness::Big).is_err());
+    }
+
+    fn convert_endianness_roundtrip_element<E: AffineCurve>(compression: UseCompression, coordinate_size: usize) {
+        let num_els = 10;
+        let mut rng = thread_rng();
+        let elements: Vec<E> = random_point_vec(num_els, &mut rng);
+        let mut buf = vec![0; buffer_size::<E>(compression) * num_els];
+        buf.write_batch(&elements, compression).unwrap();
+        let little_endian = buf.clone();
+
+        // little endian is the canonical order
+        convert_endianness(&mut buf, coordinate_size, Endianness::Little).unwrap();
+        assert_eq!(buf, little_endian);
+
+        convert_endianness(&mut buf, coordinate_size, Endianness::Big).unwrap();
+        assert_ne!(buf, little_endian);
+        assert_eq!(buf[0], little_endian[coordinate_size - 1]);
+
+        convert_endianness(&mut buf, coordinate_size, Endianness::Big).unwrap();
+        let deserialized: Vec<E> = buf.read_batch(compression, CheckForCorrectness::No).unwrap();
+        assert_eq!(elements, deserialized);
+    }
+
     fn read_write_single_element<E: AffineCurve>(compression: UseCompression) {
         // uncompressed buffers are twice the size
         let el = E::prime_subgroup_generator();
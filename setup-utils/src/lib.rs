@@ -12,18 +12,20 @@ mod groth16_utils;
 pub use groth16_utils::Groth16Params;
 
 mod elements;
-pub use elements::{BatchExpMode, CheckForCorrectness, ElementType, SubgroupCheckMode, UseCompression};
+pub use elements::{
+    BatchExpMode, CheckForCorrectness, ElementType, Endianness, SectionMask, SubgroupCheckMode, UseCompression,
+};
 
 mod helpers;
 pub use helpers::*;
 
 mod io;
-pub use io::{buffer_size, BatchDeserializer, BatchSerializer, Deserializer, Serializer};
+pub use io::{buffer_size, convert_endianness, BatchDeserializer, BatchSerializer, Deserializer, Serializer};
 
 pub mod rayon_cfg;
 
 mod seed;
-pub use seed::derive_rng_from_seed;
+pub use seed::{derive_entropy_from_seed, derive_rng_from_seed, rng_from_entropy};
 
 // Re-exports for handling hashes
 pub use blake2::digest::generic_array::GenericArray;
@@ -5,10 +5,20 @@ use rand_chacha::ChaChaRng;
 pub const SEED_PERSONALIZATION: &[u8] = b"CELOSEED";
 
 pub fn derive_rng_from_seed(seed: &[u8]) -> impl Rng {
+    rng_from_entropy(derive_entropy_from_seed(seed))
+}
+
+/// Hashes the seed into the 32 bytes of entropy `derive_rng_from_seed` seeds its RNG with
+pub fn derive_entropy_from_seed(seed: &[u8]) -> [u8; 32] {
     let seed_hash = Params::new()
         .personal(SEED_PERSONALIZATION)
         .to_state()
         .update(seed)
         .finalize();
-    ChaChaRng::from_seed(*seed_hash.as_array())
+    *seed_hash.as_array()
+}
+
+/// Returns the RNG seeded with the 32 bytes of entropy as-is, which must be high-entropy
+pub fn rng_from_entropy(entropy: [u8; 32]) -> impl Rng {
+    ChaChaRng::from_seed(entropy)
 }